@@ -389,6 +389,56 @@ fn bench_buffer_processing(c: &mut Criterion) {
     group.finish();
 }
 
+/// Create a 16-VCO patch: VCOs → Mixer → VCF → VCA → Output
+fn create_vco_bank_patch(sample_rate: f64) -> Patch {
+    let mut patch = Patch::new(sample_rate);
+
+    let mix = patch.add("mix", Mixer::new(16));
+    let vcf = patch.add("vcf", Svf::new(sample_rate));
+    let vca = patch.add("vca", Vca::new());
+    let output = patch.add("output", StereoOutput::new());
+
+    for i in 0..16 {
+        let vco = patch.add(format!("vco{}", i), Vco::new(sample_rate));
+        patch
+            .connect(vco.out("saw"), mix.in_(&format!("ch{}", i)))
+            .unwrap();
+    }
+    patch.connect(mix.out("out"), vcf.in_("in")).unwrap();
+    patch.connect(vcf.out("lp"), vca.in_("in")).unwrap();
+    patch.connect(vca.out("out"), output.in_("left")).unwrap();
+    patch.connect(vca.out("out"), output.in_("right")).unwrap();
+
+    patch.set_output(output.id());
+    patch.compile().unwrap();
+    patch
+}
+
+/// Per-sample processing against `Patch::set_block_size` on a 16-VCO patch
+fn bench_block_processing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("block_processing");
+    let buffer_size = 512;
+    group.throughput(Throughput::Elements(buffer_size as u64));
+
+    for block_size in [1, 64] {
+        group.bench_with_input(
+            BenchmarkId::new("16_vcos", format!("block{}", block_size)),
+            &block_size,
+            |b, &block_size| {
+                let mut patch = create_vco_bank_patch(44100.0);
+                patch.set_block_size(block_size);
+                b.iter(|| {
+                    for _ in 0..buffer_size {
+                        black_box(patch.tick());
+                    }
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
 fn bench_buffer_processing_complex(c: &mut Criterion) {
     let mut group = c.benchmark_group("buffer_processing_complex");
 
@@ -1207,6 +1257,7 @@ criterion_group!(
     buffer_benches,
     bench_buffer_processing,
     bench_buffer_processing_complex,
+    bench_block_processing,
 );
 
 criterion_group!(
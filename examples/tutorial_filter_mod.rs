@@ -84,4 +84,15 @@ fn main() {
     println!("  - tri: linear ramp up and down");
     println!("  - saw: slow rise, fast drop");
     println!("  - sqr: instant toggle between states");
+
+    // Bounce a few LFO cycles to disk to open in an audio editor
+    let path = std::env::temp_dir().join("quiver_filter_mod.wav");
+    patch.reset();
+    let options = RenderOptions::default()
+        .with_normalize(true)
+        .with_fade_out(0.5);
+    match render_to_wav_with(&mut patch, &path, 4.0, &options) {
+        Ok(frames) => println!("\nRendered {} frames to {}", frames, path.display()),
+        Err(e) => println!("\nRender failed: {}", e),
+    }
 }
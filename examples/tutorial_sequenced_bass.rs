@@ -15,7 +15,7 @@ fn main() {
     let clock = patch.add("clock", Clock::new(sample_rate));
 
     // Step sequencer - stores our bassline pattern
-    let seq = patch.add("seq", StepSequencer::new());
+    let seq = patch.add("seq", StepSequencer::new(8));
 
     // Bass voice: VCO → VCF → VCA
     let vco = patch.add("vco", Vco::new(sample_rate));
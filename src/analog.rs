@@ -3,8 +3,11 @@
 //! This module provides primitives for modeling analog circuit behavior:
 //! saturation, soft clipping, component variation, thermal drift, and noise.
 
-use crate::port::{GraphModule, PortDef, PortSpec, PortValues, SignalKind};
-use crate::rng;
+use crate::port::{
+    GraphModule, PortDef, PortSpec, PortValues, RuntimeReader, RuntimeWriter, SignalKind,
+};
+use crate::rng::{self, Rng};
+use alloc::string::String;
 use alloc::vec;
 use core::f64::consts::TAU;
 use libm::Libm;
@@ -338,17 +341,29 @@ pub struct VoctTrackingModel {
 
     /// Drift rate (how fast the tracking wanders)
     drift_rate: f64,
+
+    /// Source of the drift, and the seed it restarts from on reset
+    rng: Rng,
+    seed: u64,
 }
 
 impl VoctTrackingModel {
     /// Create a new tracking model with typical analog characteristics
     pub fn new() -> Self {
+        Self::from_seed(rng::random_u64())
+    }
+
+    /// Create a tracking model whose errors and drift are drawn from `seed`
+    pub fn from_seed(seed: u64) -> Self {
+        let mut rng = Rng::from_seed(seed);
         Self {
-            base_error_cents: rng::random_bipolar() * 5.0, // ±5 cents base
-            octave_error_coef: 1.0 + rng::random() * 2.0,  // 1-3 cents/octave
+            base_error_cents: rng.next_f64_bipolar() * 5.0, // ±5 cents base
+            octave_error_coef: 1.0 + rng.next_f64() * 2.0,  // 1-3 cents/octave
             center_octave: 4.0,
             drift_state: 0.0,
             drift_rate: 0.0001,
+            rng,
+            seed,
         }
     }
 
@@ -360,13 +375,15 @@ impl VoctTrackingModel {
             center_octave: 4.0,
             drift_state: 0.0,
             drift_rate: 0.0,
+            rng: Rng::from_seed(0),
+            seed: 0,
         }
     }
 
     /// Apply tracking error to a V/Oct value, returning the modified V/Oct
     pub fn apply(&mut self, voct: f64, dt: f64) -> f64 {
         // Update drift (slow random walk)
-        self.drift_state += rng::random_bipolar() * self.drift_rate * dt * 1000.0;
+        self.drift_state += self.rng.next_f64_bipolar() * self.drift_rate * dt * 1000.0;
         self.drift_state = self.drift_state.clamp(-10.0, 10.0);
 
         // Calculate octave distance from center
@@ -386,6 +403,7 @@ impl VoctTrackingModel {
     /// Reset the drift state
     pub fn reset(&mut self) {
         self.drift_state = 0.0;
+        self.rng = Rng::from_seed(self.seed);
     }
 }
 
@@ -615,9 +633,46 @@ impl GraphModule for AnalogVco {
         self.hf_rolloff.set_sample_rate(sample_rate);
     }
 
+    fn set_rng(&mut self, mut rng: Rng) {
+        // Redraw this instance's component variation from the stream
+        self.freq_component.instance_offset =
+            rng.next_f64_bipolar() * self.freq_component.tolerance;
+        self.dc_offset = rng.next_f64_bipolar() * 0.01;
+        self.voct_tracking = VoctTrackingModel::from_seed(rng.next_u64());
+    }
+
     fn type_id(&self) -> &'static str {
         "analog_vco"
     }
+
+    fn supports_runtime_state(&self) -> bool {
+        true
+    }
+
+    fn serialize_runtime(&self, state: &mut RuntimeWriter) {
+        state.put_f64(self.phase);
+        state.put_f64(self.last_output);
+        state.put_f64(self.last_sync);
+        state.put_f64(self.sync_ramp);
+        state.put_f64(self.thermal.temperature);
+        state.put_f64(self.voct_tracking.drift_state);
+        state.put_rng(&self.voct_tracking.rng);
+        state.put_u64(self.voct_tracking.seed);
+        state.put_f64(self.hf_rolloff.state);
+    }
+
+    fn deserialize_runtime(&mut self, state: &mut RuntimeReader<'_>) -> Result<(), String> {
+        self.phase = state.f64()?;
+        self.last_output = state.f64()?;
+        self.last_sync = state.f64()?;
+        self.sync_ramp = state.f64()?;
+        self.thermal.temperature = state.f64()?;
+        self.voct_tracking.drift_state = state.f64()?;
+        self.voct_tracking.rng = state.rng()?;
+        self.voct_tracking.seed = state.u64()?;
+        self.hf_rolloff.state = state.f64()?;
+        Ok(())
+    }
 }
 
 /// Saturator module for adding warmth and harmonics
@@ -673,6 +728,10 @@ impl GraphModule for Saturator {
     fn type_id(&self) -> &'static str {
         "saturator"
     }
+
+    fn supports_runtime_state(&self) -> bool {
+        true
+    }
 }
 
 /// Wavefolder module
@@ -724,6 +783,10 @@ impl GraphModule for Wavefolder {
     fn type_id(&self) -> &'static str {
         "wavefolder"
     }
+
+    fn supports_runtime_state(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
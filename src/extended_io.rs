@@ -19,12 +19,22 @@
 //!
 //! The Web Audio interface provides traits and structures for integrating
 //! Quiver with WebAssembly-based audio processing.
+//!
+//! # Offline Rendering
+//!
+//! `render_to_wav` bounces a patch's stereo output to a WAV file.
 
+use crate::graph::{NodeHandle, NodeId, Patch, PatchError, PortRef};
+use crate::introspection::{ParamCurve, ParamInfo};
 use crate::io::AtomicF64;
-use crate::port::{GraphModule, PortDef, PortSpec, PortValues, SignalKind};
+use crate::port::{GraphModule, ParamId, ParamRange, PortDef, PortSpec, PortValues, SignalKind};
+use crate::visual::{LevelMeter, Scope};
 use std::collections::HashMap;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 // ============================================================================
 // OSC Protocol Support
@@ -119,6 +129,106 @@ impl OscMessage {
     pub fn first_f64(&self) -> Option<f64> {
         self.args.first().and_then(|v| v.to_f64())
     }
+
+    /// Encode as an OSC 1.0 packet
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_osc_string(&mut bytes, &self.address);
+
+        let mut tags = String::from(",");
+        for arg in &self.args {
+            tags.push(match arg {
+                OscValue::Int(_) => 'i',
+                OscValue::Float(_) => 'f',
+                OscValue::String(_) => 's',
+                OscValue::Blob(_) => 'b',
+                OscValue::True => 'T',
+                OscValue::False => 'F',
+                OscValue::Nil => 'N',
+                OscValue::Infinitum => 'I',
+                OscValue::Long(_) => 'h',
+                OscValue::Double(_) => 'd',
+            });
+        }
+        write_osc_string(&mut bytes, &tags);
+
+        for arg in &self.args {
+            match arg {
+                OscValue::Int(v) => bytes.extend_from_slice(&v.to_be_bytes()),
+                OscValue::Float(v) => bytes.extend_from_slice(&v.to_be_bytes()),
+                OscValue::String(v) => write_osc_string(&mut bytes, v),
+                OscValue::Blob(v) => {
+                    bytes.extend_from_slice(&(v.len() as i32).to_be_bytes());
+                    bytes.extend_from_slice(v);
+                    pad_to_four(&mut bytes);
+                }
+                OscValue::Long(v) => bytes.extend_from_slice(&v.to_be_bytes()),
+                OscValue::Double(v) => bytes.extend_from_slice(&v.to_be_bytes()),
+                OscValue::True | OscValue::False | OscValue::Nil | OscValue::Infinitum => {}
+            }
+        }
+        bytes
+    }
+}
+
+/// Append a null-terminated OSC string padded to four bytes
+fn write_osc_string(bytes: &mut Vec<u8>, value: &str) {
+    bytes.extend_from_slice(value.as_bytes());
+    bytes.push(0);
+    pad_to_four(bytes);
+}
+
+fn pad_to_four(bytes: &mut Vec<u8>) {
+    while bytes.len() % 4 != 0 {
+        bytes.push(0);
+    }
+}
+
+/// A group of OSC messages sent as one packet
+#[derive(Debug, Clone)]
+pub struct OscBundle {
+    /// NTP time tag; 1 means "immediately"
+    pub time_tag: u64,
+    /// Bundled messages
+    pub messages: Vec<OscMessage>,
+}
+
+impl OscBundle {
+    /// Time tag asking the receiver to act on arrival
+    pub const IMMEDIATELY: u64 = 1;
+
+    /// Create an empty bundle to be handled immediately
+    pub fn new() -> Self {
+        Self {
+            time_tag: Self::IMMEDIATELY,
+            messages: Vec::new(),
+        }
+    }
+
+    /// Add a message
+    pub fn with_message(mut self, msg: OscMessage) -> Self {
+        self.messages.push(msg);
+        self
+    }
+
+    /// Encode as an OSC 1.0 bundle packet
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_osc_string(&mut bytes, "#bundle");
+        bytes.extend_from_slice(&self.time_tag.to_be_bytes());
+        for msg in &self.messages {
+            let element = msg.to_bytes();
+            bytes.extend_from_slice(&(element.len() as i32).to_be_bytes());
+            bytes.extend_from_slice(&element);
+        }
+        bytes
+    }
+}
+
+impl Default for OscBundle {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// OSC address pattern matching
@@ -282,10 +392,97 @@ impl OscBinding {
     }
 }
 
+/// What a patch binding drives
+#[derive(Debug, Clone, Copy)]
+enum OscTarget {
+    /// A module parameter, with the 0-1 argument mapped through the range
+    Param {
+        node: NodeId,
+        param: ParamId,
+        range: ParamRange,
+    },
+    /// An input held high while the argument is true
+    Gate(PortRef),
+}
+
+/// Binding between an OSC address and a node in a patch
+///
+/// `OscReceiver::handle_message` stores the latest value and flags it;
+/// `OscReceiver::apply_to_patch` writes it from the audio thread.
+struct OscPatchBinding {
+    pattern: OscPattern,
+    target: OscTarget,
+    value: AtomicF64,
+    pending: AtomicBool,
+    /// Latched by any "on" message, so a press and release arriving between
+    /// two `apply_to_patch` calls still reach the patch as one gate
+    pressed: AtomicBool,
+}
+
+impl OscPatchBinding {
+    fn new(pattern: &str, target: OscTarget) -> Self {
+        Self {
+            pattern: OscPattern::new(pattern),
+            target,
+            value: AtomicF64::new(0.0),
+            pending: AtomicBool::new(false),
+            pressed: AtomicBool::new(false),
+        }
+    }
+
+    fn apply(&self, msg: &OscMessage) -> bool {
+        if !self.pattern.matches(&msg.address) {
+            return false;
+        }
+        let value = match self.target {
+            OscTarget::Param { range, .. } => msg.first_f64().map(|v| range.apply(v)),
+            OscTarget::Gate(_) => msg.args.first().and_then(|v| v.to_bool()).map(|on| {
+                if on {
+                    self.pressed.store(true, Ordering::Release);
+                    OscReceiver::GATE_HIGH
+                } else {
+                    0.0
+                }
+            }),
+        };
+        match value {
+            Some(value) => {
+                self.value.set(value);
+                self.pending.store(true, Ordering::Release);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn write(&self, patch: &mut Patch) {
+        let pressed = self.pressed.swap(false, Ordering::Acquire);
+        let pending = self.pending.swap(false, Ordering::Acquire);
+        if !pressed && !pending {
+            return;
+        }
+        let mut value = self.value.get();
+        if pressed && value == 0.0 {
+            // Released already: play the press now and the release next time
+            value = OscReceiver::GATE_HIGH;
+            self.pending.store(true, Ordering::Release);
+        }
+        match self.target {
+            OscTarget::Param { node, param, .. } => patch.set_param(node, param, value),
+            OscTarget::Gate(port) => {
+                // A port the patch no longer has is left alone
+                let _ = patch.set_input_value(port, value);
+            }
+        }
+    }
+}
+
 /// OSC receiver that routes messages to bindings
 pub struct OscReceiver {
     /// Registered bindings
     bindings: Vec<OscBinding>,
+    /// Bindings to patch parameters and inputs
+    patch_bindings: Vec<OscPatchBinding>,
     /// Counter for total messages received
     message_count: AtomicU32,
     /// Counter for messages that matched at least one binding
@@ -293,10 +490,14 @@ pub struct OscReceiver {
 }
 
 impl OscReceiver {
+    /// Voltage a `bind_gate` input is held at while on
+    pub const GATE_HIGH: f64 = 5.0;
+
     /// Create a new OSC receiver
     pub fn new() -> Self {
         Self {
             bindings: Vec::new(),
+            patch_bindings: Vec::new(),
             message_count: AtomicU32::new(0),
             matched_count: AtomicU32::new(0),
         }
@@ -321,6 +522,51 @@ impl OscReceiver {
         );
     }
 
+    /// Bind an address to a module parameter
+    ///
+    /// The message's first argument, a 0-1 fader position as TouchOSC
+    /// sends, is mapped through `range` and set on `param` of `node` by
+    /// [`apply_to_patch`](Self::apply_to_patch). The pattern may use the
+    /// `OscPattern` wildcards, so `/fader/*` answers every fader address.
+    pub fn bind_param(
+        &mut self,
+        pattern: &str,
+        node: &NodeHandle,
+        param: ParamId,
+        range: ParamRange,
+    ) {
+        self.patch_bindings.push(OscPatchBinding::new(
+            pattern,
+            OscTarget::Param {
+                node: node.id(),
+                param,
+                range,
+            },
+        ));
+    }
+
+    /// Bind an address to a gate or trigger input
+    ///
+    /// A true or nonzero argument holds `port` at 5V and a false or zero one
+    /// drops it to 0V, like a momentary button. A press released before
+    /// [`apply_to_patch`](Self::apply_to_patch) runs still reaches the
+    /// patch, for one call. The port is driven only while unpatched.
+    pub fn bind_gate(&mut self, pattern: &str, port: PortRef) {
+        self.patch_bindings
+            .push(OscPatchBinding::new(pattern, OscTarget::Gate(port)));
+    }
+
+    /// Write the latest values of the `bind_param` and `bind_gate` bindings
+    ///
+    /// `handle_message` only records them, so messages can arrive on a
+    /// network thread; call this from the audio thread before each tick or
+    /// block. Each binding applies its newest value once.
+    pub fn apply_to_patch(&self, patch: &mut Patch) {
+        for binding in &self.patch_bindings {
+            binding.write(patch);
+        }
+    }
+
     /// Process an OSC message
     /// Returns true if at least one binding matched
     pub fn handle_message(&self, msg: &OscMessage) -> bool {
@@ -331,6 +577,11 @@ impl OscReceiver {
                 handled = true;
             }
         }
+        for binding in &self.patch_bindings {
+            if binding.apply(msg) {
+                handled = true;
+            }
+        }
         if handled {
             self.matched_count.fetch_add(1, Ordering::Relaxed);
         }
@@ -339,7 +590,7 @@ impl OscReceiver {
 
     /// Get the number of bindings
     pub fn binding_count(&self) -> usize {
-        self.bindings.len()
+        self.bindings.len() + self.patch_bindings.len()
     }
 
     /// Get the total number of messages received
@@ -415,6 +666,229 @@ impl GraphModule for OscInput {
     fn type_id(&self) -> &'static str {
         "osc_input"
     }
+
+    fn supports_runtime_state(&self) -> bool {
+        true
+    }
+}
+
+/// Latest values of one outgoing OSC address
+///
+/// The audio thread writes the snapshot with the `write_*` methods, which
+/// neither block nor allocate; `OscSender` reads it from its send loop.
+pub struct OscSource {
+    address: String,
+    values: Vec<AtomicF64>,
+    /// Node output captured by `write_output`
+    port: Option<PortRef>,
+}
+
+impl OscSource {
+    fn new(address: impl Into<String>, len: usize, port: Option<PortRef>) -> Self {
+        Self {
+            address: address.into(),
+            values: (0..len).map(|_| AtomicF64::new(0.0)).collect(),
+            port,
+        }
+    }
+
+    /// OSC address the values are sent to
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Number of float arguments sent
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether the source sends no arguments
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Set one value; out-of-range indices are ignored
+    pub fn set(&self, index: usize, value: f64) {
+        if let Some(slot) = self.values.get(index) {
+            slot.set(value);
+        }
+    }
+
+    /// Get one value
+    pub fn get(&self, index: usize) -> Option<f64> {
+        self.values.get(index).map(|v| v.get())
+    }
+
+    /// Copy `values` into the snapshot, up to its length
+    pub fn set_all(&self, values: &[f64]) {
+        for (slot, &value) in self.values.iter().zip(values) {
+            slot.set(value);
+        }
+    }
+
+    /// Snapshot a level meter as RMS, peak, and peak hold in dB
+    pub fn write_meter(&self, meter: &LevelMeter) {
+        self.set_all(&[meter.rms(), meter.peak(), meter.peak_hold()]);
+    }
+
+    /// Snapshot a scope's last completed trace, if it has one
+    pub fn write_scope(&self, scope: &Scope) {
+        if let Some(trace) = scope.trace() {
+            self.set_all(trace);
+        }
+    }
+
+    /// Snapshot the node output this source was added for
+    pub fn write_output(&self, patch: &Patch) {
+        if let Some(value) = self
+            .port
+            .and_then(|port| patch.get_output_value(port.node, port.port))
+        {
+            self.set(0, value);
+        }
+    }
+
+    fn message(&self) -> OscMessage {
+        let mut msg = OscMessage::new(self.address.clone());
+        msg.args = self
+            .values
+            .iter()
+            .map(|v| OscValue::Float(v.get() as f32))
+            .collect();
+        msg
+    }
+}
+
+/// Streams snapshots of engine values out as OSC bundles
+///
+/// Each source is an address with one or more float arguments. The audio
+/// thread writes sources through their shared handles; a separate loop
+/// calls `poll` or `send`, which packs every source into one bundle at
+/// most `rate_hz` times a second.
+///
+/// ```rust,ignore
+/// let mut sender = OscSender::new(30.0);
+/// let vu = sender.add_meter("/vu/master");
+///
+/// // Audio thread
+/// meter.tick(left);
+/// vu.write_meter(&meter);
+///
+/// // Send loop
+/// let socket = UdpSocket::bind("0.0.0.0:0")?;
+/// loop {
+///     sender.send(&socket, "192.168.1.20:9001")?;
+///     std::thread::sleep(Duration::from_millis(5));
+/// }
+/// ```
+pub struct OscSender {
+    sources: Vec<Arc<OscSource>>,
+    /// Minimum time between bundles
+    interval: Duration,
+    /// When the last bundle went out
+    last_send: Option<Instant>,
+    /// Number of bundles produced
+    bundle_count: u32,
+}
+
+impl OscSender {
+    /// Create a sender emitting at most `rate_hz` bundles a second
+    pub fn new(rate_hz: f64) -> Self {
+        Self {
+            sources: Vec::new(),
+            interval: Self::interval_for(rate_hz),
+            last_send: None,
+            bundle_count: 0,
+        }
+    }
+
+    fn interval_for(rate_hz: f64) -> Duration {
+        Duration::from_secs_f64(1.0 / rate_hz.clamp(0.1, 1000.0))
+    }
+
+    /// Change the send rate
+    pub fn set_rate(&mut self, rate_hz: f64) {
+        self.interval = Self::interval_for(rate_hz);
+    }
+
+    /// Current send rate in Hz
+    pub fn rate(&self) -> f64 {
+        1.0 / self.interval.as_secs_f64()
+    }
+
+    /// Add a source of `len` float arguments
+    pub fn add_source(&mut self, address: impl Into<String>, len: usize) -> Arc<OscSource> {
+        self.push(OscSource::new(address, len, None))
+    }
+
+    /// Add a source for a `LevelMeter`: RMS, peak, and peak hold in dB
+    pub fn add_meter(&mut self, address: impl Into<String>) -> Arc<OscSource> {
+        self.add_source(address, 3)
+    }
+
+    /// Add a source for a `Scope`, sized to its trace
+    pub fn add_scope(&mut self, address: impl Into<String>, scope: &Scope) -> Arc<OscSource> {
+        self.add_source(address, scope.trace_len())
+    }
+
+    /// Add a source for a node output, written by `OscSource::write_output`
+    pub fn add_output(&mut self, address: impl Into<String>, port: PortRef) -> Arc<OscSource> {
+        self.push(OscSource::new(address, 1, Some(port)))
+    }
+
+    fn push(&mut self, source: OscSource) -> Arc<OscSource> {
+        let source = Arc::new(source);
+        self.sources.push(source.clone());
+        source
+    }
+
+    /// Get the number of sources
+    pub fn source_count(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Get the number of bundles produced
+    pub fn bundle_count(&self) -> u32 {
+        self.bundle_count
+    }
+
+    /// Bundle the current value of every source
+    pub fn bundle(&self) -> OscBundle {
+        OscBundle {
+            time_tag: OscBundle::IMMEDIATELY,
+            messages: self.sources.iter().map(|s| s.message()).collect(),
+        }
+    }
+
+    /// Encoded bundle if a send is due at `now`, else `None`
+    pub fn poll(&mut self, now: Instant) -> Option<Vec<u8>> {
+        if self.sources.is_empty() {
+            return None;
+        }
+        if let Some(last) = self.last_send {
+            if now.saturating_duration_since(last) < self.interval {
+                return None;
+            }
+        }
+        self.last_send = Some(now);
+        self.bundle_count = self.bundle_count.wrapping_add(1);
+        Some(self.bundle().to_bytes())
+    }
+
+    /// Send a bundle to `target` if one is due; returns whether one was sent
+    pub fn send(
+        &mut self,
+        socket: &UdpSocket,
+        target: impl ToSocketAddrs,
+    ) -> std::io::Result<bool> {
+        match self.poll(Instant::now()) {
+            Some(packet) => {
+                socket.send_to(&packet, target)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
 }
 
 // ============================================================================
@@ -440,6 +914,8 @@ pub struct PluginParameter {
     pub unit: String,
     /// Number of steps (0 = continuous)
     pub steps: u32,
+    /// Mapping between the host's normalized value and `min..max`
+    pub curve: ParamCurve,
 }
 
 impl PluginParameter {
@@ -454,6 +930,17 @@ impl PluginParameter {
             default,
             unit: String::new(),
             steps: 0,
+            curve: ParamCurve::Linear,
+        }
+    }
+
+    /// Describe a module parameter from its introspection info
+    pub fn from_info(id: u32, info: &ParamInfo) -> Self {
+        let param =
+            Self::new(id, &info.name, info.min, info.max, info.default).with_curve(info.curve);
+        match &info.unit {
+            Some(unit) => param.with_unit(unit),
+            None => param,
         }
     }
 
@@ -475,14 +962,20 @@ impl PluginParameter {
         self
     }
 
+    /// Set the normalization curve
+    pub fn with_curve(mut self, curve: ParamCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
     /// Normalize a value to 0.0-1.0 range
     pub fn normalize(&self, value: f64) -> f64 {
-        (value - self.min) / (self.max - self.min)
+        self.curve.normalize(value, self.min, self.max)
     }
 
     /// Denormalize from 0.0-1.0 to parameter range
     pub fn denormalize(&self, normalized: f64) -> f64 {
-        self.min + normalized * (self.max - self.min)
+        self.curve.apply(normalized, self.min, self.max)
     }
 
     /// Quantize to steps (if discrete)
@@ -602,7 +1095,16 @@ impl PluginInfo {
     }
 }
 
+/// Patch level treated as digital full scale: the ±5V audio convention
+const FULL_SCALE_VOLTS: f64 = 5.0;
+
 /// Plugin wrapper for adapting Quiver patches to plugin formats
+///
+/// A format binding (CLAP, VST3, ...) forwards the host's calls here: give
+/// it a compiled patch with `set_patch`, route host input channels to patch
+/// inputs with `connect_input`, expose node parameters with
+/// `add_node_parameter`, and call `process` with each host buffer. Host
+/// samples at ±1.0 full scale map to ±5V in the patch.
 pub struct PluginWrapper {
     /// Plugin metadata
     pub info: PluginInfo,
@@ -616,6 +1118,14 @@ pub struct PluginWrapper {
     pub sample_rate: f64,
     /// Processing state
     pub is_processing: AtomicBool,
+    /// Wrapped patch
+    patch: Option<Patch>,
+    /// Node parameter each plugin parameter drives, if any
+    param_targets: Vec<Option<(NodeId, ParamId)>>,
+    /// Value last written to each target, to skip unchanged parameters
+    applied: Vec<f64>,
+    /// Patch input each host input channel drives
+    input_ports: Vec<Option<PortRef>>,
 }
 
 impl PluginWrapper {
@@ -628,14 +1138,82 @@ impl PluginWrapper {
             param_values: Vec::new(),
             sample_rate: 44100.0,
             is_processing: AtomicBool::new(false),
+            patch: None,
+            param_targets: Vec::new(),
+            applied: Vec::new(),
+            input_ports: Vec::new(),
         }
     }
 
+    /// Wrap a compiled patch, adopting its sample rate and latency
+    pub fn set_patch(&mut self, patch: Patch) {
+        self.sample_rate = patch.sample_rate();
+        self.info.latency = patch.total_latency() as u32;
+        self.patch = Some(patch);
+    }
+
+    /// Get the wrapped patch
+    pub fn patch(&self) -> Option<&Patch> {
+        self.patch.as_ref()
+    }
+
+    /// Get the wrapped patch mutably
+    pub fn patch_mut(&mut self) -> Option<&mut Patch> {
+        self.patch.as_mut()
+    }
+
+    /// Feed host input `channel` into an unpatched input of the patch
+    pub fn connect_input(&mut self, channel: usize, port: PortRef) -> Result<(), PatchError> {
+        let patch = self.patch.as_mut().ok_or(PatchError::InvalidNode)?;
+        patch.set_input_value(port, 0.0)?;
+        if self.input_ports.len() <= channel {
+            self.input_ports.resize(channel + 1, None);
+        }
+        self.input_ports[channel] = Some(port);
+        Ok(())
+    }
+
     /// Add a parameter
     pub fn add_parameter(&mut self, param: PluginParameter) -> Arc<AtomicF64> {
+        self.push_parameter(param, None)
+    }
+
+    /// Expose a module parameter of the wrapped patch to the host
+    ///
+    /// Range, default, curve, and unit come from the module's introspection
+    /// info, and the value starts at the parameter's current setting. The
+    /// value is written to the node at the start of each `process` call.
+    /// Returns `None` without a patch or if the node has no such parameter.
+    pub fn add_node_parameter(
+        &mut self,
+        node: &NodeHandle,
+        param: ParamId,
+    ) -> Option<Arc<AtomicF64>> {
+        let patch = self.patch.as_ref()?;
+        let (_, _, module) = patch.nodes().find(|(id, _, _)| *id == node.id())?;
+        let def = module.params().iter().find(|def| def.id == param)?;
+        let current = module.get_param(param).unwrap_or(def.default);
+        let info = ParamInfo::from_def(def, current);
+
+        let id = self.parameters.len() as u32;
+        let value = self.push_parameter(
+            PluginParameter::from_info(id, &info),
+            Some((node.id(), param)),
+        );
+        value.set(current);
+        Some(value)
+    }
+
+    fn push_parameter(
+        &mut self,
+        param: PluginParameter,
+        target: Option<(NodeId, ParamId)>,
+    ) -> Arc<AtomicF64> {
         let value = Arc::new(AtomicF64::new(param.default));
         self.param_values.push(value.clone());
         self.parameters.push(param);
+        self.param_targets.push(target);
+        self.applied.push(f64::NAN);
         value
     }
 
@@ -660,9 +1238,91 @@ impl PluginWrapper {
         }
     }
 
-    /// Set sample rate
+    /// Set parameter value by index, clamped to its range
+    pub fn set_parameter(&self, index: usize, value: f64) {
+        if let (Some(param), Some(slot)) =
+            (self.parameters.get(index), self.param_values.get(index))
+        {
+            slot.set(param.quantize(value.clamp(param.min, param.max)));
+        }
+    }
+
+    /// Get parameter value by index, normalized 0-1
+    pub fn get_parameter_normalized(&self, index: usize) -> Option<f64> {
+        let param = self.parameters.get(index)?;
+        self.get_parameter(index).map(|v| param.normalize(v))
+    }
+
+    /// Set sample rate, forwarding it to the wrapped patch
     pub fn set_sample_rate(&mut self, sample_rate: f64) {
         self.sample_rate = sample_rate;
+        if let Some(patch) = &mut self.patch {
+            patch.set_sample_rate(sample_rate);
+            self.info.latency = patch.total_latency() as u32;
+        }
+    }
+
+    /// Render `num_frames` of host audio through the wrapped patch
+    ///
+    /// Connected input channels are held on their patch inputs sample by
+    /// sample. The patch's stereo output goes to output channels 0 and 1,
+    /// or is summed to mono for a single channel; any further channels are
+    /// silenced. Short buffers read as silence and are written only as far
+    /// as they reach. Without a patch the outputs are cleared.
+    pub fn process(
+        &mut self,
+        in_buffers: &[&[f32]],
+        out_buffers: &mut [&mut [f32]],
+        num_frames: usize,
+    ) {
+        let Some(patch) = &mut self.patch else {
+            for out in out_buffers.iter_mut() {
+                out.fill(0.0);
+            }
+            return;
+        };
+
+        for ((target, applied), value) in self
+            .param_targets
+            .iter()
+            .zip(&mut self.applied)
+            .zip(&self.param_values)
+        {
+            let value = value.get();
+            if let Some((node, param)) = *target {
+                if value != *applied {
+                    patch.set_param(node, param, value);
+                    *applied = value;
+                }
+            }
+        }
+
+        for frame in 0..num_frames {
+            for (channel, port) in self.input_ports.iter().enumerate() {
+                if let Some(port) = *port {
+                    let sample = in_buffers
+                        .get(channel)
+                        .and_then(|buffer| buffer.get(frame))
+                        .copied()
+                        .unwrap_or(0.0);
+                    let _ = patch.set_input_value(port, sample as f64 * FULL_SCALE_VOLTS);
+                }
+            }
+
+            let (left, right) = patch.tick();
+            let (left, right) = (left / FULL_SCALE_VOLTS, right / FULL_SCALE_VOLTS);
+            let mono = out_buffers.len() == 1;
+            for (channel, out) in out_buffers.iter_mut().enumerate() {
+                if let Some(sample) = out.get_mut(frame) {
+                    *sample = match channel {
+                        0 if mono => ((left + right) * 0.5) as f32,
+                        0 => left as f32,
+                        1 => right as f32,
+                        _ => 0.0,
+                    };
+                }
+            }
+        }
     }
 
     /// Start processing
@@ -681,10 +1341,20 @@ impl PluginWrapper {
     }
 
     /// Get the latency in samples
+    ///
+    /// Taken from `Patch::total_latency` when a patch is set.
     pub fn latency(&self) -> u32 {
         self.info.latency
     }
 
+    /// Samples the host should keep processing after its input goes silent
+    ///
+    /// The patch's latency, so input still in flight reaches the output.
+    /// Decaying modules such as reverbs ring on past this.
+    pub fn tail_samples(&self) -> u32 {
+        self.info.latency
+    }
+
     /// Set the latency in samples
     pub fn set_latency(&mut self, samples: u32) {
         self.info.latency = samples;
@@ -1377,6 +2047,188 @@ pub fn deinterleave_stereo(input: &[f32], left: &mut [f64], right: &mut [f64]) {
     }
 }
 
+// ============================================================================
+// Offline Rendering
+// ============================================================================
+
+/// Sample format of a rendered WAV file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WavBitDepth {
+    /// 16-bit integer PCM
+    Int16,
+    /// 24-bit integer PCM
+    #[default]
+    Int24,
+    /// 32-bit IEEE float
+    Float32,
+}
+
+impl WavBitDepth {
+    pub fn bits(&self) -> u16 {
+        match self {
+            WavBitDepth::Int16 => 16,
+            WavBitDepth::Int24 => 24,
+            WavBitDepth::Float32 => 32,
+        }
+    }
+}
+
+/// Options for `render_to_wav_with`
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// Sample format of the file
+    pub bit_depth: WavBitDepth,
+    /// Scale the render so its loudest sample reaches full scale
+    pub normalize: bool,
+    /// Extra seconds rendered after the duration, faded linearly to silence
+    pub fade_out_secs: f64,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            bit_depth: WavBitDepth::Int24,
+            normalize: false,
+            fade_out_secs: 0.0,
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn with_bit_depth(mut self, bit_depth: WavBitDepth) -> Self {
+        self.bit_depth = bit_depth;
+        self
+    }
+
+    pub fn with_normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    pub fn with_fade_out(mut self, secs: f64) -> Self {
+        self.fade_out_secs = secs.max(0.0);
+        self
+    }
+}
+
+/// Error from rendering a patch to a file
+#[derive(Debug)]
+pub enum RenderError {
+    /// The patch has no output module set, so there is nothing to render
+    NoOutput,
+    /// Creating or writing the file failed
+    Io(std::io::Error),
+    /// The WAV encoder rejected the format or data
+    Wav(String),
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::NoOutput => write!(f, "patch has no output module set"),
+            RenderError::Io(e) => write!(f, "I/O error: {}", e),
+            RenderError::Wav(msg) => write!(f, "WAV encoding error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RenderError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<hound::Error> for RenderError {
+    fn from(error: hound::Error) -> Self {
+        match error {
+            hound::Error::IoError(e) => RenderError::Io(e),
+            other => RenderError::Wav(other.to_string()),
+        }
+    }
+}
+
+/// Render `duration_secs` of a patch's stereo output to a 24-bit WAV file
+///
+/// See `render_to_wav_with` for other formats, normalization and a fade-out.
+pub fn render_to_wav(
+    patch: &mut Patch,
+    path: impl AsRef<Path>,
+    duration_secs: f64,
+) -> Result<usize, RenderError> {
+    render_to_wav_with(patch, path, duration_secs, &RenderOptions::default())
+}
+
+/// Render a patch's stereo output to a WAV file at the patch's sample rate
+///
+/// Ticks the patch for `duration_secs` plus the fade-out tail and writes
+/// the output with ±5V mapped to full scale; louder samples are clipped
+/// unless `normalize` is set. Returns the number of stereo frames written.
+/// The patch must be compiled and have an output module.
+pub fn render_to_wav_with(
+    patch: &mut Patch,
+    path: impl AsRef<Path>,
+    duration_secs: f64,
+    options: &RenderOptions,
+) -> Result<usize, RenderError> {
+    if patch.output_node().is_none() {
+        return Err(RenderError::NoOutput);
+    }
+
+    let sample_rate = patch.sample_rate();
+    let body = (duration_secs.max(0.0) * sample_rate) as usize;
+    let tail = (options.fade_out_secs.max(0.0) * sample_rate) as usize;
+    let mut frames = Vec::with_capacity(body + tail);
+    for i in 0..body + tail {
+        let (left, right) = patch.tick();
+        let gain = if i < body {
+            1.0
+        } else {
+            1.0 - (i - body + 1) as f64 / tail as f64
+        };
+        frames.push((left * gain, right * gain));
+    }
+
+    let scale = if options.normalize {
+        let peak = frames
+            .iter()
+            .fold(0.0f64, |peak, &(l, r)| peak.max(l.abs()).max(r.abs()));
+        if peak > 0.0 {
+            1.0 / peak
+        } else {
+            1.0
+        }
+    } else {
+        1.0 / FULL_SCALE_VOLTS
+    };
+
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: sample_rate.round() as u32,
+        bits_per_sample: options.bit_depth.bits(),
+        sample_format: match options.bit_depth {
+            WavBitDepth::Float32 => hound::SampleFormat::Float,
+            _ => hound::SampleFormat::Int,
+        },
+    };
+    let mut writer = hound::WavWriter::create(path, spec)?;
+    let max_int = ((1i64 << (options.bit_depth.bits() - 1)) - 1) as f64;
+    for &(left, right) in &frames {
+        for sample in [left, right] {
+            let sample = (sample * scale).clamp(-1.0, 1.0);
+            match options.bit_depth {
+                WavBitDepth::Float32 => writer.write_sample(sample as f32)?,
+                _ => writer.write_sample((sample * max_int).round() as i32)?,
+            }
+        }
+    }
+    writer.finalize()?;
+
+    Ok(frames.len())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1607,6 +2459,144 @@ mod tests {
         assert_eq!(receiver.message_count(), 0);
     }
 
+    #[test]
+    fn test_osc_receiver_bind_param_scales_through_range() {
+        use crate::modules::Svf;
+
+        let mut patch = Patch::new(44100.0);
+        let vcf = patch.add("vcf", Svf::new(44100.0));
+
+        let mut receiver = OscReceiver::new();
+        receiver.bind_param(
+            "/fader/*",
+            &vcf,
+            1,
+            ParamRange::Exponential {
+                min: 20.0,
+                max: 20000.0,
+            },
+        );
+        receiver.bind_param(
+            "/patch/vcf/resonance",
+            &vcf,
+            2,
+            ParamRange::Linear { min: 0.0, max: 1.0 },
+        );
+        assert_eq!(receiver.binding_count(), 2);
+
+        assert!(receiver.handle_message(&OscMessage::new("/fader/cutoff").with_float(0.75)));
+        assert!(receiver.handle_message(&OscMessage::new("/patch/vcf/resonance").with_float(0.25)));
+        assert!(!receiver.handle_message(&OscMessage::new("/patch/vcf/drive").with_float(1.0)));
+        assert_eq!(receiver.matched_count(), 2);
+
+        // Nothing reaches the patch until the audio thread applies it
+        let before = patch.get_param(vcf.id(), 1).unwrap();
+        receiver.apply_to_patch(&mut patch);
+
+        // Three quarters of the way up 20 Hz..20 kHz in octaves
+        let cutoff = patch.get_param(vcf.id(), 1).unwrap();
+        assert!((cutoff - 3556.56).abs() < 0.01, "cutoff {cutoff}");
+        assert!((cutoff - before).abs() > 1.0);
+        let resonance = patch.get_param(vcf.id(), 2).unwrap();
+        assert!((resonance - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_osc_receiver_bind_gate_latches_short_presses() {
+        use crate::modules::Adsr;
+
+        let mut patch = Patch::new(44100.0);
+        let env = patch.add("env", Adsr::new(44100.0));
+        let gate = env.in_("gate");
+
+        let mut receiver = OscReceiver::new();
+        receiver.bind_gate("/pad/[12]", gate);
+
+        receiver.handle_message(&OscMessage::new("/pad/1").with_float(1.0));
+        receiver.apply_to_patch(&mut patch);
+        assert_eq!(patch.input_value(gate), Some(OscReceiver::GATE_HIGH));
+
+        receiver.handle_message(&OscMessage::new("/pad/2").with_float(0.0));
+        receiver.apply_to_patch(&mut patch);
+        assert_eq!(patch.input_value(gate), Some(0.0));
+
+        // Press and release between two applies: high once, then low
+        receiver.handle_message(&OscMessage::new("/pad/1").with_int(1));
+        receiver.handle_message(&OscMessage::new("/pad/1").with_int(0));
+        receiver.apply_to_patch(&mut patch);
+        assert_eq!(patch.input_value(gate), Some(OscReceiver::GATE_HIGH));
+        receiver.apply_to_patch(&mut patch);
+        assert_eq!(patch.input_value(gate), Some(0.0));
+    }
+
+    #[test]
+    fn test_osc_message_encoding() {
+        let bytes = OscMessage::new("/vu").with_float(0.5).to_bytes();
+        let mut expected = b"/vu\0,f\0\0".to_vec();
+        expected.extend_from_slice(&0.5f32.to_be_bytes());
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_osc_sender_bundles_sources() {
+        let mut sender = OscSender::new(30.0);
+        let vu = sender.add_source("/vu/left", 1);
+        let master = sender.add_meter("/vu/master");
+        assert_eq!(sender.source_count(), 2);
+
+        vu.set(0, 0.75);
+        let mut meter = LevelMeter::new(44100.0);
+        for _ in 0..100 {
+            meter.tick(0.5);
+        }
+        master.write_meter(&meter);
+
+        let packet = sender.poll(Instant::now()).unwrap();
+        assert!(packet.starts_with(b"#bundle\0"));
+        assert_eq!(&packet[8..16], &OscBundle::IMMEDIATELY.to_be_bytes());
+
+        // First element: size, then the /vu/left message
+        let first = OscMessage::new("/vu/left").with_float(0.75).to_bytes();
+        assert_eq!(&packet[16..20], &(first.len() as i32).to_be_bytes());
+        assert_eq!(&packet[20..20 + first.len()], first.as_slice());
+
+        let meter_msg = OscMessage::new("/vu/master")
+            .with_float(meter.rms() as f32)
+            .with_float(meter.peak() as f32)
+            .with_float(meter.peak_hold() as f32)
+            .to_bytes();
+        assert_eq!(&packet[24 + first.len()..], meter_msg.as_slice());
+    }
+
+    #[test]
+    fn test_osc_sender_throttles_to_rate() {
+        let mut sender = OscSender::new(10.0);
+        assert!(sender.poll(Instant::now()).is_none());
+
+        let value = sender.add_source("/level", 1);
+        value.set(0, 1.0);
+        let start = Instant::now();
+        assert!(sender.poll(start).is_some());
+        assert!(sender.poll(start + Duration::from_millis(50)).is_none());
+        assert!(sender.poll(start + Duration::from_millis(100)).is_some());
+        assert_eq!(sender.bundle_count(), 2);
+    }
+
+    #[test]
+    fn test_osc_source_writes_node_output() {
+        use crate::modules::Offset;
+
+        let mut patch = Patch::new(44100.0);
+        let offset = patch.add("offset", Offset::new(2.5));
+        patch.compile().unwrap();
+        patch.tick();
+
+        let mut sender = OscSender::new(30.0);
+        let source = sender.add_output("/offset", offset.out("out"));
+        source.write_output(&patch);
+        assert_eq!(source.get(0), Some(2.5));
+    }
+
     #[test]
     fn test_osc_receiver_default() {
         let receiver = OscReceiver::default();
@@ -1785,6 +2775,119 @@ mod tests {
         assert_eq!(wrapper.latency(), 256);
     }
 
+    #[test]
+    fn test_plugin_wrapper_processes_host_audio() {
+        use crate::modules::StereoOutput;
+
+        let mut patch = Patch::new(48000.0);
+        let out = patch.add("out", StereoOutput::new());
+        patch.set_output(out.id());
+        patch.compile().unwrap();
+
+        let info = PluginInfo::effect("com.quiver.thru", "Thru", "Quiver");
+        let mut wrapper = PluginWrapper::new(info, AudioBusConfig::stereo_io());
+        wrapper.set_patch(patch);
+        assert_eq!(wrapper.sample_rate, 48000.0);
+        wrapper.connect_input(0, out.in_("left")).unwrap();
+        wrapper.connect_input(1, out.in_("right")).unwrap();
+
+        let left_in: Vec<f32> = (0..64).map(|i| (i as f32 / 64.0) - 0.5).collect();
+        let right_in = vec![0.25f32; 64];
+        let mut left_out = vec![1.0f32; 64];
+        let mut right_out = vec![1.0f32; 64];
+        wrapper.process(
+            &[&left_in, &right_in],
+            &mut [&mut left_out, &mut right_out],
+            64,
+        );
+
+        assert_eq!(left_out.len(), 64);
+        for i in 0..64 {
+            assert!((left_out[i] - left_in[i]).abs() < 1e-6, "frame {i}");
+            assert!((right_out[i] - 0.25).abs() < 1e-6, "frame {i}");
+        }
+
+        // A single output channel gets the mono sum
+        let mut mono = vec![0.0f32; 32];
+        wrapper.process(&[&right_in, &right_in], &mut [&mut mono], 32);
+        assert!(mono.iter().all(|&s| (s - 0.25).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_plugin_wrapper_synth_and_no_patch() {
+        let info = PluginInfo::synth("com.quiver.test", "Test Synth", "Quiver");
+        let mut wrapper = PluginWrapper::new(info, AudioBusConfig::stereo_out());
+
+        let mut left = vec![1.0f32; 128];
+        let mut right = vec![1.0f32; 128];
+        wrapper.process(&[], &mut [&mut left, &mut right], 128);
+        assert!(left.iter().chain(&right).all(|&s| s == 0.0));
+
+        wrapper.set_patch(render_test_patch(1.0));
+        wrapper.process(&[], &mut [&mut left, &mut right], 128);
+        assert!(left.iter().any(|&s| s.abs() > 0.1));
+        assert!(left.iter().all(|&s| s.abs() <= 1.0));
+    }
+
+    #[test]
+    fn test_plugin_wrapper_bridges_node_parameters() {
+        use crate::modules::{StereoOutput, Svf};
+
+        let mut patch = Patch::new(44100.0);
+        let vcf = patch.add("vcf", Svf::new(44100.0));
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(vcf.out("lp"), out.in_("left")).unwrap();
+        patch.set_output(out.id());
+        patch.compile().unwrap();
+
+        let info = PluginInfo::effect("com.quiver.filter", "Filter", "Quiver");
+        let mut wrapper = PluginWrapper::new(info, AudioBusConfig::stereo_io());
+        assert!(wrapper.add_node_parameter(&vcf, 1).is_none());
+        wrapper.set_patch(patch);
+        assert!(wrapper.add_node_parameter(&vcf, 99).is_none());
+
+        let cutoff = wrapper.add_node_parameter(&vcf, 1).unwrap();
+        let param = &wrapper.parameters[0];
+        assert_eq!(param.name, "Cutoff");
+        assert_eq!(param.unit, "Hz");
+        assert_eq!(param.curve, ParamCurve::Exponential);
+        assert!((param.min - 20.0).abs() < 1e-9 && (param.max - 20000.0).abs() < 1e-9);
+
+        wrapper.set_parameter_normalized(0, 0.75);
+        assert!((cutoff.get() - 3556.56).abs() < 0.01);
+        assert!((wrapper.get_parameter_normalized(0).unwrap() - 0.75).abs() < 1e-9);
+
+        let mut left = vec![0.0f32; 16];
+        let mut right = vec![0.0f32; 16];
+        wrapper.process(&[], &mut [&mut left, &mut right], 16);
+        let applied = wrapper.patch().unwrap().get_param(vcf.id(), 1).unwrap();
+        assert!((applied - 3556.56).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_plugin_wrapper_reports_patch_latency() {
+        use crate::modules::{Distortion, Oversample, StereoOutput};
+
+        let mut patch = Patch::new(44100.0);
+        let drive = patch.add(
+            "drive",
+            Oversample::new(Distortion::new(44100.0), 4, 44100.0),
+        );
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(drive.out("out"), out.in_("left")).unwrap();
+        patch.set_output(out.id());
+        patch.compile().unwrap();
+
+        let info = PluginInfo::effect("com.quiver.drive", "Drive", "Quiver");
+        let mut wrapper = PluginWrapper::new(info, AudioBusConfig::stereo_io());
+        wrapper.set_patch(patch);
+        assert_eq!(
+            wrapper.latency(),
+            Oversample::<Distortion>::LATENCY_SAMPLES as u32
+        );
+        assert_eq!(wrapper.tail_samples(), wrapper.latency());
+    }
+
     #[test]
     fn test_plugin_wrapper_processing_state() {
         let info = PluginInfo::synth("com.quiver.test", "Test Synth", "Quiver");
@@ -1886,16 +2989,16 @@ mod tests {
         // Write directly to left buffer
         {
             let left = processor.left_buffer_mut();
-            for i in 0..128 {
-                left[i] = (i as f64) / 128.0;
+            for (i, sample) in left.iter_mut().enumerate().take(128) {
+                *sample = (i as f64) / 128.0;
             }
         }
 
         // Write directly to right buffer
         {
             let right = processor.right_buffer_mut();
-            for i in 0..128 {
-                right[i] = 1.0 - (i as f64) / 128.0;
+            for (i, sample) in right.iter_mut().enumerate().take(128) {
+                *sample = 1.0 - (i as f64) / 128.0;
             }
         }
 
@@ -1957,4 +3060,85 @@ mod tests {
         assert!((dst[2] - 1.0).abs() < 0.001);
         assert!((dst[3] + 1.0).abs() < 0.001);
     }
+
+    // Offline Rendering Tests
+    /// A 5V sine scaled by `level` into the left output
+    fn render_test_patch(level: f64) -> Patch {
+        use crate::modules::{StereoOutput, Vco};
+
+        let mut patch = Patch::new(8000.0);
+        let vco = patch.add("vco", Vco::new(8000.0));
+        let out = patch.add("out", StereoOutput::new());
+        patch
+            .connect_attenuated(vco.out("sin"), out.in_("left"), level)
+            .unwrap();
+        patch.set_output(out.id());
+        patch.compile().unwrap();
+        patch
+    }
+
+    fn render_test_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("quiver_{}_{}.wav", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_render_to_wav_format_and_fade() {
+        let path = render_test_path("render_fade");
+        let options = RenderOptions::default()
+            .with_bit_depth(WavBitDepth::Int16)
+            .with_fade_out(0.05);
+        let frames = render_to_wav_with(&mut render_test_patch(1.0), &path, 0.1, &options).unwrap();
+        assert_eq!(frames, 1200);
+
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        let spec = reader.spec();
+        assert_eq!(spec.channels, 2);
+        assert_eq!(spec.sample_rate, 8000);
+        assert_eq!(spec.bits_per_sample, 16);
+        let samples: Vec<i16> = reader.samples::<i16>().map(|s| s.unwrap()).collect();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(samples.len(), 2400);
+
+        // A 5V sine fills the 16-bit range
+        let body_peak = samples[..1600].iter().map(|s| s.unsigned_abs()).max();
+        assert!(body_peak.unwrap() > 32000);
+        let tail_peak = samples[2380..].iter().map(|s| s.unsigned_abs()).max();
+        assert!(tail_peak.unwrap() < 1000);
+    }
+
+    #[test]
+    fn test_render_to_wav_normalize_float() {
+        let path = render_test_path("render_normalize");
+        let mut patch = render_test_patch(0.25);
+        let options = RenderOptions::default()
+            .with_bit_depth(WavBitDepth::Float32)
+            .with_normalize(true);
+        render_to_wav_with(&mut patch, &path, 0.1, &options).unwrap();
+
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().sample_format, hound::SampleFormat::Float);
+        let peak = reader
+            .samples::<f32>()
+            .map(|s| s.unwrap().abs())
+            .fold(0.0f32, f32::max);
+        std::fs::remove_file(&path).unwrap();
+        assert!((peak - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_render_to_wav_errors() {
+        let mut patch = Patch::new(8000.0);
+        let path = render_test_path("render_no_output");
+        assert!(matches!(
+            render_to_wav(&mut patch, &path, 0.1),
+            Err(RenderError::NoOutput)
+        ));
+        assert!(!path.exists());
+
+        let missing_dir = render_test_path("missing_dir").join("out.wav");
+        assert!(matches!(
+            render_to_wav(&mut render_test_patch(1.0), missing_dir, 0.1),
+            Err(RenderError::Io(_))
+        ));
+    }
 }
@@ -4,7 +4,14 @@
 //! arbitrary signal routing between modules. It handles topological sorting,
 //! execution ordering, and signal propagation.
 
-use crate::port::{GraphModule, ParamId, PortId, PortSpec, PortValues, SignalKind};
+use crate::modules::DcBlocker;
+use crate::port::{
+    BlockPortValues, GraphModule, MergeMode, ParamId, PortDef, PortId, PortSpec, PortValues,
+    RuntimeReader, RuntimeWriter, SignalKind, SmoothedParam,
+};
+use crate::rng::Rng;
+use crate::simd::ProcessContext;
+use crate::transport::Transport;
 use crate::StdMap;
 use alloc::boxed::Box;
 use alloc::collections::VecDeque;
@@ -12,6 +19,7 @@ use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
+use libm::Libm;
 use serde::{Deserialize, Serialize};
 use slotmap::{DefaultKey, SlotMap};
 
@@ -27,6 +35,21 @@ pub enum ValidationMode {
     Strict,
 }
 
+/// Detection of non-finite (NaN/Inf) values during `Patch::tick`
+///
+/// Off by default since checking costs time on every sample. Detected values
+/// are replaced with 0.0 and reported through `Patch::last_error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanGuard {
+    /// No detection
+    #[default]
+    Off,
+    /// Check only the patch output
+    Output,
+    /// Check every node's outputs, pinpointing the node that produced the value
+    PerNode,
+}
+
 /// Result of signal kind compatibility check
 #[derive(Debug, Clone)]
 pub struct CompatibilityResult {
@@ -152,11 +175,101 @@ pub struct Cable {
     pub offset: Option<f64>,
 }
 
+/// A named sidechain source that modules can subscribe to without a cable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SidechainBus {
+    pub name: String,
+    pub source: PortRef,
+}
+
+/// A node's random stream: the master seed mixed with an FNV-1a hash of its
+/// name, so adding or removing other nodes leaves it unchanged
+fn node_rng(seed: u64, name: &str) -> Rng {
+    let hash = name.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    Rng::from_seed(seed ^ hash)
+}
+
+/// The input an unpatched normalled input copies, following chains of
+/// normals through other unpatched inputs
+fn normalled_source(spec: &PortSpec, unpatched: &[PortId], input: PortId) -> Option<PortId> {
+    let mut source = spec.input_by_id(input)?.normalled_to?;
+    // Bounded so a loop of normals settles on its defaults
+    for _ in 0..spec.inputs.len() {
+        if !unpatched.contains(&source) {
+            break;
+        }
+        source = spec.input_by_id(source)?.normalled_to?;
+    }
+    spec.input_by_id(source).map(|port| port.id)
+}
+
+/// Connections a [`Patch::replace_module`] call could not keep
+#[derive(Debug, Clone, Default)]
+pub struct ReplaceReport {
+    /// Cables to or from a port the new module lacks, or whose signal kinds
+    /// no longer pass validation
+    pub dropped_cables: Vec<Cable>,
+    /// Sidechain buses sourced from, or subscriptions feeding, a missing port
+    pub dropped_sidechains: Vec<String>,
+}
+
+impl ReplaceReport {
+    /// Whether every connection survived
+    pub fn is_empty(&self) -> bool {
+        self.dropped_cables.is_empty() && self.dropped_sidechains.is_empty()
+    }
+}
+
 /// Internal node representation
 struct Node {
     module: Box<dyn GraphModule>,
     name: String,
     position: Option<(f32, f32)>,
+    sync_group: Option<u32>,
+    mute: bool,
+    /// Input and output passed through while bypassed
+    bypass: Option<(PortId, PortId)>,
+}
+
+impl Node {
+    /// Whether the module is skipped by the tick loop
+    fn is_parked(&self) -> bool {
+        self.mute || self.bypass.is_some()
+    }
+
+    /// Whether a parked module carries its dry input on `output`
+    fn passes_dry(&self, output: &PortDef) -> bool {
+        !self.mute
+            && self
+                .bypass
+                .is_some_and(|(_, out)| out == output.id || output.kind == SignalKind::Audio)
+    }
+
+    /// Outputs of a muted or bypassed module for one sample
+    fn tick_parked(&self, inputs: &PortValues, outputs: &mut PortValues) {
+        let dry = self
+            .bypass
+            .and_then(|(input, _)| inputs.get(input))
+            .unwrap_or(0.0);
+        for output in &self.module.port_spec().outputs {
+            let value = if self.passes_dry(output) { dry } else { 0.0 };
+            outputs.set(output.id, value);
+        }
+    }
+
+    /// Outputs of a muted or bypassed module for one block
+    fn tick_parked_block(&self, inputs: &BlockPortValues, outputs: &mut BlockPortValues) {
+        let dry = self.bypass.and_then(|(input, _)| inputs.get_buffer(input));
+        for output in &self.module.port_spec().outputs {
+            let buffer = outputs.get_buffer_mut(output.id);
+            match dry {
+                Some(dry) if self.passes_dry(output) => buffer.copy_from_slice(dry),
+                _ => buffer.fill(0.0),
+            }
+        }
+    }
 }
 
 /// Error types for patch operations
@@ -165,7 +278,9 @@ pub enum PatchError {
     InvalidNode,
     InvalidPort,
     InvalidCable,
-    CycleDetected {
+    /// A feedback loop with no `UnitDelay` (or other module that
+    /// `breaks_feedback`) on it; lists the nodes on the loop
+    UnbrokenFeedbackLoop {
         nodes: Vec<NodeId>,
     },
     CompilationFailed(String),
@@ -175,6 +290,15 @@ pub enum PatchError {
         to_kind: SignalKind,
         message: String,
     },
+    /// A sidechain subscription names a bus that is not registered
+    UnknownSidechain(String),
+    /// A node produced NaN or infinity (reported by the NaN guard)
+    NonFinite {
+        node: NodeId,
+        port: PortId,
+    },
+    /// A runtime state snapshot could not be restored
+    RuntimeState(String),
 }
 
 impl core::fmt::Display for PatchError {
@@ -183,9 +307,11 @@ impl core::fmt::Display for PatchError {
             PatchError::InvalidNode => write!(f, "Invalid node"),
             PatchError::InvalidPort => write!(f, "Invalid port"),
             PatchError::InvalidCable => write!(f, "Invalid cable"),
-            PatchError::CycleDetected { nodes } => {
-                write!(f, "Cycle detected involving {} nodes", nodes.len())
-            }
+            PatchError::UnbrokenFeedbackLoop { nodes } => write!(
+                f,
+                "Feedback loop through {} nodes has no unit delay to break it",
+                nodes.len()
+            ),
             PatchError::CompilationFailed(msg) => write!(f, "Compilation failed: {}", msg),
             PatchError::SignalMismatch {
                 from_kind,
@@ -196,6 +322,11 @@ impl core::fmt::Display for PatchError {
                 "Signal mismatch: {:?} -> {:?}: {}",
                 from_kind, to_kind, message
             ),
+            PatchError::UnknownSidechain(name) => write!(f, "Unknown sidechain bus: {}", name),
+            PatchError::NonFinite { node, port } => {
+                write!(f, "Non-finite value on port {} of node {:?}", port, node)
+            }
+            PatchError::RuntimeState(msg) => write!(f, "Runtime state: {}", msg),
         }
     }
 }
@@ -253,6 +384,146 @@ impl NodeHandle {
     }
 }
 
+/// Maximum internal oversampling factor
+pub const MAX_OVERSAMPLING: usize = 16;
+
+/// Largest block size accepted by `Patch::set_block_size`
+pub const MAX_BLOCK_SIZE: usize = 4096;
+
+/// Polyphase FIR decimator for bringing an oversampled signal back to the base rate
+///
+/// Only one output is computed per `factor` inputs, so the cost is that of a
+/// single polyphase branch set rather than filtering at the oversampled rate.
+#[derive(Debug, Clone)]
+struct Decimator {
+    taps: Vec<f64>,
+    history: Vec<f64>,
+    write_pos: usize,
+}
+
+impl Decimator {
+    /// Filter taps per unit of oversampling
+    const TAPS_PER_FACTOR: usize = 32;
+
+    fn new(factor: usize) -> Self {
+        let len = Self::TAPS_PER_FACTOR * factor + 1;
+        // Cutoff just below the base-rate Nyquist (cycles per oversampled sample)
+        let cutoff = 0.45 / factor as f64;
+        let center = (len - 1) as f64 / 2.0;
+        let mut taps: Vec<f64> = (0..len)
+            .map(|i| {
+                let n = i as f64 - center;
+                let sinc = if n == 0.0 {
+                    2.0 * cutoff
+                } else {
+                    Libm::<f64>::sin(core::f64::consts::TAU * cutoff * n)
+                        / (core::f64::consts::PI * n)
+                };
+                // Blackman window
+                let phase = core::f64::consts::TAU * i as f64 / (len - 1) as f64;
+                let window =
+                    0.42 - 0.5 * Libm::<f64>::cos(phase) + 0.08 * Libm::<f64>::cos(2.0 * phase);
+                sinc * window
+            })
+            .collect();
+        let sum: f64 = taps.iter().sum();
+        for tap in &mut taps {
+            *tap /= sum;
+        }
+
+        Self {
+            history: vec![0.0; len],
+            taps,
+            write_pos: 0,
+        }
+    }
+
+    fn serialize_runtime(&self, state: &mut RuntimeWriter) {
+        state.put_f64s(&self.history);
+        state.put_usize(self.write_pos);
+    }
+
+    fn deserialize_runtime(&mut self, state: &mut RuntimeReader<'_>) -> Result<(), String> {
+        state.f64s_into(&mut self.history)?;
+        let pos = state.usize()?;
+        if pos >= self.history.len() {
+            return Err(String::from("decimator position out of range"));
+        }
+        self.write_pos = pos;
+        Ok(())
+    }
+
+    fn push(&mut self, sample: f64) {
+        self.history[self.write_pos] = sample;
+        self.write_pos = (self.write_pos + 1) % self.history.len();
+    }
+
+    /// Filter the most recent input samples into one base-rate output
+    fn output(&self) -> f64 {
+        let len = self.history.len();
+        self.taps
+            .iter()
+            .enumerate()
+            .map(|(i, tap)| tap * self.history[(self.write_pos + len - 1 - i) % len])
+            .sum()
+    }
+
+    fn reset(&mut self) {
+        self.history.fill(0.0);
+        self.write_pos = 0;
+    }
+}
+
+/// Delay inserted on an output port to line it up with a later parallel path
+///
+/// One line serves every cable from `source` that needs the same delay.
+#[derive(Debug, Clone)]
+struct CompensationDelay {
+    source: PortRef,
+    line: Vec<f64>,
+    pos: usize,
+    /// The source value from `line.len()` samples ago
+    output: f64,
+    /// The delayed source over the current block
+    block: Vec<f64>,
+}
+
+impl CompensationDelay {
+    fn new(source: PortRef, delay: usize) -> Self {
+        Self {
+            source,
+            line: vec![0.0; delay],
+            pos: 0,
+            output: 0.0,
+            block: Vec::new(),
+        }
+    }
+
+    fn delay(&self) -> usize {
+        self.line.len()
+    }
+
+    fn push(&mut self, sample: f64) -> f64 {
+        self.output = self.line[self.pos];
+        self.line[self.pos] = sample;
+        self.pos = (self.pos + 1) % self.line.len();
+        self.output
+    }
+
+    fn reset(&mut self) {
+        self.line.fill(0.0);
+        self.pos = 0;
+        self.output = 0.0;
+        self.block.clear();
+    }
+}
+
+/// Tag at the start of a `Patch::save_runtime_state` blob
+const RUNTIME_STATE_MAGIC: &[u8] = b"QRTS";
+
+/// Format version of `Patch::save_runtime_state` blobs
+pub const RUNTIME_STATE_VERSION: u64 = 7;
+
 /// The main patch graph containing modules and connections
 pub struct Patch {
     nodes: SlotMap<NodeId, Node>,
@@ -264,6 +535,8 @@ pub struct Patch {
 
     // Configuration
     sample_rate: f64,
+    oversampling: usize,
+    decimators: Option<(Decimator, Decimator)>,
 
     // Output node
     output_node: Option<NodeId>,
@@ -271,6 +544,58 @@ pub struct Patch {
     // Validation
     validation_mode: ValidationMode,
     warnings: Vec<String>,
+
+    // Musical transport shared with modules (optional)
+    transport: Option<Transport>,
+
+    // Debug monitoring: muted nodes, soloed node, and the nodes downstream of it
+    muted: Vec<NodeId>,
+    solo: Option<NodeId>,
+    solo_downstream: Vec<NodeId>,
+
+    // Sidechain buses, their subscribers, and the implicit cables resolved at compile
+    sidechain_buses: Vec<SidechainBus>,
+    sidechain_subscriptions: Vec<(String, PortRef)>,
+    sidechain_cables: Vec<Cable>,
+
+    // Non-finite value detection
+    nan_guard: NanGuard,
+    last_error: Option<PatchError>,
+
+    // Block processing: the rendered block being handed out by `tick`, each
+    // output port's samples for the block, and reusable per-node port buffers
+    block_size: usize,
+    block_output: Vec<(f64, f64)>,
+    block_cursor: usize,
+    block_buffers: StdMap<PortRef, Vec<f64>>,
+    block_io: StdMap<NodeId, (BlockPortValues, BlockPortValues)>,
+
+    // Latency compensation resolved at compile: each node's output latency,
+    // the delay line (index into `delay_lines`) feeding a node from a port
+    latencies: StdMap<NodeId, usize>,
+    compensation: StdMap<(PortRef, NodeId), usize>,
+    delay_lines: Vec<CompensationDelay>,
+
+    // DC blockers on the audio outputs of modules that `wants_dc_block`
+    dc_blockers: StdMap<PortRef, DcBlocker>,
+
+    // Cables out of a delay back into its own loop, read before the delay ticks
+    feedback_cables: Vec<(PortRef, PortRef)>,
+
+    // Modules with feedback cables, whose outputs are written ahead of each sample
+    feedback_breakers: Vec<NodeId>,
+
+    // Cables into each node of `execution_order`, as indices into `all_cables`
+    incoming_cables: Vec<Vec<usize>>,
+
+    // Values a `SubPatch` holds on the unconnected inputs it exposes
+    external_inputs: StdMap<PortRef, f64>,
+
+    // Master seed every node's random stream is derived from
+    seed: Option<u64>,
+
+    // Parameter glides the engine runs for modules that don't smooth themselves
+    param_smoothers: Vec<(NodeId, ParamId, SmoothedParam)>,
 }
 
 impl Patch {
@@ -282,9 +607,35 @@ impl Patch {
             execution_order: Vec::new(),
             buffers: StdMap::new(),
             sample_rate,
+            oversampling: 1,
+            decimators: None,
             output_node: None,
             validation_mode: ValidationMode::None,
             warnings: Vec::new(),
+            transport: None,
+            muted: Vec::new(),
+            solo: None,
+            solo_downstream: Vec::new(),
+            sidechain_buses: Vec::new(),
+            sidechain_subscriptions: Vec::new(),
+            sidechain_cables: Vec::new(),
+            nan_guard: NanGuard::Off,
+            last_error: None,
+            block_size: 1,
+            block_output: Vec::new(),
+            block_cursor: 0,
+            block_buffers: StdMap::new(),
+            block_io: StdMap::new(),
+            latencies: StdMap::new(),
+            compensation: StdMap::new(),
+            delay_lines: Vec::new(),
+            dc_blockers: StdMap::new(),
+            feedback_cables: Vec::new(),
+            feedback_breakers: Vec::new(),
+            incoming_cables: Vec::new(),
+            external_inputs: StdMap::new(),
+            seed: None,
+            param_smoothers: Vec::new(),
         }
     }
 
@@ -308,23 +659,310 @@ impl Patch {
         self.warnings.clear();
     }
 
-    /// Get the sample rate
+    /// Get the sample rate of the patch output
     pub fn sample_rate(&self) -> f64 {
         self.sample_rate
     }
 
+    /// Change the output sample rate, re-configuring every module and the transport
+    pub fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+        let internal_rate = self.internal_sample_rate();
+        for (_, node) in &mut self.nodes {
+            node.module.set_sample_rate(internal_rate);
+        }
+        for blocker in self.dc_blockers.values_mut() {
+            blocker.set_sample_rate(internal_rate);
+        }
+        if let Some(transport) = &mut self.transport {
+            transport.set_sample_rate(sample_rate);
+        }
+        self.discard_block();
+    }
+
+    /// Seed every module's random numbers from one master seed
+    ///
+    /// Each node gets its own stream, derived from the seed and the node's
+    /// name, through `GraphModule::set_rng`, so renders with the same seed
+    /// are bit-identical and nodes do not share correlated noise. Modules
+    /// added or replaced later are seeded too. Reseeding rewinds the streams;
+    /// call it before rendering.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+        for (_, node) in &mut self.nodes {
+            node.module.set_rng(node_rng(seed, &node.name));
+        }
+    }
+
+    /// The master seed set by [`set_seed`](Self::set_seed), if any
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Run the whole graph at an integer multiple of the output sample rate
+    ///
+    /// Every module is re-configured with the oversampled rate and the stereo
+    /// output is low-pass filtered and decimated back to the base rate, which
+    /// reduces aliasing from nonlinear modules at the cost of CPU. A factor of
+    /// 1 disables oversampling; factors are clamped to [`MAX_OVERSAMPLING`].
+    ///
+    /// External inputs are read once per internal tick and therefore held
+    /// across the oversampled ticks of each output sample. The transport keeps
+    /// running at the base rate.
+    pub fn set_oversampling(&mut self, factor: usize) {
+        let factor = factor.clamp(1, MAX_OVERSAMPLING);
+        self.oversampling = factor;
+        self.decimators = if factor > 1 {
+            Some((Decimator::new(factor), Decimator::new(factor)))
+        } else {
+            None
+        };
+
+        let internal_rate = self.internal_sample_rate();
+        for (_, node) in &mut self.nodes {
+            node.module.set_sample_rate(internal_rate);
+        }
+        for blocker in self.dc_blockers.values_mut() {
+            blocker.set_sample_rate(internal_rate);
+        }
+    }
+
+    /// Get the oversampling factor (1 when disabled)
+    pub fn oversampling(&self) -> usize {
+        self.oversampling
+    }
+
+    /// Sample rate the modules run at (output rate times the oversampling factor)
+    pub fn internal_sample_rate(&self) -> f64 {
+        self.sample_rate * self.oversampling as f64
+    }
+
+    /// Process the graph in blocks of `size` samples
+    ///
+    /// With a block size above 1, `tick` renders a whole block through each
+    /// module's `GraphModule::tick_block` and then hands it out a sample at a
+    /// time. `Vco`, `Svf`, `Vca`, and `Mixer` process their block in one
+    /// pass; other modules fall back to looping `tick` and produce the same
+    /// output as per-sample processing. Parameter changes, bypass, and
+    /// mute/solo take effect at the next block boundary, and
+    /// `get_output_value` reports the last sample of the rendered block.
+    ///
+    /// A patch with a transport attached or with oversampling enabled keeps
+    /// ticking per sample, since the transport and the decimators advance
+    /// once per sample. Sizes are clamped to 1..=[`MAX_BLOCK_SIZE`]; 1, the
+    /// default, disables block processing. Samples of a partly handed-out
+    /// block are not part of `save_runtime_state`.
+    pub fn set_block_size(&mut self, size: usize) {
+        self.block_size = size.clamp(1, MAX_BLOCK_SIZE);
+        self.block_io.clear();
+        self.discard_block();
+    }
+
+    /// Get the processing block size (1 when processing per sample)
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Build a processing context describing this patch
+    ///
+    /// The context carries both the base rate and the oversampling factor, so
+    /// tempo and absolute-time calculations can use the output rate while DSP
+    /// uses the internal rate.
+    pub fn process_context(&self, block_size: usize) -> ProcessContext {
+        let mut context = ProcessContext::new(self.sample_rate, block_size);
+        context.oversampling = self.oversampling;
+        if let Some(transport) = &self.transport {
+            context.tempo = Some(transport.bpm());
+            context.sample_position = transport.position_samples();
+        }
+        context
+    }
+
+    /// Attach a transport that is advanced once per tick and shared with all modules
+    ///
+    /// The transport's sample rate is set to the patch sample rate.
+    pub fn set_transport(&mut self, mut transport: Transport) {
+        transport.set_sample_rate(self.sample_rate);
+        self.transport = Some(transport);
+        self.discard_block();
+    }
+
+    /// Detach the transport
+    pub fn clear_transport(&mut self) {
+        self.transport = None;
+        self.discard_block();
+    }
+
+    /// Get the current transport state
+    pub fn transport(&self) -> Option<&Transport> {
+        self.transport.as_ref()
+    }
+
+    /// Get mutable access to the transport (play/stop, tempo, position)
+    pub fn transport_mut(&mut self) -> Option<&mut Transport> {
+        self.transport.as_mut()
+    }
+
+    /// Enable or disable detection of NaN/Inf values during `tick`
+    pub fn set_nan_guard(&mut self, guard: NanGuard) {
+        self.nan_guard = guard;
+    }
+
+    pub fn nan_guard(&self) -> NanGuard {
+        self.nan_guard
+    }
+
+    /// The most recent runtime error, such as a non-finite value caught by the NaN guard
+    pub fn last_error(&self) -> Option<&PatchError> {
+        self.last_error.as_ref()
+    }
+
+    pub fn clear_error(&mut self) {
+        self.last_error = None;
+    }
+
+    /// Bypass a node, passing its input straight to its output
+    ///
+    /// The node's `GraphModule::bypass_route` names the audio input and
+    /// output; while bypassed the module is not processed and the input is
+    /// copied to that output and to any other audio outputs (so a mono-in,
+    /// stereo-out reverb stays centred), with the remaining outputs at zero.
+    /// Takes effect on the next tick (or the next block, see
+    /// `set_block_size`) without recompiling, so it can be toggled during
+    /// playback to A/B an effect. The module resumes from the state it was in
+    /// when bypassed.
+    ///
+    /// Fails with `InvalidPort` if the module has no bypass route.
+    pub fn set_bypass(&mut self, node: NodeId, bypass: bool) -> Result<(), PatchError> {
+        let n = self.nodes.get_mut(node).ok_or(PatchError::InvalidNode)?;
+        n.bypass = if bypass {
+            Some(n.module.bypass_route().ok_or(PatchError::InvalidPort)?)
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    /// Whether a node is bypassed
+    pub fn is_bypassed(&self, node: NodeId) -> bool {
+        self.nodes.get(node).is_some_and(|n| n.bypass.is_some())
+    }
+
+    /// Hard-mute a node, stopping its processing and zeroing its outputs
+    ///
+    /// Unlike the debugging `mute_node`, the module is not ticked at all and
+    /// solo does not override it. Hard mute wins over bypass. Takes effect on
+    /// the next tick (or the next block) without recompiling. See "Bypass and Mute" in the
+    /// connect-modules guide for how the two mutes interact with solo.
+    pub fn set_hard_mute(&mut self, node: NodeId, mute: bool) {
+        if let Some(n) = self.nodes.get_mut(node) {
+            n.mute = mute;
+        }
+    }
+
+    /// Alias for `set_hard_mute`
+    pub fn set_mute(&mut self, node: NodeId, mute: bool) {
+        self.set_hard_mute(node, mute);
+    }
+
+    /// Whether a node is hard-muted with `set_hard_mute`
+    pub fn is_hard_muted(&self, node: NodeId) -> bool {
+        self.nodes.get(node).is_some_and(|n| n.mute)
+    }
+
+    /// Mute or unmute a node for debugging
+    ///
+    /// A muted node keeps processing, but downstream modules (and the patch
+    /// output, if it is the output node) see silence on all of its outputs.
+    /// This is independent of `set_hard_mute`; `is_muted` reports only this
+    /// state.
+    pub fn mute_node(&mut self, node: NodeId, muted: bool) {
+        self.muted.retain(|&id| id != node);
+        if muted {
+            self.muted.push(node);
+        }
+    }
+
+    /// Whether a node is muted with `mute_node`
+    pub fn is_muted(&self, node: NodeId) -> bool {
+        self.muted.contains(&node)
+    }
+
+    /// Solo a node's branch for debugging, or clear the solo with `None`
+    ///
+    /// While a node is soloed, audio inputs of the modules downstream of it
+    /// only receive signal originating from the soloed node's branch; other
+    /// audio sources feeding those modules are silenced. Control signals (CV,
+    /// gates) still pass so modulation is unchanged. Solo wins over
+    /// `mute_node`: a soloed node is heard even if it is also muted. A node
+    /// hard-muted with `set_hard_mute` stays silent.
+    pub fn solo_node(&mut self, node: Option<NodeId>) {
+        self.solo = node;
+        self.update_solo_downstream();
+    }
+
+    /// The currently soloed node
+    pub fn soloed_node(&self) -> Option<NodeId> {
+        self.solo
+    }
+
+    fn update_solo_downstream(&mut self) {
+        self.solo_downstream.clear();
+        let Some(solo) = self.solo else {
+            return;
+        };
+
+        let mut queue = VecDeque::new();
+        queue.push_back(solo);
+        while let Some(node) = queue.pop_front() {
+            for cable in self.cables.iter().chain(&self.sidechain_cables) {
+                let next = cable.to.node;
+                if cable.from.node == node && next != solo && !self.solo_downstream.contains(&next)
+                {
+                    self.solo_downstream.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    /// Whether the debug mute/solo state silences a cable
+    fn is_silenced(&self, cable: &Cable, kind: SignalKind) -> bool {
+        let from = cable.from.node;
+        if self.solo != Some(from) && self.muted.contains(&from) {
+            return true;
+        }
+
+        match self.solo {
+            Some(solo) => {
+                let in_branch = from == solo || self.solo_downstream.contains(&from);
+                !in_branch
+                    && self.solo_downstream.contains(&cable.to.node)
+                    && kind == SignalKind::Audio
+            }
+            None => false,
+        }
+    }
+
     /// Add a module to the patch
     pub fn add<M: GraphModule + 'static>(
         &mut self,
         name: impl Into<String>,
         mut module: M,
     ) -> NodeHandle {
-        module.set_sample_rate(self.sample_rate);
+        let name = name.into();
+        module.set_sample_rate(self.internal_sample_rate());
+        if let Some(seed) = self.seed {
+            module.set_rng(node_rng(seed, &name));
+        }
         let spec = module.port_spec().clone();
         let id = self.nodes.insert(Node {
             module: Box::new(module),
-            name: name.into(),
+            name,
             position: None,
+            sync_group: None,
+            mute: false,
+            bypass: None,
         });
         self.invalidate();
         NodeHandle { id, spec }
@@ -336,12 +974,19 @@ impl Patch {
         name: impl Into<String>,
         mut module: Box<dyn GraphModule>,
     ) -> NodeHandle {
-        module.set_sample_rate(self.sample_rate);
+        let name = name.into();
+        module.set_sample_rate(self.internal_sample_rate());
+        if let Some(seed) = self.seed {
+            module.set_rng(node_rng(seed, &name));
+        }
         let spec = module.port_spec().clone();
         let id = self.nodes.insert(Node {
             module,
-            name: name.into(),
+            name,
             position: None,
+            sync_group: None,
+            mute: false,
+            bypass: None,
         });
         self.invalidate();
         NodeHandle { id, spec }
@@ -361,10 +1006,153 @@ impl Patch {
             self.output_node = None;
         }
 
+        self.muted.retain(|&id| id != node);
+        if self.solo == Some(node) {
+            self.solo = None;
+        }
+
+        self.sidechain_buses.retain(|bus| bus.source.node != node);
+        self.sidechain_subscriptions
+            .retain(|(_, to)| to.node != node);
+        self.external_inputs.retain(|port, _| port.node != node);
+
+        self.invalidate();
+        Ok(())
+    }
+
+    /// Swap a node's module for another, keeping the cables that still fit
+    ///
+    /// The node keeps its name, position, sync group, mute, and cables,
+    /// matched by port ID: a cable survives if the new module has the port
+    /// and, under the validation mode, the signal kinds are still
+    /// compatible. Bypass stays on only if the new module has a bypass route.
+    /// The patch is recompiled; the report lists what was dropped.
+    ///
+    /// Existing [`NodeHandle`]s still describe the old ports; fetch a new
+    /// one with [`get_handle_by_name`](Self::get_handle_by_name).
+    pub fn replace_module(
+        &mut self,
+        node: NodeId,
+        mut module: Box<dyn GraphModule>,
+    ) -> Result<ReplaceReport, PatchError> {
+        module.set_sample_rate(self.internal_sample_rate());
+        let n = self.nodes.get_mut(node).ok_or(PatchError::InvalidNode)?;
+        if let Some(seed) = self.seed {
+            module.set_rng(node_rng(seed, &n.name));
+        }
+        n.bypass = n.bypass.and_then(|_| module.bypass_route());
+        n.module = module;
+
+        let mut report = ReplaceReport::default();
+        for cable in core::mem::take(&mut self.cables) {
+            let keep = (cable.from.node != node && cable.to.node != node)
+                || (self.validate_output_port(cable.from).is_ok()
+                    && self.validate_input_port(cable.to).is_ok()
+                    && self
+                        .validate_signal_compatibility(cable.from, cable.to)
+                        .is_ok());
+            if keep {
+                self.cables.push(cable);
+            } else {
+                report.dropped_cables.push(cable);
+            }
+        }
+
+        let mut buses = core::mem::take(&mut self.sidechain_buses);
+        buses.retain(|bus| {
+            let keep = self.validate_output_port(bus.source).is_ok();
+            if !keep {
+                report.dropped_sidechains.push(bus.name.clone());
+            }
+            keep
+        });
+        self.sidechain_buses = buses;
+        let mut subscriptions = core::mem::take(&mut self.sidechain_subscriptions);
+        subscriptions.retain(|(name, to)| {
+            let keep = self.validate_input_port(*to).is_ok();
+            if !keep {
+                report.dropped_sidechains.push(name.clone());
+            }
+            keep
+        });
+        self.sidechain_subscriptions = subscriptions;
+
+        self.buffers.retain(|port_ref, _| port_ref.node != node);
+        self.compile()?;
+        Ok(report)
+    }
+
+    /// Register an output as a named sidechain bus
+    ///
+    /// Modules subscribe with [`subscribe_sidechain`](Self::subscribe_sidechain)
+    /// and receive the source signal as if cabled, like DAW sidechain routing.
+    /// Registering an existing name re-points that bus.
+    pub fn register_sidechain(&mut self, name: &str, source: PortRef) -> Result<(), PatchError> {
+        self.validate_output_port(source)?;
+        self.sidechain_buses.retain(|bus| bus.name != name);
+        self.sidechain_buses.push(SidechainBus {
+            name: name.to_string(),
+            source,
+        });
+        self.invalidate();
+        Ok(())
+    }
+
+    /// Remove a sidechain bus; returns whether it existed
+    pub fn unregister_sidechain(&mut self, name: &str) -> bool {
+        let before = self.sidechain_buses.len();
+        self.sidechain_buses.retain(|bus| bus.name != name);
+        self.invalidate();
+        self.sidechain_buses.len() != before
+    }
+
+    /// Feed a sidechain bus into an input (typically a module's `sidechain` port)
+    ///
+    /// The bus is resolved at [`compile`](Self::compile), which fails with
+    /// [`PatchError::UnknownSidechain`] if no bus of that name is registered.
+    pub fn subscribe_sidechain(&mut self, bus: &str, to: PortRef) -> Result<(), PatchError> {
+        self.validate_input_port(to)?;
+        self.sidechain_subscriptions.push((bus.to_string(), to));
         self.invalidate();
         Ok(())
     }
 
+    /// Registered sidechain buses
+    pub fn sidechain_buses(&self) -> &[SidechainBus] {
+        &self.sidechain_buses
+    }
+
+    fn resolve_sidechains(&mut self) -> Result<(), PatchError> {
+        self.sidechain_cables.clear();
+        for (name, to) in &self.sidechain_subscriptions {
+            let bus = self
+                .sidechain_buses
+                .iter()
+                .find(|bus| &bus.name == name)
+                .ok_or_else(|| PatchError::UnknownSidechain(name.clone()))?;
+            self.sidechain_cables.push(Cable {
+                from: bus.source,
+                to: *to,
+                attenuation: None,
+                offset: None,
+            });
+        }
+        Ok(())
+    }
+
+    /// Explicit cables followed by resolved sidechain subscriptions
+    fn all_cables(&self) -> impl Iterator<Item = &Cable> {
+        self.cables.iter().chain(&self.sidechain_cables)
+    }
+
+    /// Cable `index` of `all_cables`
+    fn cable_at(&self, index: usize) -> &Cable {
+        match self.cables.get(index) {
+            Some(cable) => cable,
+            None => &self.sidechain_cables[index - self.cables.len()],
+        }
+    }
+
     /// Connect an output port to an input port
     pub fn connect(&mut self, from: PortRef, to: PortRef) -> Result<CableId, PatchError> {
         self.validate_output_port(from)?;
@@ -510,25 +1298,145 @@ impl Patch {
         Ok(())
     }
 
+    /// Set a cable's gain (attenuverter), -2.0 to 2.0
+    ///
+    /// The engine scales the cable's source value by the gain, then adds the
+    /// offset, before summing it into the destination input. Takes effect
+    /// on the next tick; unity gain matches an unmodulated cable.
+    pub fn set_cable_gain(&mut self, cable_id: CableId, gain: f64) -> Result<(), PatchError> {
+        let cable = self
+            .cables
+            .get_mut(cable_id)
+            .ok_or(PatchError::InvalidCable)?;
+        cable.attenuation = Some(gain.clamp(-2.0, 2.0));
+        Ok(())
+    }
+
+    /// Set a cable's DC offset, -10.0 to 10.0V, added after the gain
+    pub fn set_cable_offset(&mut self, cable_id: CableId, offset: f64) -> Result<(), PatchError> {
+        let cable = self
+            .cables
+            .get_mut(cable_id)
+            .ok_or(PatchError::InvalidCable)?;
+        cable.offset = Some(offset.clamp(-10.0, 10.0));
+        Ok(())
+    }
+
     /// Set the output node for the patch
     pub fn set_output(&mut self, node: NodeId) {
         self.output_node = Some(node);
     }
 
+    /// The node whose ports 0/1 are the patch output, if one is set
+    pub fn output_node(&self) -> Option<NodeId> {
+        self.output_node
+    }
+
     /// Set a parameter on a module
+    ///
+    /// Cancels any glide on the parameter from
+    /// [`set_param_smoothed`](Self::set_param_smoothed).
     pub fn set_param(&mut self, node: NodeId, param: ParamId, value: f64) {
+        self.param_smoothers
+            .retain(|&(n, p, _)| (n, p) != (node, param));
         if let Some(n) = self.nodes.get_mut(node) {
             n.module.set_param(param, value);
         }
     }
 
-    /// Get a parameter value from a module
-    pub fn get_param(&self, node: NodeId, param: ParamId) -> Option<f64> {
-        self.nodes.get(node).and_then(|n| n.module.get_param(param))
-    }
-
-    /// Set module position (for UI)
-    pub fn set_position(&mut self, node: NodeId, position: (f32, f32)) {
+    /// Glide a parameter to `value`, covering 99% of the way in `ms` milliseconds
+    ///
+    /// For sweeping a parameter from a GUI slider or MIDI CC without zipper
+    /// noise. Modules that smooth the parameter themselves take the glide
+    /// through `GraphModule::set_param_smoothed`; for the rest the patch
+    /// steps it with `set_param` every sample (every block when processing
+    /// in blocks).
+    pub fn set_param_smoothed(&mut self, node: NodeId, param: ParamId, value: f64, ms: f64) {
+        self.param_smoothers
+            .retain(|&(n, p, _)| (n, p) != (node, param));
+        let sample_rate = self.internal_sample_rate();
+        let Some(n) = self.nodes.get_mut(node) else {
+            return;
+        };
+        if n.module.set_param_smoothed(param, value, ms) {
+            return;
+        }
+        match n.module.get_param(param) {
+            Some(current) => {
+                let mut smoother = SmoothedParam::new(current);
+                smoother.set_target(value, ms, sample_rate);
+                if smoother.is_settled() {
+                    n.module.set_param(param, value);
+                } else {
+                    self.param_smoothers.push((node, param, smoother));
+                }
+            }
+            None => n.module.set_param(param, value),
+        }
+    }
+
+    /// Get a parameter value from a module
+    ///
+    /// During a glide the patch runs, this is the value it is heading for.
+    pub fn get_param(&self, node: NodeId, param: ParamId) -> Option<f64> {
+        let gliding = self
+            .param_smoothers
+            .iter()
+            .find(|&&(n, p, _)| (n, p) == (node, param));
+        match gliding {
+            Some((_, _, smoother)) => Some(smoother.target()),
+            None => self.nodes.get(node).and_then(|n| n.module.get_param(param)),
+        }
+    }
+
+    /// Hold an unpatched input at `value` in place of its default
+    ///
+    /// For driving a port from outside the graph, such as a gate from a
+    /// controller button. A cable into the input takes precedence while it
+    /// is connected.
+    pub fn set_input_value(&mut self, port: PortRef, value: f64) -> Result<(), PatchError> {
+        let node = self.nodes.get(port.node).ok_or(PatchError::InvalidNode)?;
+        if !node
+            .module
+            .port_spec()
+            .inputs
+            .iter()
+            .any(|p| p.id == port.port)
+        {
+            return Err(PatchError::InvalidPort);
+        }
+        self.external_inputs.insert(port, value);
+        Ok(())
+    }
+
+    /// Return an input held by [`set_input_value`](Self::set_input_value) to its default
+    pub fn clear_input_value(&mut self, port: PortRef) {
+        self.external_inputs.remove(&port);
+    }
+
+    /// The value an input is held at by [`set_input_value`](Self::set_input_value)
+    pub fn input_value(&self, port: PortRef) -> Option<f64> {
+        self.external_inputs.get(&port).copied()
+    }
+
+    /// Move the patch's parameter glides `samples` ahead
+    fn advance_param_smoothers(&mut self, samples: usize) {
+        if self.param_smoothers.is_empty() {
+            return;
+        }
+        for (node, param, smoother) in &mut self.param_smoothers {
+            let value = smoother.advance(samples);
+            if let Some(n) = self.nodes.get_mut(*node) {
+                n.module.set_param(*param, value);
+            }
+        }
+        let nodes = &self.nodes;
+        self.param_smoothers
+            .retain(|(node, _, smoother)| !smoother.is_settled() && nodes.contains_key(*node));
+    }
+
+    /// Set module position (for UI)
+    pub fn set_position(&mut self, node: NodeId, position: (f32, f32)) {
         if let Some(n) = self.nodes.get_mut(node) {
             n.position = Some(position);
         }
@@ -539,6 +1447,54 @@ impl Patch {
         self.nodes.get(node).and_then(|n| n.position)
     }
 
+    /// Add a node to a sync group, or remove it from its group with `None`
+    ///
+    /// A node belongs to at most one group. All members of a group restart
+    /// together on [`Patch::reset_sync_group`].
+    pub fn set_sync_group(&mut self, node: NodeId, group: Option<u32>) {
+        if let Some(n) = self.nodes.get_mut(node) {
+            n.sync_group = group;
+        }
+    }
+
+    /// Get the sync group a node belongs to
+    pub fn sync_group(&self, node: NodeId) -> Option<u32> {
+        self.nodes.get(node).and_then(|n| n.sync_group)
+    }
+
+    /// Reset every module in a sync group, returning how many were reset
+    ///
+    /// Members restart from phase zero on the next tick, so LFOs and clocks
+    /// in the group line up again. Nodes outside the group are untouched.
+    pub fn reset_sync_group(&mut self, group: u32) -> usize {
+        let mut count = 0;
+        for (_, node) in self.nodes.iter_mut() {
+            if node.sync_group == Some(group) {
+                node.module.reset();
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Freeze every stochastic module's recent random values into fixed
+    /// state, returning how many modules froze
+    ///
+    /// Turns a generative patch into a deterministic one that keeps playing
+    /// what it just played (see `GraphModule::freeze_to_params`). Modules
+    /// with nothing random, or that have not generated anything yet, are
+    /// left alone. The frozen values are module state, so they are kept by
+    /// `to_def` and restored by `from_def`.
+    pub fn freeze_to_params(&mut self) -> usize {
+        let mut count = 0;
+        for (_, node) in self.nodes.iter_mut() {
+            if node.module.freeze_to_params() {
+                count += 1;
+            }
+        }
+        count
+    }
+
     /// Get module name
     pub fn get_name(&self, node: NodeId) -> Option<&str> {
         self.nodes.get(node).map(|n| n.name.as_str())
@@ -549,6 +1505,22 @@ impl Patch {
         self.nodes.len()
     }
 
+    /// Static estimate of the per-sample processing cost
+    ///
+    /// Sums each module's `cost_hint`, scaled by the oversampling factor
+    /// since every module ticks that many times per output sample. The
+    /// result is relative (one unit is roughly a simple module), meant for
+    /// warning about patches that may overrun the audio budget rather than
+    /// as a measurement.
+    pub fn estimated_cost(&self) -> u64 {
+        let per_tick: u64 = self
+            .nodes
+            .values()
+            .map(|node| node.module.cost_hint() as u64)
+            .sum();
+        per_tick * self.oversampling as u64
+    }
+
     /// Get number of cables
     pub fn cable_count(&self) -> usize {
         self.cables.len()
@@ -597,9 +1569,42 @@ impl Patch {
     }
 
     /// Compile the patch into an executable order
+    ///
+    /// Feedback loops are allowed when they pass through a module that
+    /// `breaks_feedback`, such as a `UnitDelay`. The delay writes its stored
+    /// output before the rest of its loop runs and reads its input after the
+    /// loop has written it, so a loop through one `UnitDelay` has a round trip
+    /// of exactly one sample. A loop without such a module fails with
+    /// [`PatchError::UnbrokenFeedbackLoop`]. Patches with feedback loops
+    /// process per sample even when a block size is set.
     pub fn compile(&mut self) -> Result<(), PatchError> {
+        self.resolve_sidechains()?;
+        self.feedback_cables = self.find_feedback_cables();
+        self.feedback_breakers.clear();
+        for &(from, _) in &self.feedback_cables {
+            if !self.feedback_breakers.contains(&from.node) {
+                self.feedback_breakers.push(from.node);
+            }
+        }
         let order = self.topological_sort()?;
         self.execution_order = order;
+        self.incoming_cables = self
+            .execution_order
+            .iter()
+            .map(|&node| {
+                self.all_cables()
+                    .enumerate()
+                    .filter(|(_, cable)| cable.to.node == node)
+                    .map(|(index, _)| index)
+                    .collect()
+            })
+            .collect();
+        self.update_solo_downstream();
+        self.compensate_latency();
+        self.insert_dc_blockers();
+        self.block_io.clear();
+        self.block_buffers.clear();
+        self.discard_block();
 
         // Pre-allocate output buffers
         self.buffers.clear();
@@ -618,12 +1623,140 @@ impl Patch {
         Ok(())
     }
 
+    /// Put a DC blocker on each audio output of the modules that ask for one
+    fn insert_dc_blockers(&mut self) {
+        self.dc_blockers.clear();
+        let rate = self.internal_sample_rate();
+        for (id, node) in &self.nodes {
+            if !node.module.wants_dc_block() {
+                continue;
+            }
+            for output in &node.module.port_spec().outputs {
+                if output.kind == SignalKind::Audio {
+                    let port_ref = PortRef {
+                        node: id,
+                        port: output.id,
+                    };
+                    self.dc_blockers.insert(port_ref, DcBlocker::new(rate));
+                }
+            }
+        }
+    }
+
+    /// Add up module latencies along every path and delay the cables into a
+    /// node so all of them arrive with the latency of its slowest input
+    fn compensate_latency(&mut self) {
+        self.latencies.clear();
+        self.compensation.clear();
+        self.delay_lines.clear();
+
+        for &node_id in &self.execution_order {
+            let sources: Vec<(PortRef, usize)> = self
+                .all_cables()
+                .filter(|cable| cable.to.node == node_id && !self.is_feedback(cable))
+                .map(|cable| (cable.from, self.latencies[&cable.from.node]))
+                .collect();
+            let input_latency = sources.iter().map(|&(_, l)| l).max().unwrap_or(0);
+
+            for (source, latency) in sources {
+                let delay = input_latency - latency;
+                if delay == 0 || self.compensation.contains_key(&(source, node_id)) {
+                    continue;
+                }
+                let line = match self
+                    .delay_lines
+                    .iter()
+                    .position(|line| line.source == source && line.delay() == delay)
+                {
+                    Some(line) => line,
+                    None => {
+                        self.delay_lines.push(CompensationDelay::new(source, delay));
+                        self.delay_lines.len() - 1
+                    }
+                };
+                self.compensation.insert((source, node_id), line);
+            }
+
+            let own = self.nodes[node_id].module.latency_samples();
+            self.latencies.insert(node_id, input_latency + own);
+        }
+    }
+
+    /// Cumulative latency at a node's outputs, in internal samples (after compile)
+    pub fn node_latency(&self, node: NodeId) -> Option<usize> {
+        self.latencies.get(&node).copied()
+    }
+
+    /// Delay inserted on a cable to align it with its destination's other
+    /// inputs, in internal samples (after compile)
+    pub fn compensation_delay(&self, cable: CableId) -> Option<usize> {
+        let cable = self.cables.get(cable)?;
+        Some(
+            self.compensation
+                .get(&(cable.from, cable.to.node))
+                .map_or(0, |&line| self.delay_lines[line].delay()),
+        )
+    }
+
+    /// Latency from the patch's sources to its output, in samples
+    ///
+    /// The latency accumulated at the output node, plus the decimation
+    /// filter's delay when oversampling, rounded to whole base-rate samples.
+    /// Hosts report this so the patch can be aligned with other tracks.
+    pub fn total_latency(&self) -> usize {
+        let graph = self
+            .output_node
+            .and_then(|node| self.node_latency(node))
+            .unwrap_or(0);
+        if self.oversampling == 1 {
+            return graph;
+        }
+        // The decimator centres on the newest input minus half its length,
+        // and it reads at the last of each sample's sub-samples
+        let factor = self.oversampling;
+        let decimation = Decimator::TAPS_PER_FACTOR * factor / 2 - (factor - 1);
+        (graph + decimation + factor / 2) / factor
+    }
+
+    /// Cables out of a feedback-breaking module into a node upstream of it
+    fn find_feedback_cables(&self) -> Vec<(PortRef, PortRef)> {
+        let mut feedback = Vec::new();
+        for (breaker, node) in &self.nodes {
+            if !node.module.breaks_feedback() {
+                continue;
+            }
+
+            let mut upstream = vec![breaker];
+            let mut queue = VecDeque::from([breaker]);
+            while let Some(to) = queue.pop_front() {
+                for cable in self.all_cables() {
+                    let from = cable.from.node;
+                    if cable.to.node == to && !upstream.contains(&from) {
+                        upstream.push(from);
+                        queue.push_back(from);
+                    }
+                }
+            }
+
+            feedback.extend(
+                self.all_cables()
+                    .filter(|c| c.from.node == breaker && upstream.contains(&c.to.node))
+                    .map(|c| (c.from, c.to)),
+            );
+        }
+        feedback
+    }
+
+    fn is_feedback(&self, cable: &Cable) -> bool {
+        self.feedback_cables.contains(&(cable.from, cable.to))
+    }
+
     fn topological_sort(&self) -> Result<Vec<NodeId>, PatchError> {
         let mut in_degree: StdMap<NodeId, usize> = self.nodes.keys().map(|k| (k, 0)).collect();
         let mut successors: StdMap<NodeId, Vec<NodeId>> =
             self.nodes.keys().map(|k| (k, vec![])).collect();
 
-        for cable in &self.cables {
+        for cable in self.all_cables().filter(|c| !self.is_feedback(c)) {
             *in_degree.entry(cable.to.node).or_insert(0) += 1;
             successors
                 .entry(cable.from.node)
@@ -652,12 +1785,28 @@ impl Patch {
         }
 
         if result.len() != self.nodes.len() {
-            let in_cycle: Vec<NodeId> = in_degree
+            // Left over are the loops and the nodes downstream of them; peel
+            // off the downstream nodes, which feed nothing left over
+            let mut in_loop: Vec<NodeId> = in_degree
                 .into_iter()
                 .filter(|(_, deg)| *deg > 0)
                 .map(|(id, _)| id)
                 .collect();
-            return Err(PatchError::CycleDetected { nodes: in_cycle });
+            loop {
+                let before = in_loop.len();
+                let feeds_loop = |node: NodeId, in_loop: &[NodeId]| {
+                    successors[&node].iter().any(|next| in_loop.contains(next))
+                };
+                in_loop = in_loop
+                    .iter()
+                    .copied()
+                    .filter(|&node| feeds_loop(node, &in_loop))
+                    .collect();
+                if in_loop.len() == before {
+                    break;
+                }
+            }
+            return Err(PatchError::UnbrokenFeedbackLoop { nodes: in_loop });
         }
 
         Ok(result)
@@ -665,633 +1814,3128 @@ impl Patch {
 
     /// Process a single sample, returning stereo output
     pub fn tick(&mut self) -> (f64, f64) {
-        for &node_id in &self.execution_order.clone() {
-            let inputs = self.gather_inputs(node_id);
-            let mut outputs = PortValues::new();
+        if self.block_size > 1
+            && self.oversampling == 1
+            && self.transport.is_none()
+            && self.feedback_cables.is_empty()
+        {
+            if self.block_cursor >= self.block_output.len() {
+                self.process_graph_block();
+            }
+            let output = self.block_output[self.block_cursor];
+            self.block_cursor += 1;
+            return output;
+        }
 
-            // Process the module
-            if let Some(node) = self.nodes.get_mut(node_id) {
-                node.module.tick(&inputs, &mut outputs);
+        let output = if self.oversampling > 1 {
+            for _ in 0..self.oversampling {
+                self.process_graph();
+                let output = self.read_output();
+                let (left, right) = self.guarded_output(output);
+                if let Some((dec_left, dec_right)) = &mut self.decimators {
+                    dec_left.push(left);
+                    dec_right.push(right);
+                }
+            }
+            match &self.decimators {
+                Some((dec_left, dec_right)) => (dec_left.output(), dec_right.output()),
+                None => self.read_output(),
             }
+        } else {
+            self.process_graph();
+            let output = self.read_output();
+            self.guarded_output(output)
+        };
 
-            // Store outputs in buffers
-            self.scatter_outputs(node_id, &outputs);
+        if let Some(transport) = &mut self.transport {
+            transport.advance();
         }
 
-        self.read_output()
+        output
     }
 
-    fn gather_inputs(&self, node_id: NodeId) -> PortValues {
-        let node = match self.nodes.get(node_id) {
-            Some(n) => n,
-            None => return PortValues::new(),
-        };
-        let spec = node.module.port_spec();
-        let mut values = PortValues::new();
+    /// Snapshot the complete running state of the patch
+    ///
+    /// Captures what `tick` evolves rather than the topology: every module's
+    /// runtime state (see `GraphModule::serialize_runtime`), the cable
+    /// buffers carrying last-sample values, the DC blockers, the oversampling
+    /// decimators, the transport, and the parameter glides started with
+    /// `set_param_smoothed`. Restoring the snapshot into the same patch (or one
+    /// built identically, matched by node name) resumes output exactly.
+    ///
+    /// The blob starts with a magic tag and `RUNTIME_STATE_VERSION`. It
+    /// includes delay and reverb buffers in full, so it can run to hundreds
+    /// of kilobytes per buffer-based module.
+    ///
+    /// Fails with `RuntimeState` if a module does not support runtime state
+    /// (see `GraphModule::supports_runtime_state`).
+    pub fn save_runtime_state(&self) -> Result<Vec<u8>, PatchError> {
+        self.check_runtime_state_support()
+            .map_err(PatchError::RuntimeState)?;
+        Ok(self.write_runtime_state())
+    }
 
-        for input in &spec.inputs {
-            let port_ref = PortRef {
-                node: node_id,
-                port: input.id,
-            };
+    /// Whether every module supports runtime state
+    fn supports_runtime_state(&self) -> bool {
+        self.check_runtime_state_support().is_ok()
+    }
 
-            // Sum all incoming cables (hardware-style input mixing)
-            let mut sum = 0.0;
-            let mut has_connection = false;
+    fn check_runtime_state_support(&self) -> Result<(), String> {
+        match self
+            .nodes
+            .values()
+            .find(|node| !node.module.supports_runtime_state())
+        {
+            Some(node) => Err(format!(
+                "node '{}' ({}) does not support runtime state",
+                node.name,
+                node.module.type_id()
+            )),
+            None => Ok(()),
+        }
+    }
 
-            for cable in &self.cables {
-                if cable.to == port_ref {
-                    has_connection = true;
-                    let value = self.buffers.get(&cable.from).copied().unwrap_or(0.0);
-                    // Apply attenuation/attenuverter (signal * gain)
-                    let attenuated = cable.attenuation.map(|a| value * a).unwrap_or(value);
-                    // Apply DC offset after attenuation
-                    let with_offset = cable.offset.map(|o| attenuated + o).unwrap_or(attenuated);
-                    sum += with_offset;
-                }
+    fn write_runtime_state(&self) -> Vec<u8> {
+        let mut state = RuntimeWriter::new();
+        state.put_bytes(RUNTIME_STATE_MAGIC);
+        state.put_u64(RUNTIME_STATE_VERSION);
+
+        state.put_usize(self.nodes.len());
+        for (id, node) in &self.nodes {
+            state.put_bytes(node.name.as_bytes());
+            state.put_bytes(node.module.type_id().as_bytes());
+
+            let mut module_state = RuntimeWriter::new();
+            node.module.serialize_runtime(&mut module_state);
+            state.put_bytes(&module_state.into_bytes());
+
+            let mut buffers: Vec<(PortId, f64)> = self
+                .buffers
+                .iter()
+                .filter(|(port_ref, _)| port_ref.node == id)
+                .map(|(port_ref, &value)| (port_ref.port, value))
+                .collect();
+            buffers.sort_by_key(|&(port, _)| port);
+            state.put_usize(buffers.len());
+            for (port, value) in buffers {
+                state.put_u64(port as u64);
+                state.put_f64(value);
             }
 
-            if has_connection {
-                values.set(input.id, sum);
-            } else if let Some(normalled) = input.normalled_to {
-                // Use normalled (internal) connection
-                let normalled_ref = PortRef {
-                    node: node_id,
-                    port: normalled,
-                };
-                if let Some(&v) = self.buffers.get(&normalled_ref) {
-                    values.set(input.id, v);
-                } else {
-                    values.set(input.id, input.default);
-                }
-            } else {
-                // Use default value
-                values.set(input.id, input.default);
+            let mut blockers: Vec<(PortId, &DcBlocker)> = self
+                .dc_blockers
+                .iter()
+                .filter(|(port_ref, _)| port_ref.node == id)
+                .map(|(port_ref, blocker)| (port_ref.port, blocker))
+                .collect();
+            blockers.sort_by_key(|&(port, _)| port);
+            state.put_usize(blockers.len());
+            for (port, blocker) in blockers {
+                state.put_u64(port as u64);
+                blocker.serialize_runtime(&mut state);
             }
         }
 
-        values
-    }
-
-    fn scatter_outputs(&mut self, node_id: NodeId, outputs: &PortValues) {
-        for (&port_id, &value) in &outputs.values {
-            let port_ref = PortRef {
-                node: node_id,
-                port: port_id,
-            };
-            self.buffers.insert(port_ref, value);
+        state.put_bool(self.decimators.is_some());
+        if let Some((dec_left, dec_right)) = &self.decimators {
+            dec_left.serialize_runtime(&mut state);
+            dec_right.serialize_runtime(&mut state);
         }
-    }
 
-    fn read_output(&self) -> (f64, f64) {
-        if let Some(output_node) = self.output_node {
-            let left = self
-                .buffers
-                .get(&PortRef {
-                    node: output_node,
-                    port: 0, // Assuming port 0 is left
-                })
-                .copied()
-                .unwrap_or(0.0);
-            let right = self
-                .buffers
-                .get(&PortRef {
-                    node: output_node,
-                    port: 1, // Assuming port 1 is right
-                })
-                .copied()
-                .unwrap_or(left); // Mono fallback
-            (left, right)
-        } else {
-            (0.0, 0.0)
+        state.put_bool(self.transport.is_some());
+        if let Some(transport) = &self.transport {
+            transport.serialize_runtime(&mut state);
         }
-    }
 
-    /// Reset all modules in the patch
-    pub fn reset(&mut self) {
-        for (_, node) in &mut self.nodes {
-            node.module.reset();
+        state.put_usize(self.delay_lines.len());
+        for line in &self.delay_lines {
+            state.put_bytes(self.nodes[line.source.node].name.as_bytes());
+            state.put_u64(line.source.port as u64);
+            state.put_f64s(&line.line);
+            state.put_usize(line.pos);
+            state.put_f64(line.output);
         }
-        for value in self.buffers.values_mut() {
-            *value = 0.0;
+
+        state.put_usize(self.param_smoothers.len());
+        for (node, param, smoother) in &self.param_smoothers {
+            state.put_bytes(self.nodes[*node].name.as_bytes());
+            state.put_u64(*param as u64);
+            smoother.serialize_runtime(&mut state);
         }
-    }
 
-    /// Iterate over all nodes
-    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &str, &dyn GraphModule)> {
-        self.nodes
-            .iter()
-            .map(|(id, node)| (id, node.name.as_str(), node.module.as_ref()))
+        state.into_bytes()
     }
 
-    /// Get a NodeId by module name
-    pub fn get_node_id_by_name(&self, name: &str) -> Option<NodeId> {
-        self.nodes
-            .iter()
-            .find(|(_, node)| node.name == name)
-            .map(|(id, _)| id)
+    /// Restore a snapshot taken with `save_runtime_state`
+    ///
+    /// Nodes are matched by name and must have the same module type. The
+    /// whole snapshot is checked before any state is replaced, and a module
+    /// that rejects its part is rolled back with the others, so on error the
+    /// patch is left as it was.
+    pub fn restore_runtime_state(&mut self, bytes: &[u8]) -> Result<(), PatchError> {
+        self.check_runtime_state_support()
+            .and_then(|()| self.read_runtime_state(bytes))
+            .map_err(PatchError::RuntimeState)
     }
 
-    /// Get a NodeHandle by module name
-    pub fn get_handle_by_name(&self, name: &str) -> Option<NodeHandle> {
-        self.nodes
-            .iter()
-            .find(|(_, node)| node.name == name)
-            .map(|(id, node)| NodeHandle::from_module(id, node.module.as_ref()))
-    }
+    fn read_runtime_state(&mut self, bytes: &[u8]) -> Result<(), String> {
+        let mut state = RuntimeReader::new(bytes);
+        if state.bytes()? != RUNTIME_STATE_MAGIC {
+            return Err(String::from("not a runtime state snapshot"));
+        }
+        let version = state.u64()?;
+        if version != RUNTIME_STATE_VERSION {
+            return Err(format!("unsupported runtime state version {}", version));
+        }
 
-    /// Disconnect a cable by finding matching port refs
-    pub fn disconnect_ports(&mut self, from: PortRef, to: PortRef) -> Result<(), PatchError> {
-        let idx = self
-            .cables
-            .iter()
-            .position(|c| c.from == from && c.to == to)
-            .ok_or(PatchError::InvalidCable)?;
+        let count = state.usize()?;
+        if count != self.nodes.len() {
+            return Err(format!(
+                "snapshot has {} nodes, patch has {}",
+                count,
+                self.nodes.len()
+            ));
+        }
 
-        self.cables.remove(idx);
-        self.invalidate();
-        Ok(())
-    }
+        // Parse everything into staging copies first
+        let mut modules: Vec<(NodeId, &[u8])> = Vec::with_capacity(count);
+        let mut buffers = StdMap::new();
+        let mut blockers = self.dc_blockers.clone();
+        for _ in 0..count {
+            let name = core::str::from_utf8(state.bytes()?)
+                .map_err(|_| String::from("node name is not UTF-8"))?;
+            let type_id = state.bytes()?;
+            let id = self
+                .get_node_id_by_name(name)
+                .ok_or_else(|| format!("no node named '{}'", name))?;
+            if modules.iter().any(|&(seen, _)| seen == id) {
+                return Err(format!("node '{}' appears twice", name));
+            }
+            if self.nodes[id].module.type_id().as_bytes() != type_id {
+                return Err(format!("node '{}' has a different module type", name));
+            }
+            modules.push((id, state.bytes()?));
 
-    /// Get all module names
-    pub fn module_names(&self) -> Vec<&str> {
-        self.nodes
-            .iter()
-            .map(|(_, node)| node.name.as_str())
-            .collect()
-    }
+            for _ in 0..state.usize()? {
+                let port = PortId::try_from(state.u64()?)
+                    .map_err(|_| String::from("port id out of range"))?;
+                buffers.insert(PortRef { node: id, port }, state.f64()?);
+            }
 
-    /// Get the current output buffer value for a specific port
-    ///
-    /// This is used by the observer to collect real-time values for metering,
-    /// scope display, and other visualizations.
-    pub fn get_output_value(&self, node: NodeId, port: PortId) -> Option<f64> {
-        self.buffers.get(&PortRef { node, port }).copied()
-    }
+            for _ in 0..state.usize()? {
+                let port = PortId::try_from(state.u64()?)
+                    .map_err(|_| String::from("port id out of range"))?;
+                blockers
+                    .get_mut(&PortRef { node: id, port })
+                    .ok_or_else(|| format!("no DC blocker on '{}'", name))?
+                    .deserialize_runtime(&mut state)?;
+            }
+        }
 
-    /// Get the signal kind for an output port by node ID and port ID
-    pub fn get_output_signal_kind(&self, node: NodeId, port: PortId) -> Option<SignalKind> {
-        let node_data = self.nodes.get(node)?;
-        node_data
-            .module
-            .port_spec()
-            .outputs
-            .iter()
-            .find(|p| p.id == port)
-            .map(|p| p.kind)
-    }
-}
+        let has_decimators = state.bool()?;
+        let mut decimators = self.decimators.clone();
+        match (&mut decimators, has_decimators) {
+            (Some((dec_left, dec_right)), true) => {
+                dec_left.deserialize_runtime(&mut state)?;
+                dec_right.deserialize_runtime(&mut state)?;
+            }
+            (None, false) => {}
+            _ => return Err(String::from("oversampling differs from the snapshot")),
+        }
+
+        let has_transport = state.bool()?;
+        let mut transport = self.transport;
+        match (&mut transport, has_transport) {
+            (Some(transport), true) => transport.deserialize_runtime(&mut state)?,
+            (None, false) => {}
+            _ => return Err(String::from("transport presence differs from the snapshot")),
+        }
+
+        let count = state.usize()?;
+        if count != self.delay_lines.len() {
+            return Err(String::from(
+                "latency compensation differs from the snapshot",
+            ));
+        }
+        let mut lines = self.delay_lines.clone();
+        for _ in 0..count {
+            let name = core::str::from_utf8(state.bytes()?)
+                .map_err(|_| String::from("node name is not UTF-8"))?;
+            let port =
+                PortId::try_from(state.u64()?).map_err(|_| String::from("port id out of range"))?;
+            let values = state.f64s()?;
+            let line = lines
+                .iter_mut()
+                .find(|line| {
+                    self.nodes[line.source.node].name == name
+                        && line.source.port == port
+                        && line.delay() == values.len()
+                })
+                .ok_or_else(|| format!("no compensation delay on '{}'", name))?;
+            let pos = state.usize()?;
+            if pos >= values.len() {
+                return Err(String::from("compensation delay position out of range"));
+            }
+            line.line = values;
+            line.pos = pos;
+            line.output = state.f64()?;
+        }
+
+        let count = state.usize()?;
+        let mut smoothers = Vec::with_capacity(count.min(state.remaining()));
+        for _ in 0..count {
+            let name = core::str::from_utf8(state.bytes()?)
+                .map_err(|_| String::from("node name is not UTF-8"))?;
+            let node = self
+                .get_node_id_by_name(name)
+                .ok_or_else(|| format!("no node named '{}'", name))?;
+            let param = ParamId::try_from(state.u64()?)
+                .map_err(|_| String::from("param id out of range"))?;
+            let mut smoother = SmoothedParam::new(0.0);
+            smoother.deserialize_runtime(&mut state)?;
+            smoothers.push((node, param, smoother));
+        }
+
+        if state.remaining() != 0 {
+            return Err(String::from("trailing bytes after runtime state"));
+        }
+
+        // Module payloads are only checked by the modules themselves, so keep
+        // each module's previous state to roll back to if one is rejected
+        let mut previous: Vec<(NodeId, Vec<u8>)> = Vec::with_capacity(modules.len());
+        for (id, bytes) in modules {
+            let node = &mut self.nodes[id];
+            let mut saved = RuntimeWriter::new();
+            node.module.serialize_runtime(&mut saved);
+            previous.push((id, saved.into_bytes()));
+
+            let mut module_state = RuntimeReader::new(bytes);
+            let result = match node.module.deserialize_runtime(&mut module_state) {
+                Ok(()) if module_state.remaining() != 0 => {
+                    Err(format!("node '{}' left runtime state unread", node.name))
+                }
+                Ok(()) => Ok(()),
+                Err(e) => Err(format!("node '{}': {}", node.name, e)),
+            };
+            if let Err(e) = result {
+                for (id, bytes) in previous.iter().rev() {
+                    let restored = self.nodes[*id]
+                        .module
+                        .deserialize_runtime(&mut RuntimeReader::new(bytes));
+                    debug_assert!(restored.is_ok(), "module rejected its own state");
+                }
+                return Err(e);
+            }
+        }
+
+        self.buffers = buffers;
+        self.dc_blockers = blockers;
+        self.decimators = decimators;
+        self.transport = transport;
+        self.delay_lines = lines;
+        self.param_smoothers = smoothers;
+        self.discard_block();
+        Ok(())
+    }
+
+    /// Run every module once in execution order
+    fn process_graph(&mut self) {
+        self.advance_param_smoothers(1);
+        // Nothing to glide, delay, filter, or monitor: read inputs through
+        // the compiled per-node cable lists, skipping those lookups
+        let plain = self.param_smoothers.is_empty()
+            && self.compensation.is_empty()
+            && self.dc_blockers.is_empty()
+            && self.solo.is_none()
+            && self.muted.is_empty();
+        for &node_id in &self.feedback_breakers {
+            let node = &self.nodes[node_id];
+            if node.is_parked() {
+                continue;
+            }
+            let mut outputs = PortValues::new();
+            node.module.feedback_outputs(&mut outputs);
+            for (&port, &value) in &outputs.values {
+                self.buffers.insert(
+                    PortRef {
+                        node: node_id,
+                        port,
+                    },
+                    value,
+                );
+            }
+        }
+        let order = core::mem::take(&mut self.execution_order);
+        for (position, &node_id) in order.iter().enumerate() {
+            let inputs = if plain {
+                self.gather_plain_inputs(node_id, &self.incoming_cables[position])
+            } else {
+                self.gather_inputs(node_id)
+            };
+            let mut outputs = PortValues::new();
+
+            // Process the module
+            if let Some(node) = self.nodes.get_mut(node_id) {
+                if node.is_parked() {
+                    node.tick_parked(&inputs, &mut outputs);
+                } else {
+                    if let Some(transport) = &self.transport {
+                        node.module.sync_transport(transport);
+                    }
+                    node.module.tick(&inputs, &mut outputs);
+                }
+            }
+
+            if self.nan_guard == NanGuard::PerNode {
+                for (&port, value) in outputs.values.iter_mut() {
+                    if !value.is_finite() {
+                        *value = 0.0;
+                        self.last_error = Some(PatchError::NonFinite {
+                            node: node_id,
+                            port,
+                        });
+                    }
+                }
+            }
+            if !self.dc_blockers.is_empty() {
+                for (&port, value) in outputs.values.iter_mut() {
+                    let port_ref = PortRef {
+                        node: node_id,
+                        port,
+                    };
+                    if let Some(blocker) = self.dc_blockers.get_mut(&port_ref) {
+                        *value = blocker.process(*value);
+                    }
+                }
+            }
+
+            // Store outputs in buffers
+            self.scatter_outputs(node_id, &outputs);
+        }
+        self.execution_order = order;
+    }
+
+    /// Run every module over one block in execution order, filling `block_output`
+    fn process_graph_block(&mut self) {
+        let frames = self.block_size;
+        let ctx = self.process_context(frames);
+        self.advance_param_smoothers(frames);
+
+        for &node_id in &self.execution_order.clone() {
+            let (mut inputs, mut outputs) = self
+                .block_io
+                .remove(&node_id)
+                .unwrap_or_else(|| (BlockPortValues::new(frames), BlockPortValues::new(frames)));
+            self.gather_block_inputs(node_id, &mut inputs);
+            outputs.clear();
+
+            if let Some(node) = self.nodes.get_mut(node_id) {
+                if node.is_parked() {
+                    node.tick_parked_block(&inputs, &mut outputs);
+                } else {
+                    node.module.tick_block(&inputs, &mut outputs, &ctx);
+                }
+            }
+
+            for (port, values) in outputs.buffers_mut() {
+                if self.nan_guard == NanGuard::PerNode {
+                    for value in values.iter_mut() {
+                        if !value.is_finite() {
+                            *value = 0.0;
+                            self.last_error = Some(PatchError::NonFinite {
+                                node: node_id,
+                                port,
+                            });
+                        }
+                    }
+                }
+
+                let port_ref = PortRef {
+                    node: node_id,
+                    port,
+                };
+                if let Some(blocker) = self.dc_blockers.get_mut(&port_ref) {
+                    for value in values.iter_mut() {
+                        *value = blocker.process(*value);
+                    }
+                }
+                if let Some(&last) = values.get(frames - 1) {
+                    self.buffers.insert(port_ref, last);
+                }
+                let block = self.block_buffers.entry(port_ref).or_default();
+                block.clear();
+                block.extend_from_slice(values);
+            }
+            for line in &mut self.delay_lines {
+                if line.source.node != node_id {
+                    continue;
+                }
+                let mut block = core::mem::take(&mut line.block);
+                block.clear();
+                match self.block_buffers.get(&line.source) {
+                    Some(source) => block.extend(source.iter().map(|&v| line.push(v))),
+                    None => {
+                        let value = self.buffers.get(&line.source).copied().unwrap_or(0.0);
+                        block.extend((0..frames).map(|_| line.push(value)));
+                    }
+                }
+                line.block = block;
+            }
+            self.block_io.insert(node_id, (inputs, outputs));
+        }
+
+        self.block_output.clear();
+        for i in 0..frames {
+            let output = self.output_from(|port| {
+                self.block_buffers
+                    .get(&port)
+                    .and_then(|block| block.get(i))
+                    .copied()
+            });
+            let output = self.guarded_output(output);
+            self.block_output.push(output);
+        }
+        self.block_cursor = 0;
+    }
+
+    /// Merge each input's cables over the block
+    fn gather_block_inputs(&self, node_id: NodeId, inputs: &mut BlockPortValues) {
+        let Some(node) = self.nodes.get(node_id) else {
+            return;
+        };
+        let spec = node.module.port_spec();
+        let mut unpatched = Vec::new();
+        let monitoring = self.solo.is_some() || !self.muted.is_empty();
+
+        for input in &spec.inputs {
+            let port_ref = PortRef {
+                node: node_id,
+                port: input.id,
+            };
+            let buffer = inputs.get_buffer_mut(input.id);
+            buffer.fill(0.0);
+            let mode = input.merge_mode;
+            let mut count = 0;
+            let mut has_connection = false;
+
+            for cable in self.all_cables() {
+                if cable.to == port_ref {
+                    has_connection = true;
+                    if monitoring && self.is_silenced(cable, input.kind) {
+                        continue;
+                    }
+                    let scale = |value: f64| {
+                        let attenuated = cable.attenuation.map(|a| value * a).unwrap_or(value);
+                        cable.offset.map(|o| attenuated + o).unwrap_or(attenuated)
+                    };
+                    let delayed = self
+                        .compensation
+                        .get(&(cable.from, node_id))
+                        .map(|&line| &self.delay_lines[line].block);
+                    match delayed.or_else(|| self.block_buffers.get(&cable.from)) {
+                        Some(source) => {
+                            for (sum, &value) in buffer.iter_mut().zip(source) {
+                                *sum = mode.fold(*sum, scale(value), count);
+                            }
+                        }
+                        None => {
+                            let value = self.buffers.get(&cable.from).copied().unwrap_or(0.0);
+                            for sum in buffer.iter_mut() {
+                                *sum = mode.fold(*sum, scale(value), count);
+                            }
+                        }
+                    }
+                    count += 1;
+                }
+            }
+
+            if has_connection {
+                if mode == MergeMode::Average && count > 1 {
+                    for sum in buffer.iter_mut() {
+                        *sum = mode.finish(*sum, count);
+                    }
+                }
+                continue;
+            }
+            if let Some(&value) = self.external_inputs.get(&port_ref) {
+                buffer.fill(value);
+                continue;
+            }
+            buffer.fill(input.default);
+            if input.normalled_to.is_some() {
+                unpatched.push(input.id);
+            }
+        }
+
+        for &input in &unpatched {
+            if let Some(source) = normalled_source(spec, &unpatched, input) {
+                let mut buffer = inputs.take_buffer(input);
+                if let Some(values) = inputs.get_buffer(source) {
+                    buffer.copy_from_slice(values);
+                }
+                inputs.put_buffer(input, buffer);
+            }
+        }
+    }
+
+    /// Drop any rendered samples `tick` has not handed out yet
+    fn discard_block(&mut self) {
+        self.block_output.clear();
+        self.block_cursor = 0;
+    }
+
+    fn gather_inputs(&self, node_id: NodeId) -> PortValues {
+        let node = match self.nodes.get(node_id) {
+            Some(n) => n,
+            None => return PortValues::new(),
+        };
+        let spec = node.module.port_spec();
+        let mut values = PortValues::new();
+        let mut unpatched = Vec::new();
+        let monitoring = self.solo.is_some() || !self.muted.is_empty();
+
+        for input in &spec.inputs {
+            let port_ref = PortRef {
+                node: node_id,
+                port: input.id,
+            };
+
+            // Merge all incoming cables, summing by default (hardware-style
+            // input mixing)
+            let mut sum = 0.0;
+            let mut count = 0;
+            let mut has_connection = false;
+
+            for cable in self.all_cables() {
+                if cable.to == port_ref {
+                    has_connection = true;
+                    if monitoring && self.is_silenced(cable, input.kind) {
+                        continue;
+                    }
+                    let value = match self.compensation.get(&(cable.from, node_id)) {
+                        Some(&line) => self.delay_lines[line].output,
+                        None => self.buffers.get(&cable.from).copied().unwrap_or(0.0),
+                    };
+                    // Apply attenuation/attenuverter (signal * gain)
+                    let attenuated = cable.attenuation.map(|a| value * a).unwrap_or(value);
+                    // Apply DC offset after attenuation
+                    let with_offset = cable.offset.map(|o| attenuated + o).unwrap_or(attenuated);
+                    sum = input.merge_mode.fold(sum, with_offset, count);
+                    count += 1;
+                }
+            }
+
+            if has_connection {
+                values.set(input.id, input.merge_mode.finish(sum, count));
+            } else if let Some(&v) = self.external_inputs.get(&port_ref) {
+                values.set(input.id, v);
+            } else {
+                // Use default value
+                values.set(input.id, input.default);
+                if input.normalled_to.is_some() {
+                    unpatched.push(input.id);
+                }
+            }
+        }
+
+        // Normalled inputs left unpatched follow their source input
+        for &input in &unpatched {
+            if let Some(value) =
+                normalled_source(spec, &unpatched, input).and_then(|source| values.get(source))
+            {
+                values.set(input, value);
+            }
+        }
+
+        values
+    }
+
+    /// `gather_inputs` for a patch with no compensation delays and nothing
+    /// soloed or muted, reading only the node's own `incoming` cables
+    fn gather_plain_inputs(&self, node_id: NodeId, incoming: &[usize]) -> PortValues {
+        let mut values = PortValues::new();
+        let Some(node) = self.nodes.get(node_id) else {
+            return values;
+        };
+        let spec = node.module.port_spec();
+        let mut unpatched = Vec::new();
+
+        for input in &spec.inputs {
+            let port_ref = PortRef {
+                node: node_id,
+                port: input.id,
+            };
+            let mut sum = 0.0;
+            let mut count = 0;
+            for &index in incoming {
+                let cable = self.cable_at(index);
+                if cable.to == port_ref {
+                    let value = self.buffers.get(&cable.from).copied().unwrap_or(0.0);
+                    let attenuated = cable.attenuation.map(|a| value * a).unwrap_or(value);
+                    let with_offset = cable.offset.map(|o| attenuated + o).unwrap_or(attenuated);
+                    sum = input.merge_mode.fold(sum, with_offset, count);
+                    count += 1;
+                }
+            }
+
+            if count > 0 {
+                values.set(input.id, input.merge_mode.finish(sum, count));
+            } else if let Some(&v) = self.external_inputs.get(&port_ref) {
+                values.set(input.id, v);
+            } else {
+                values.set(input.id, input.default);
+                if input.normalled_to.is_some() {
+                    unpatched.push(input.id);
+                }
+            }
+        }
+
+        for &input in &unpatched {
+            if let Some(value) =
+                normalled_source(spec, &unpatched, input).and_then(|source| values.get(source))
+            {
+                values.set(input, value);
+            }
+        }
+
+        values
+    }
+
+    fn scatter_outputs(&mut self, node_id: NodeId, outputs: &PortValues) {
+        for (&port_id, &value) in &outputs.values {
+            let port_ref = PortRef {
+                node: node_id,
+                port: port_id,
+            };
+            self.buffers.insert(port_ref, value);
+        }
+        for line in &mut self.delay_lines {
+            if line.source.node == node_id {
+                let value = self.buffers.get(&line.source).copied().unwrap_or(0.0);
+                line.push(value);
+            }
+        }
+    }
+
+    /// Patch output with non-finite values replaced when the NaN guard is on
+    fn guarded_output(&mut self, (left, right): (f64, f64)) -> (f64, f64) {
+        if self.nan_guard == NanGuard::Off || (left.is_finite() && right.is_finite()) {
+            return (left, right);
+        }
+
+        if let Some(node) = self.output_node {
+            let port = if left.is_finite() { 1 } else { 0 };
+            self.last_error = Some(PatchError::NonFinite { node, port });
+        }
+        let sanitize = |v: f64| if v.is_finite() { v } else { 0.0 };
+        (sanitize(left), sanitize(right))
+    }
+
+    fn read_output(&self) -> (f64, f64) {
+        self.output_from(|port| self.buffers.get(&port).copied())
+    }
+
+    /// Stereo output read through `value`, honouring mute and the mono fallback
+    fn output_from(&self, value: impl Fn(PortRef) -> Option<f64>) -> (f64, f64) {
+        if let Some(output_node) = self.output_node {
+            if self.solo != Some(output_node) && self.muted.contains(&output_node) {
+                return (0.0, 0.0);
+            }
+            let left = value(PortRef {
+                node: output_node,
+                port: 0, // Assuming port 0 is left
+            })
+            .unwrap_or(0.0);
+            let right = value(PortRef {
+                node: output_node,
+                port: 1, // Assuming port 1 is right
+            })
+            .unwrap_or(left); // Mono fallback
+            (left, right)
+        } else {
+            (0.0, 0.0)
+        }
+    }
+
+    /// Reset all modules in the patch
+    pub fn reset(&mut self) {
+        for (_, node) in &mut self.nodes {
+            node.module.reset();
+        }
+        for value in self.buffers.values_mut() {
+            *value = 0.0;
+        }
+        if let Some((dec_left, dec_right)) = &mut self.decimators {
+            dec_left.reset();
+            dec_right.reset();
+        }
+        for line in &mut self.delay_lines {
+            line.reset();
+        }
+        for blocker in self.dc_blockers.values_mut() {
+            blocker.reset();
+        }
+        for (node, param, smoother) in self.param_smoothers.drain(..) {
+            if let Some(n) = self.nodes.get_mut(node) {
+                n.module.set_param(param, smoother.target());
+            }
+        }
+        self.discard_block();
+    }
+
+    /// Iterate over all nodes
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &str, &dyn GraphModule)> {
+        self.nodes
+            .iter()
+            .map(|(id, node)| (id, node.name.as_str(), node.module.as_ref()))
+    }
+
+    /// Get a NodeId by module name
+    pub fn get_node_id_by_name(&self, name: &str) -> Option<NodeId> {
+        self.nodes
+            .iter()
+            .find(|(_, node)| node.name == name)
+            .map(|(id, _)| id)
+    }
+
+    /// Get a NodeHandle by module name
+    pub fn get_handle_by_name(&self, name: &str) -> Option<NodeHandle> {
+        self.nodes
+            .iter()
+            .find(|(_, node)| node.name == name)
+            .map(|(id, node)| NodeHandle::from_module(id, node.module.as_ref()))
+    }
+
+    /// Disconnect a cable by finding matching port refs
+    pub fn disconnect_ports(&mut self, from: PortRef, to: PortRef) -> Result<(), PatchError> {
+        let idx = self
+            .cables
+            .iter()
+            .position(|c| c.from == from && c.to == to)
+            .ok_or(PatchError::InvalidCable)?;
+
+        self.cables.remove(idx);
+        self.invalidate();
+        Ok(())
+    }
+
+    /// Get all module names
+    pub fn module_names(&self) -> Vec<&str> {
+        self.nodes
+            .iter()
+            .map(|(_, node)| node.name.as_str())
+            .collect()
+    }
+
+    /// Get the current output buffer value for a specific port
+    ///
+    /// This is used by the observer to collect real-time values for metering,
+    /// scope display, and other visualizations.
+    pub fn get_output_value(&self, node: NodeId, port: PortId) -> Option<f64> {
+        self.buffers.get(&PortRef { node, port }).copied()
+    }
+
+    /// Get the signal kind for an output port by node ID and port ID
+    pub fn get_output_signal_kind(&self, node: NodeId, port: PortId) -> Option<SignalKind> {
+        let node_data = self.nodes.get(node)?;
+        node_data
+            .module
+            .port_spec()
+            .outputs
+            .iter()
+            .find(|p| p.id == port)
+            .map(|p| p.kind)
+    }
+}
+
+/// Time taken to fade amounts, enables, and order swaps in an `FxChain`
+const FX_CHAIN_FADE_MS: f64 = 5.0;
+
+/// One effect in an `FxChain`
+struct FxSlot {
+    module: Box<dyn GraphModule>,
+    amount: f64,
+    enabled: bool,
+    // Smoothed wet amount actually applied (0 while disabled)
+    mix: f64,
+    audio_in: PortId,
+    audio_out: PortId,
+    inputs: PortValues,
+    outputs: PortValues,
+}
+
+impl FxSlot {
+    fn new(module: Box<dyn GraphModule>) -> Self {
+        let spec = module.port_spec();
+        let first_audio = |ports: &[crate::port::PortDef]| {
+            ports
+                .iter()
+                .find(|p| p.kind == SignalKind::Audio)
+                .or_else(|| ports.first())
+                .map(|p| p.id)
+                .unwrap_or(0)
+        };
+        let audio_in = first_audio(&spec.inputs);
+        let audio_out = first_audio(&spec.outputs);
+
+        // Unpatched controls sit at their defaults, as they would in a Patch
+        let mut inputs = PortValues::new();
+        for port in &spec.inputs {
+            inputs.set(port.id, port.default);
+        }
+
+        Self {
+            module,
+            amount: 1.0,
+            enabled: true,
+            mix: 1.0,
+            audio_in,
+            audio_out,
+            inputs,
+            outputs: PortValues::new(),
+        }
+    }
+
+    fn target_mix(&self) -> f64 {
+        if self.enabled {
+            self.amount
+        } else {
+            0.0
+        }
+    }
+}
+
+/// An ordered chain of mono effects with per-effect dry/wet and bypass
+///
+/// Unlike a `Chain` combinator, the order and mix of an `FxChain` can change
+/// at runtime. Each effect is fed from its first audio input and read from
+/// its first audio output; its other inputs stay at their defaults.
+///
+/// Amount and enable changes are smoothed. Reordering fades the chain to the
+/// dry signal, swaps the order at the bottom of the fade, and fades back in,
+/// so the new order never starts mid-waveform.
+pub struct FxChain {
+    slots: Vec<FxSlot>,
+    // Requested order (what the API indexes) and the order being processed
+    order: Vec<usize>,
+    running_order: Vec<usize>,
+    // Level of the processed chain versus the dry input during an order swap
+    wet_level: f64,
+    fade_step: f64,
+    spec: PortSpec,
+}
+
+impl FxChain {
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            slots: Vec::new(),
+            order: Vec::new(),
+            running_order: Vec::new(),
+            wet_level: 1.0,
+            fade_step: Self::fade_step_for(sample_rate),
+            spec: PortSpec {
+                inputs: vec![crate::port::PortDef::new(0, "in", SignalKind::Audio)],
+                outputs: vec![crate::port::PortDef::new(10, "out", SignalKind::Audio)],
+            },
+        }
+    }
+
+    fn fade_step_for(sample_rate: f64) -> f64 {
+        1000.0 / (FX_CHAIN_FADE_MS * sample_rate)
+    }
+
+    /// Builder: append an effect at full amount
+    pub fn with_effect<M: GraphModule + 'static>(mut self, module: M) -> Self {
+        self.push(Box::new(module));
+        self
+    }
+
+    /// Append an effect at the end of the chain
+    pub fn push(&mut self, module: Box<dyn GraphModule>) {
+        self.slots.push(FxSlot::new(module));
+        self.order.push(self.slots.len() - 1);
+        self.running_order.push(self.slots.len() - 1);
+    }
+
+    /// Remove the effect at `index`, returning it
+    pub fn remove(&mut self, index: usize) -> Result<Box<dyn GraphModule>, PatchError> {
+        let slot = *self.order.get(index).ok_or(PatchError::InvalidNode)?;
+        for order in [&mut self.order, &mut self.running_order] {
+            order.retain(|&s| s != slot);
+            for s in order.iter_mut().filter(|s| **s > slot) {
+                *s -= 1;
+            }
+        }
+        Ok(self.slots.remove(slot).module)
+    }
+
+    /// Move the effect at position `from` to position `to`
+    pub fn reorder(&mut self, from: usize, to: usize) -> Result<(), PatchError> {
+        if from >= self.order.len() || to >= self.order.len() {
+            return Err(PatchError::InvalidNode);
+        }
+        let slot = self.order.remove(from);
+        self.order.insert(to, slot);
+        Ok(())
+    }
+
+    /// Set the dry/wet amount of the effect at `index` (0 = dry, 1 = wet)
+    pub fn set_amount(&mut self, index: usize, amount: f64) -> Result<(), PatchError> {
+        let slot = *self.order.get(index).ok_or(PatchError::InvalidNode)?;
+        self.slots[slot].amount = amount.clamp(0.0, 1.0);
+        Ok(())
+    }
+
+    pub fn amount(&self, index: usize) -> Option<f64> {
+        self.order.get(index).map(|&s| self.slots[s].amount)
+    }
+
+    /// Enable or bypass the effect at `index`
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) -> Result<(), PatchError> {
+        let slot = *self.order.get(index).ok_or(PatchError::InvalidNode)?;
+        self.slots[slot].enabled = enabled;
+        Ok(())
+    }
+
+    pub fn is_enabled(&self, index: usize) -> Option<bool> {
+        self.order.get(index).map(|&s| self.slots[s].enabled)
+    }
+
+    /// The effect at `index`
+    pub fn effect(&self, index: usize) -> Option<&dyn GraphModule> {
+        self.order
+            .get(index)
+            .map(|&s| self.slots[s].module.as_ref())
+    }
+
+    pub fn effect_mut(&mut self, index: usize) -> Option<&mut Box<dyn GraphModule>> {
+        let slot = *self.order.get(index)?;
+        Some(&mut self.slots[slot].module)
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Whether a reorder is still fading to the new order
+    pub fn is_swapping(&self) -> bool {
+        self.order != self.running_order || self.wet_level < 1.0
+    }
+
+    /// Process one sample through the chain
+    pub fn process(&mut self, input: f64) -> f64 {
+        let swap_pending = self.order != self.running_order;
+        if swap_pending {
+            self.wet_level = (self.wet_level - self.fade_step).max(0.0);
+            if self.wet_level == 0.0 {
+                self.running_order.clone_from(&self.order);
+            }
+        } else {
+            self.wet_level = (self.wet_level + self.fade_step).min(1.0);
+        }
+
+        let mut signal = input;
+        for &s in &self.running_order {
+            let slot = &mut self.slots[s];
+            let target = slot.target_mix();
+            slot.mix += (target - slot.mix).clamp(-self.fade_step, self.fade_step);
+            if slot.mix == 0.0 && target == 0.0 {
+                continue;
+            }
+
+            slot.inputs.set(slot.audio_in, signal);
+            slot.module.tick(&slot.inputs, &mut slot.outputs);
+            let wet = slot.outputs.get_or(slot.audio_out, 0.0);
+            signal += (wet - signal) * slot.mix;
+        }
+
+        input + (signal - input) * self.wet_level
+    }
+}
+
+impl GraphModule for FxChain {
+    fn port_spec(&self) -> &PortSpec {
+        &self.spec
+    }
+
+    fn tick(&mut self, inputs: &PortValues, outputs: &mut PortValues) {
+        let out = self.process(inputs.get_or(0, 0.0));
+        outputs.set(10, out);
+    }
+
+    fn reset(&mut self) {
+        self.running_order.clone_from(&self.order);
+        self.wet_level = 1.0;
+        for slot in &mut self.slots {
+            slot.module.reset();
+            slot.mix = slot.target_mix();
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.fade_step = Self::fade_step_for(sample_rate);
+        for slot in &mut self.slots {
+            slot.module.set_sample_rate(sample_rate);
+        }
+    }
+
+    fn sync_transport(&mut self, transport: &Transport) {
+        for slot in &mut self.slots {
+            slot.module.sync_transport(transport);
+        }
+    }
+
+    fn set_rng(&mut self, mut rng: Rng) {
+        for slot in &mut self.slots {
+            slot.module.set_rng(Rng::from_seed(rng.next_u64()));
+        }
+    }
+
+    fn type_id(&self) -> &'static str {
+        "fx_chain"
+    }
+
+    fn supports_runtime_state(&self) -> bool {
+        self.slots
+            .iter()
+            .all(|slot| slot.module.supports_runtime_state())
+    }
+
+    fn serialize_runtime(&self, state: &mut RuntimeWriter) {
+        // The order mid-swap, which differs from `order` until the fade completes
+        for &slot in &self.running_order {
+            state.put_usize(slot);
+        }
+        state.put_f64(self.wet_level);
+        for slot in &self.slots {
+            let mut inner = RuntimeWriter::new();
+            slot.module.serialize_runtime(&mut inner);
+            state.put_f64(slot.mix);
+            state.put_bytes(&inner.into_bytes());
+        }
+    }
+
+    fn deserialize_runtime(&mut self, state: &mut RuntimeReader<'_>) -> Result<(), String> {
+        let mut running_order = Vec::with_capacity(self.slots.len());
+        for _ in 0..self.slots.len() {
+            let slot = state.usize()?;
+            if slot >= self.slots.len() || running_order.contains(&slot) {
+                return Err(format!("invalid effect order entry {}", slot));
+            }
+            running_order.push(slot);
+        }
+        self.running_order = running_order;
+        self.wet_level = state.f64()?;
+        for slot in &mut self.slots {
+            slot.mix = state.f64()?;
+            let mut inner = RuntimeReader::new(state.bytes()?);
+            slot.module.deserialize_runtime(&mut inner)?;
+            inner.finish()?;
+        }
+        Ok(())
+    }
+
+    fn cost_hint(&self) -> u32 {
+        self.slots
+            .iter()
+            .map(|slot| slot.module.cost_hint())
+            .sum::<u32>()
+            .max(1)
+    }
+}
+
+/// A patch wrapped as a single module
+///
+/// Encapsulates a reusable sub-graph, such as a voice, so it can be added to
+/// a parent patch (or several times over) like any other module. The
+/// `SubPatch` has only the ports declared with
+/// [`expose_input`](Self::expose_input) and
+/// [`expose_output`](Self::expose_output): inputs take IDs from 0 and
+/// outputs from 100, in the order they are exposed. Each tick writes the
+/// outer inputs onto the inner ports, ticks the inner patch once, and reads
+/// the exposed outputs.
+///
+/// The inner patch runs one sample at a time, at the rate set on the
+/// `SubPatch`. Its own output node and transport are not used.
+pub struct SubPatch {
+    patch: Patch,
+    inputs: Vec<PortRef>,
+    outputs: Vec<PortRef>,
+    spec: PortSpec,
+}
+
+impl SubPatch {
+    /// ID of the first exposed output
+    pub const OUTPUT_BASE: PortId = 100;
+
+    /// Wrap a patch, compiling it
+    pub fn new(mut patch: Patch) -> Result<Self, PatchError> {
+        patch.set_block_size(1);
+        patch.compile()?;
+        Ok(Self {
+            patch,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            spec: PortSpec {
+                inputs: Vec::new(),
+                outputs: Vec::new(),
+            },
+        })
+    }
+
+    /// Builder: expose an unconnected inner input as an input of this module
+    ///
+    /// The outer port copies the inner port's kind, default, and
+    /// attenuverter flag. Fails with `InvalidPort` if the port does not
+    /// exist or already has a cable inside the patch.
+    pub fn expose_input(
+        mut self,
+        node: NodeId,
+        port: PortId,
+        name: impl Into<String>,
+    ) -> Result<Self, PatchError> {
+        let port_ref = PortRef { node, port };
+        let inner = self
+            .patch
+            .nodes
+            .get(node)
+            .ok_or(PatchError::InvalidNode)?
+            .module
+            .port_spec()
+            .inputs
+            .iter()
+            .find(|p| p.id == port)
+            .ok_or(PatchError::InvalidPort)?;
+        if self.patch.all_cables().any(|cable| cable.to == port_ref) {
+            return Err(PatchError::InvalidPort);
+        }
+
+        let mut def =
+            PortDef::new(self.inputs.len() as PortId, name, inner.kind).with_default(inner.default);
+        def.has_attenuverter = inner.has_attenuverter;
+        self.patch.external_inputs.insert(port_ref, inner.default);
+        self.spec.inputs.push(def);
+        self.inputs.push(port_ref);
+        Ok(self)
+    }
+
+    /// Builder: expose an inner output as an output of this module
+    pub fn expose_output(
+        mut self,
+        node: NodeId,
+        port: PortId,
+        name: impl Into<String>,
+    ) -> Result<Self, PatchError> {
+        let kind = self
+            .patch
+            .get_output_signal_kind(node, port)
+            .ok_or(PatchError::InvalidPort)?;
+        let id = Self::OUTPUT_BASE + self.outputs.len() as PortId;
+        self.spec.outputs.push(PortDef::new(id, name, kind));
+        self.outputs.push(PortRef { node, port });
+        Ok(self)
+    }
+
+    /// The wrapped patch
+    pub fn patch(&self) -> &Patch {
+        &self.patch
+    }
+
+    /// The wrapped patch, e.g. for setting parameters
+    ///
+    /// Recompile after rewiring it, and leave its block size at 1.
+    pub fn patch_mut(&mut self) -> &mut Patch {
+        &mut self.patch
+    }
+}
+
+impl GraphModule for SubPatch {
+    fn port_spec(&self) -> &PortSpec {
+        &self.spec
+    }
+
+    fn tick(&mut self, inputs: &PortValues, outputs: &mut PortValues) {
+        for (def, port) in self.spec.inputs.iter().zip(&self.inputs) {
+            let value = inputs.get_or(def.id, def.default);
+            self.patch.external_inputs.insert(*port, value);
+        }
+        self.patch.tick();
+        for (def, port) in self.spec.outputs.iter().zip(&self.outputs) {
+            let value = self.patch.get_output_value(port.node, port.port);
+            outputs.set(def.id, value.unwrap_or(0.0));
+        }
+    }
+
+    fn reset(&mut self) {
+        self.patch.reset();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.patch.set_sample_rate(sample_rate);
+    }
+
+    fn set_rng(&mut self, mut rng: Rng) {
+        self.patch.set_seed(rng.next_u64());
+    }
+
+    fn type_id(&self) -> &'static str {
+        "sub_patch"
+    }
+
+    fn supports_runtime_state(&self) -> bool {
+        self.patch.supports_runtime_state()
+    }
+
+    fn serialize_runtime(&self, state: &mut RuntimeWriter) {
+        state.put_bytes(&self.patch.write_runtime_state());
+    }
+
+    fn deserialize_runtime(&mut self, state: &mut RuntimeReader<'_>) -> Result<(), String> {
+        self.patch
+            .restore_runtime_state(state.bytes()?)
+            .map_err(|e| e.to_string())
+    }
+
+    fn latency_samples(&self) -> usize {
+        self.outputs
+            .iter()
+            .map(|port| self.patch.latencies.get(&port.node).copied().unwrap_or(0))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn cost_hint(&self) -> u32 {
+        self.patch
+            .nodes
+            .values()
+            .map(|node| node.module.cost_hint())
+            .sum::<u32>()
+            .max(1)
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::port::{PortDef, SignalKind};
 
-    // Simple passthrough module for testing
-    struct Passthrough {
+    // Simple passthrough module for testing
+    struct Passthrough {
+        spec: PortSpec,
+    }
+
+    impl Passthrough {
+        fn new() -> Self {
+            Self {
+                spec: PortSpec {
+                    inputs: vec![PortDef::new(0, "in", SignalKind::Audio)],
+                    outputs: vec![PortDef::new(10, "out", SignalKind::Audio)],
+                },
+            }
+        }
+    }
+
+    impl GraphModule for Passthrough {
+        fn port_spec(&self) -> &PortSpec {
+            &self.spec
+        }
+
+        fn tick(&mut self, inputs: &PortValues, outputs: &mut PortValues) {
+            let input = inputs.get_or(0, 0.0);
+            outputs.set(10, input);
+        }
+
+        fn reset(&mut self) {}
+
+        fn set_sample_rate(&mut self, _: f64) {}
+    }
+
+    #[test]
+    fn test_add_module() {
+        let mut patch = Patch::new(44100.0);
+        let handle = patch.add("test", Passthrough::new());
+        assert_eq!(patch.node_count(), 1);
+        assert!(patch.get_name(handle.id()).is_some());
+    }
+
+    #[test]
+    fn test_connect() {
+        let mut patch = Patch::new(44100.0);
+        let a = patch.add("a", Passthrough::new());
+        let b = patch.add("b", Passthrough::new());
+
+        let result = patch.connect(a.out("out"), b.in_("in"));
+        assert!(result.is_ok());
+        assert_eq!(patch.cable_count(), 1);
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let mut patch = Patch::new(44100.0);
+        let a = patch.add("a", Passthrough::new());
+        let b = patch.add("b", Passthrough::new());
+        let c = patch.add("c", Passthrough::new());
+
+        // A -> B -> C
+        patch.connect(a.out("out"), b.in_("in")).unwrap();
+        patch.connect(b.out("out"), c.in_("in")).unwrap();
+
+        patch.compile().unwrap();
+
+        let order = patch.execution_order();
+        let a_pos = order.iter().position(|&x| x == a.id()).unwrap();
+        let b_pos = order.iter().position(|&x| x == b.id()).unwrap();
+        let c_pos = order.iter().position(|&x| x == c.id()).unwrap();
+
+        assert!(a_pos < b_pos, "A should come before B");
+        assert!(b_pos < c_pos, "B should come before C");
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let mut patch = Patch::new(44100.0);
+        let a = patch.add("a", Passthrough::new());
+        let b = patch.add("b", Passthrough::new());
+
+        // Create cycle: A -> B -> A
+        patch.connect(a.out("out"), b.in_("in")).unwrap();
+        patch.connect(b.out("out"), a.in_("in")).unwrap();
+
+        let result = patch.compile();
+        assert!(matches!(
+            result,
+            Err(PatchError::UnbrokenFeedbackLoop { ref nodes }) if nodes.len() == 2
+        ));
+    }
+
+    #[test]
+    fn test_feedback_through_unit_delay_compiles() {
+        use crate::modules::{Mixer, Offset, StereoOutput, UnitDelay};
+
+        let mut patch = Patch::new(44100.0);
+        let src = patch.add("src", Offset::new(1.0));
+        let mix = patch.add("mix", Mixer::new(2));
+        let delay = patch.add("delay", UnitDelay::new());
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(src.out("out"), mix.in_("ch0")).unwrap();
+        patch.connect(mix.out("out"), delay.in_("in")).unwrap();
+        patch
+            .connect_attenuated(delay.out("out"), mix.in_("ch1"), 0.5)
+            .unwrap();
+        patch.connect(mix.out("out"), out.in_("left")).unwrap();
+        patch.set_output(out.id());
+        patch.set_block_size(16);
+        patch.compile().unwrap();
+
+        // y[n] = 1 + 0.5 * y[n - 1]: the loop adds only the delay's sample
+        let output: Vec<f64> = (0..6).map(|_| patch.tick().0).collect();
+        assert_eq!(output, [1.0, 1.5, 1.75, 1.875, 1.9375, 1.96875]);
+    }
+
+    #[test]
+    fn test_unbroken_feedback_loop_is_rejected() {
+        use crate::modules::StereoOutput;
+
+        let mut patch = Patch::new(44100.0);
+        let a = patch.add("a", Passthrough::new());
+        let b = patch.add("b", Passthrough::new());
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(a.out("out"), b.in_("in")).unwrap();
+        patch.connect(b.out("out"), a.in_("in")).unwrap();
+        patch.connect(b.out("out"), out.in_("left")).unwrap();
+
+        // Only the loop itself is reported, not the output it feeds
+        match patch.compile() {
+            Err(PatchError::UnbrokenFeedbackLoop { mut nodes }) => {
+                nodes.sort();
+                let mut expected = vec![a.id(), b.id()];
+                expected.sort();
+                assert_eq!(nodes, expected);
+            }
+            other => panic!("expected an unbroken loop, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cable_gain_and_offset() {
+        use crate::modules::{Offset, StereoOutput};
+
+        let mut patch = Patch::new(44100.0);
+        let src = patch.add("src", Offset::new(3.0));
+        let out = patch.add("out", StereoOutput::new());
+        let cable = patch.connect(src.out("out"), out.in_("left")).unwrap();
+        patch.set_output(out.id());
+        patch.compile().unwrap();
+        assert_eq!(patch.tick().0, 3.0);
+
+        patch.set_cable_gain(cable, 1.0).unwrap();
+        patch.set_cable_offset(cable, 0.0).unwrap();
+        assert_eq!(patch.tick().0, 3.0);
+
+        patch.set_cable_gain(cable, 0.5).unwrap();
+        patch.set_cable_offset(cable, 1.0).unwrap();
+        assert_eq!(patch.tick().0, 2.5);
+
+        assert!(matches!(
+            patch.set_cable_gain(cable + 1, 0.5),
+            Err(PatchError::InvalidCable)
+        ));
+    }
+
+    #[test]
+    fn test_merge_modes_with_two_cables() {
+        use crate::modules::Offset;
+
+        // Echoes one input per merge mode to the output with the same index
+        struct Merger {
+            spec: PortSpec,
+        }
+
+        impl GraphModule for Merger {
+            fn port_spec(&self) -> &PortSpec {
+                &self.spec
+            }
+
+            fn tick(&mut self, inputs: &PortValues, outputs: &mut PortValues) {
+                for port in 0..4 {
+                    outputs.set(10 + port, inputs.get_or(port, 0.0));
+                }
+            }
+
+            fn reset(&mut self) {}
+
+            fn set_sample_rate(&mut self, _: f64) {}
+        }
+
+        let modes = [
+            MergeMode::Sum,
+            MergeMode::Average,
+            MergeMode::Max,
+            MergeMode::Replace,
+        ];
+        let spec = PortSpec {
+            inputs: (0..4)
+                .map(|i| {
+                    PortDef::new(i, format!("in{}", i), SignalKind::CvBipolar)
+                        .with_merge_mode(modes[i as usize])
+                })
+                .collect(),
+            outputs: (0..4)
+                .map(|i| PortDef::new(10 + i, format!("out{}", i), SignalKind::CvBipolar))
+                .collect(),
+        };
+
+        for block_size in [1, 8] {
+            let mut patch = Patch::new(44100.0);
+            let high = patch.add("high", Offset::new(3.0));
+            let low = patch.add("low", Offset::new(1.0));
+            let merge = patch.add("merge", Merger { spec: spec.clone() });
+            for port in 0..4 {
+                let to = PortRef {
+                    node: merge.id(),
+                    port,
+                };
+                patch.connect(high.out("out"), to).unwrap();
+                patch.connect(low.out("out"), to).unwrap();
+            }
+            patch.set_block_size(block_size);
+            patch.compile().unwrap();
+            patch.tick();
+
+            let read = |port: PortId| patch.get_output_value(merge.id(), 10 + port).unwrap();
+            assert_eq!(read(0), 4.0, "sum");
+            assert_eq!(read(1), 2.0, "average");
+            assert_eq!(read(2), 3.0, "max");
+            assert_eq!(read(3), 1.0, "replace");
+        }
+    }
+
+    #[test]
+    fn test_normalled_input_follows_source_only_when_unpatched() {
+        use crate::modules::Offset;
+
+        // Input B is normalled to input A; both are echoed to the outputs
+        struct Dual {
+            spec: PortSpec,
+        }
+
+        impl GraphModule for Dual {
+            fn port_spec(&self) -> &PortSpec {
+                &self.spec
+            }
+
+            fn tick(&mut self, inputs: &PortValues, outputs: &mut PortValues) {
+                outputs.set(10, inputs.get_or(0, 0.0));
+                outputs.set(11, inputs.get_or(1, 0.0));
+            }
+
+            fn reset(&mut self) {}
+
+            fn set_sample_rate(&mut self, _: f64) {}
+        }
+
+        for block_size in [1, 8] {
+            let mut patch = Patch::new(44100.0);
+            let source = patch.add("source", Offset::new(2.0));
+            let zero = patch.add("zero", Offset::new(0.0));
+            let dual = patch.add(
+                "dual",
+                Dual {
+                    spec: PortSpec {
+                        inputs: vec![
+                            PortDef::new(0, "a", SignalKind::CvBipolar),
+                            PortDef::new(1, "b", SignalKind::CvBipolar).normalled_to(0),
+                        ],
+                        outputs: vec![
+                            PortDef::new(10, "a", SignalKind::CvBipolar),
+                            PortDef::new(11, "b", SignalKind::CvBipolar),
+                        ],
+                    },
+                },
+            );
+            patch.connect(source.out("out"), dual.in_("a")).unwrap();
+            patch.set_block_size(block_size);
+            patch.compile().unwrap();
+
+            // Unpatched B picks up A within the same sample
+            patch.tick();
+            assert_eq!(patch.get_output_value(dual.id(), 11), Some(2.0));
+
+            // A cable breaks the normal, even one carrying zero
+            let cable = patch.connect(zero.out("out"), dual.in_("b")).unwrap();
+            patch.compile().unwrap();
+            patch.tick();
+            assert_eq!(patch.get_output_value(dual.id(), 11), Some(0.0));
+
+            patch.disconnect(cable).unwrap();
+            patch.compile().unwrap();
+            patch.tick();
+            assert_eq!(patch.get_output_value(dual.id(), 11), Some(2.0));
+        }
+    }
+
+    #[test]
+    fn test_mult() {
+        let mut patch = Patch::new(44100.0);
+        let a = patch.add("a", Passthrough::new());
+        let b = patch.add("b", Passthrough::new());
+        let c = patch.add("c", Passthrough::new());
+
+        let result = patch.mult(a.out("out"), &[b.in_("in"), c.in_("in")]);
+        assert!(result.is_ok());
+        assert_eq!(patch.cable_count(), 2);
+    }
+
+    #[test]
+    fn test_disconnect() {
+        let mut patch = Patch::new(44100.0);
+        let a = patch.add("a", Passthrough::new());
+        let b = patch.add("b", Passthrough::new());
+
+        let cable_id = patch.connect(a.out("out"), b.in_("in")).unwrap();
+        assert_eq!(patch.cable_count(), 1);
+
+        patch.disconnect(cable_id).unwrap();
+        assert_eq!(patch.cable_count(), 0);
+    }
+
+    #[test]
+    fn test_remove_module() {
+        let mut patch = Patch::new(44100.0);
+        let a = patch.add("a", Passthrough::new());
+        let b = patch.add("b", Passthrough::new());
+
+        patch.connect(a.out("out"), b.in_("in")).unwrap();
+        assert_eq!(patch.node_count(), 2);
+        assert_eq!(patch.cable_count(), 1);
+
+        patch.remove(a.id()).unwrap();
+        assert_eq!(patch.node_count(), 1);
+        assert_eq!(patch.cable_count(), 0); // Cable should be removed too
+    }
+
+    // ========================================================================
+    // Phase 2 Tests: Signal Validation & Modulation
+    // ========================================================================
+
+    // Test modules with different signal types
+    struct GateModule {
         spec: PortSpec,
     }
 
-    impl Passthrough {
+    impl GateModule {
         fn new() -> Self {
             Self {
                 spec: PortSpec {
-                    inputs: vec![PortDef::new(0, "in", SignalKind::Audio)],
-                    outputs: vec![PortDef::new(10, "out", SignalKind::Audio)],
+                    inputs: vec![PortDef::new(0, "in", SignalKind::Gate)],
+                    outputs: vec![PortDef::new(10, "out", SignalKind::Gate)],
                 },
             }
         }
     }
 
-    impl GraphModule for Passthrough {
+    impl GraphModule for GateModule {
         fn port_spec(&self) -> &PortSpec {
             &self.spec
         }
-
         fn tick(&mut self, inputs: &PortValues, outputs: &mut PortValues) {
-            let input = inputs.get_or(0, 0.0);
-            outputs.set(10, input);
+            outputs.set(10, inputs.get_or(0, 0.0));
         }
-
         fn reset(&mut self) {}
-
         fn set_sample_rate(&mut self, _: f64) {}
     }
 
     #[test]
-    fn test_add_module() {
+    fn test_validation_mode_none() {
         let mut patch = Patch::new(44100.0);
-        let handle = patch.add("test", Passthrough::new());
-        assert_eq!(patch.node_count(), 1);
-        assert!(patch.get_name(handle.id()).is_some());
+        patch.set_validation_mode(ValidationMode::None);
+
+        let audio = patch.add("audio", Passthrough::new());
+        let gate = patch.add("gate", GateModule::new());
+
+        // Should succeed without warnings
+        let result = patch.connect(audio.out("out"), gate.in_("in"));
+        assert!(result.is_ok());
+        assert!(patch.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_validation_mode_warn() {
+        let mut patch = Patch::new(44100.0);
+        patch.set_validation_mode(ValidationMode::Warn);
+
+        let audio = patch.add("audio", Passthrough::new());
+        let gate = patch.add("gate", GateModule::new());
+
+        // Should succeed but generate warning
+        let result = patch.connect(audio.out("out"), gate.in_("in"));
+        assert!(result.is_ok());
+        assert!(!patch.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_validation_mode_strict() {
+        let mut patch = Patch::new(44100.0);
+        patch.set_validation_mode(ValidationMode::Strict);
+
+        let audio = patch.add("audio", Passthrough::new());
+        let gate = patch.add("gate", GateModule::new());
+
+        // Should fail with SignalMismatch error
+        let result = patch.connect(audio.out("out"), gate.in_("in"));
+        assert!(matches!(result, Err(PatchError::SignalMismatch { .. })));
+    }
+
+    #[test]
+    fn test_same_signal_type_no_warning() {
+        let mut patch = Patch::new(44100.0);
+        patch.set_validation_mode(ValidationMode::Warn);
+
+        let a = patch.add("a", Passthrough::new());
+        let b = patch.add("b", Passthrough::new());
+
+        // Same type should not generate warning
+        let result = patch.connect(a.out("out"), b.in_("in"));
+        assert!(result.is_ok());
+        assert!(patch.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_connect_modulated() {
+        let mut patch = Patch::new(44100.0);
+        let a = patch.add("a", Passthrough::new());
+        let b = patch.add("b", Passthrough::new());
+
+        // Connect with attenuation 0.5 and offset 1.0
+        let result = patch.connect_modulated(a.out("out"), b.in_("in"), 0.5, 1.0);
+        assert!(result.is_ok());
+
+        let cables = patch.cables();
+        assert_eq!(cables.len(), 1);
+        assert_eq!(cables[0].attenuation, Some(0.5));
+        assert_eq!(cables[0].offset, Some(1.0));
+    }
+
+    #[test]
+    fn test_modulated_signal_processing() {
+        let mut patch = Patch::new(44100.0);
+
+        // Use a module that outputs a constant value
+        struct ConstModule {
+            spec: PortSpec,
+            value: f64,
+        }
+
+        impl ConstModule {
+            fn new(value: f64) -> Self {
+                Self {
+                    value,
+                    spec: PortSpec {
+                        inputs: vec![],
+                        outputs: vec![PortDef::new(10, "out", SignalKind::Audio)],
+                    },
+                }
+            }
+        }
+
+        impl GraphModule for ConstModule {
+            fn port_spec(&self) -> &PortSpec {
+                &self.spec
+            }
+            fn tick(&mut self, _: &PortValues, outputs: &mut PortValues) {
+                outputs.set(10, self.value);
+            }
+            fn reset(&mut self) {}
+            fn set_sample_rate(&mut self, _: f64) {}
+        }
+
+        struct RecordModule {
+            spec: PortSpec,
+            last_value: f64,
+        }
+
+        impl RecordModule {
+            fn new() -> Self {
+                Self {
+                    spec: PortSpec {
+                        inputs: vec![PortDef::new(0, "in", SignalKind::Audio)],
+                        outputs: vec![],
+                    },
+                    last_value: 0.0,
+                }
+            }
+        }
+
+        impl GraphModule for RecordModule {
+            fn port_spec(&self) -> &PortSpec {
+                &self.spec
+            }
+            fn tick(&mut self, inputs: &PortValues, _: &mut PortValues) {
+                self.last_value = inputs.get_or(0, 0.0);
+            }
+            fn reset(&mut self) {}
+            fn set_sample_rate(&mut self, _: f64) {}
+        }
+
+        let source = patch.add("source", ConstModule::new(4.0));
+        let sink = patch.add("sink", RecordModule::new());
+
+        // Attenuation 0.5, offset 2.0: 4.0 * 0.5 + 2.0 = 4.0
+        patch
+            .connect_modulated(source.out("out"), sink.in_("in"), 0.5, 2.0)
+            .unwrap();
+        patch.set_output(sink.id());
+        patch.compile().unwrap();
+        patch.tick();
+
+        // The value should be processed through attenuation and offset
+        // We can't easily check the internal value, but we verified the connection works
+    }
+
+    #[test]
+    fn test_signal_compatibility() {
+        // Test specific compatibility cases
+        assert!(SignalKind::Audio
+            .is_compatible_with(&SignalKind::Audio)
+            .warning
+            .is_none());
+        assert!(SignalKind::Audio
+            .is_compatible_with(&SignalKind::CvBipolar)
+            .warning
+            .is_some());
+        assert!(SignalKind::Gate
+            .is_compatible_with(&SignalKind::Trigger)
+            .warning
+            .is_some());
+        assert!(SignalKind::Clock
+            .is_compatible_with(&SignalKind::Trigger)
+            .warning
+            .is_none());
+    }
+
+    #[test]
+    fn test_patch_get_name() {
+        let mut patch = Patch::new(44100.0);
+        let a = patch.add("my_module", Passthrough::new());
+
+        let name = patch.get_name(a.id());
+        assert_eq!(name, Some("my_module"));
+
+        // Non-existent node
+        use slotmap::DefaultKey;
+        let fake_id: NodeId = DefaultKey::default();
+        assert!(patch.get_name(fake_id).is_none());
     }
 
     #[test]
-    fn test_connect() {
+    fn test_patch_set_position() {
         let mut patch = Patch::new(44100.0);
         let a = patch.add("a", Passthrough::new());
-        let b = patch.add("b", Passthrough::new());
 
-        let result = patch.connect(a.out("out"), b.in_("in"));
-        assert!(result.is_ok());
-        assert_eq!(patch.cable_count(), 1);
+        patch.set_position(a.id(), (100.0, 200.0));
+        // Position is stored but not exposed directly in tests
     }
 
     #[test]
-    fn test_topological_sort() {
+    fn test_patch_clear_warnings() {
         let mut patch = Patch::new(44100.0);
-        let a = patch.add("a", Passthrough::new());
-        let b = patch.add("b", Passthrough::new());
-        let c = patch.add("c", Passthrough::new());
+        patch.set_validation_mode(ValidationMode::Warn);
 
-        // A -> B -> C
-        patch.connect(a.out("out"), b.in_("in")).unwrap();
-        patch.connect(b.out("out"), c.in_("in")).unwrap();
+        let audio = patch.add("audio", Passthrough::new());
+        let gate = patch.add("gate", GateModule::new());
 
-        patch.compile().unwrap();
+        patch.connect(audio.out("out"), gate.in_("in")).unwrap();
+        assert!(!patch.warnings().is_empty());
 
-        let order = patch.execution_order();
-        let a_pos = order.iter().position(|&x| x == a.id()).unwrap();
-        let b_pos = order.iter().position(|&x| x == b.id()).unwrap();
-        let c_pos = order.iter().position(|&x| x == c.id()).unwrap();
+        patch.clear_warnings();
+        assert!(patch.warnings().is_empty());
+    }
 
-        assert!(a_pos < b_pos, "A should come before B");
-        assert!(b_pos < c_pos, "B should come before C");
+    #[test]
+    fn test_patch_validation_mode_getter() {
+        let mut patch = Patch::new(44100.0);
+        patch.set_validation_mode(ValidationMode::Strict);
+        assert_eq!(patch.validation_mode(), ValidationMode::Strict);
     }
 
     #[test]
-    fn test_cycle_detection() {
+    fn test_patch_sample_rate() {
+        let patch = Patch::new(48000.0);
+        assert_eq!(patch.sample_rate(), 48000.0);
+    }
+
+    #[test]
+    fn test_patch_execution_order() {
         let mut patch = Patch::new(44100.0);
         let a = patch.add("a", Passthrough::new());
         let b = patch.add("b", Passthrough::new());
-
-        // Create cycle: A -> B -> A
         patch.connect(a.out("out"), b.in_("in")).unwrap();
-        patch.connect(b.out("out"), a.in_("in")).unwrap();
+        patch.compile().unwrap();
 
-        let result = patch.compile();
-        assert!(matches!(result, Err(PatchError::CycleDetected { .. })));
+        let order = patch.execution_order();
+        assert_eq!(order.len(), 2);
     }
 
     #[test]
-    fn test_mult() {
+    fn test_patch_mult() {
         let mut patch = Patch::new(44100.0);
         let a = patch.add("a", Passthrough::new());
         let b = patch.add("b", Passthrough::new());
         let c = patch.add("c", Passthrough::new());
 
+        // Connect one output to multiple inputs
         let result = patch.mult(a.out("out"), &[b.in_("in"), c.in_("in")]);
         assert!(result.is_ok());
         assert_eq!(patch.cable_count(), 2);
     }
 
     #[test]
-    fn test_disconnect() {
+    fn test_patch_reset() {
         let mut patch = Patch::new(44100.0);
         let a = patch.add("a", Passthrough::new());
-        let b = patch.add("b", Passthrough::new());
+        patch.set_output(a.id());
+        patch.compile().unwrap();
 
-        let cable_id = patch.connect(a.out("out"), b.in_("in")).unwrap();
-        assert_eq!(patch.cable_count(), 1);
+        for _ in 0..100 {
+            patch.tick();
+        }
 
-        patch.disconnect(cable_id).unwrap();
-        assert_eq!(patch.cable_count(), 0);
+        patch.reset();
+        // Reset clears internal state
     }
 
     #[test]
-    fn test_remove_module() {
+    fn test_patch_set_param_get_param() {
+        use crate::modules::Vco;
         let mut patch = Patch::new(44100.0);
-        let a = patch.add("a", Passthrough::new());
-        let b = patch.add("b", Passthrough::new());
-
-        patch.connect(a.out("out"), b.in_("in")).unwrap();
-        assert_eq!(patch.node_count(), 2);
-        assert_eq!(patch.cable_count(), 1);
+        let vco = patch.add("vco", Vco::new(44100.0));
 
-        patch.remove(a.id()).unwrap();
-        assert_eq!(patch.node_count(), 1);
-        assert_eq!(patch.cable_count(), 0); // Cable should be removed too
+        // Try to set/get param (may or may not have params)
+        patch.set_param(vco.id(), 0, 0.5);
+        let _ = patch.get_param(vco.id(), 0);
     }
 
-    // ========================================================================
-    // Phase 2 Tests: Signal Validation & Modulation
-    // ========================================================================
+    #[test]
+    fn test_node_handle_spec() {
+        let mut patch = Patch::new(44100.0);
+        let a = patch.add("a", Passthrough::new());
 
-    // Test modules with different signal types
-    struct GateModule {
-        spec: PortSpec,
+        let spec = a.spec();
+        assert!(!spec.inputs.is_empty());
+        assert!(!spec.outputs.is_empty());
     }
 
-    impl GateModule {
-        fn new() -> Self {
-            Self {
-                spec: PortSpec {
-                    inputs: vec![PortDef::new(0, "in", SignalKind::Gate)],
-                    outputs: vec![PortDef::new(10, "out", SignalKind::Gate)],
-                },
-            }
+    #[test]
+    fn test_transport_drives_tempo_synced_lfo() {
+        use crate::modules::Lfo;
+
+        // 120 BPM at 1kHz = 500 samples per beat, LFO cycle = 4 beats
+        let sample_rate = 1000.0;
+        let mut patch = Patch::new(sample_rate);
+        let lfo = patch.add("lfo", Lfo::new(sample_rate).with_tempo_sync(4.0));
+        let mut transport = Transport::new(120.0, 44100.0);
+        transport.play();
+        patch.set_transport(transport);
+        patch.compile().unwrap();
+
+        for _ in 0..1250 {
+            patch.tick();
         }
+
+        let transport = patch.transport().unwrap();
+        assert_eq!(transport.sample_rate(), sample_rate);
+        assert!((transport.position_beats() - 2.5).abs() < 1e-9);
+
+        // The last tick saw the transport one sample before the current position
+        let beats_seen = 1249.0 / 500.0;
+        let expected_saw = (2.0 * (beats_seen / 4.0) - 1.0) * 5.0;
+        let saw = patch.get_output_value(lfo.id(), 12).unwrap();
+        assert!(
+            (saw - expected_saw).abs() < 1e-6,
+            "saw {} != {}",
+            saw,
+            expected_saw
+        );
     }
 
-    impl GraphModule for GateModule {
-        fn port_spec(&self) -> &PortSpec {
-            &self.spec
+    #[test]
+    fn test_transport_loop_repeats_lfo_phase() {
+        use crate::modules::Lfo;
+
+        let sample_rate = 1000.0;
+        let mut patch = Patch::new(sample_rate);
+        let lfo = patch.add("lfo", Lfo::new(sample_rate).with_tempo_sync(1.5));
+        let mut transport = Transport::new(120.0, sample_rate);
+        transport.set_loop_beats(0.0, 2.0);
+        transport.play();
+        patch.set_transport(transport);
+        patch.compile().unwrap();
+
+        // One full loop is 1000 samples; the same loop offset gives the same phase
+        let mut first_pass = Vec::new();
+        for _ in 0..1000 {
+            patch.tick();
+            first_pass.push(patch.get_output_value(lfo.id(), 12).unwrap());
         }
-        fn tick(&mut self, inputs: &PortValues, outputs: &mut PortValues) {
-            outputs.set(10, inputs.get_or(0, 0.0));
+        for expected in first_pass.iter().take(600) {
+            patch.tick();
+            let saw = patch.get_output_value(lfo.id(), 12).unwrap();
+            assert!((saw - expected).abs() < 1e-6);
+        }
+
+        // Without a transport the LFO falls back to free-running at its rate
+        patch.clear_transport();
+        assert!(patch.transport().is_none());
+        patch.tick();
+        let start = patch.get_output_value(lfo.id(), 12).unwrap();
+        let step = 0.01 * 3000f64.sqrt() / sample_rate * 2.0 * 5.0;
+        for i in 1..=20 {
+            patch.tick();
+            let saw = patch.get_output_value(lfo.id(), 12).unwrap();
+            assert!(
+                (saw - start - step * i as f64).abs() < 1e-9,
+                "tick {i}: {saw}"
+            );
         }
-        fn reset(&mut self) {}
-        fn set_sample_rate(&mut self, _: f64) {}
     }
 
     #[test]
-    fn test_validation_mode_none() {
+    fn test_patch_validation_mode() {
         let mut patch = Patch::new(44100.0);
-        patch.set_validation_mode(ValidationMode::None);
 
-        let audio = patch.add("audio", Passthrough::new());
-        let gate = patch.add("gate", GateModule::new());
+        patch.set_validation_mode(ValidationMode::Strict);
+        assert_eq!(patch.validation_mode(), ValidationMode::Strict);
 
-        // Should succeed without warnings
-        let result = patch.connect(audio.out("out"), gate.in_("in"));
-        assert!(result.is_ok());
-        assert!(patch.warnings().is_empty());
+        patch.set_validation_mode(ValidationMode::Warn);
+        assert_eq!(patch.validation_mode(), ValidationMode::Warn);
     }
 
-    #[test]
-    fn test_validation_mode_warn() {
-        let mut patch = Patch::new(44100.0);
-        patch.set_validation_mode(ValidationMode::Warn);
+    /// Render one second of a 5kHz sine through heavy distortion
+    fn render_distorted_sine(oversampling: usize) -> Vec<f64> {
+        use crate::modules::{Distortion, Offset, StereoOutput, Vco};
+
+        let sample_rate = 44100.0;
+        let mut patch = Patch::new(sample_rate);
+        patch.set_oversampling(oversampling);
+        let pitch = patch.add("pitch", Offset::new(libm::log2(5000.0 / 261.63)));
+        let vco = patch.add("vco", Vco::new(sample_rate));
+        let dist = patch.add("dist", Distortion::new(sample_rate));
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(pitch.out("out"), vco.in_("voct")).unwrap();
+        patch.connect(vco.out("sin"), dist.in_("in")).unwrap();
+        patch.connect(dist.out("out"), out.in_("left")).unwrap();
+        patch.set_output(out.id());
+        patch.compile().unwrap();
 
-        let audio = patch.add("audio", Passthrough::new());
-        let gate = patch.add("gate", GateModule::new());
+        for _ in 0..4410 {
+            patch.tick();
+        }
+        (0..44100).map(|_| patch.tick().0).collect()
+    }
 
-        // Should succeed but generate warning
-        let result = patch.connect(audio.out("out"), gate.in_("in"));
-        assert!(result.is_ok());
-        assert!(!patch.warnings().is_empty());
+    fn goertzel_magnitude(samples: &[f64], freq: f64, sample_rate: f64) -> f64 {
+        let w = core::f64::consts::TAU * freq / sample_rate;
+        let coeff = 2.0 * libm::cos(w);
+        let (mut s1, mut s2) = (0.0, 0.0);
+        for &x in samples {
+            let s0 = x + coeff * s1 - s2;
+            s2 = s1;
+            s1 = s0;
+        }
+        libm::sqrt(s1 * s1 + s2 * s2 - coeff * s1 * s2) / samples.len() as f64
     }
 
     #[test]
-    fn test_validation_mode_strict() {
-        let mut patch = Patch::new(44100.0);
-        patch.set_validation_mode(ValidationMode::Strict);
+    fn test_oversampling_reduces_aliasing() {
+        let sr = 44100.0;
+        let plain = render_distorted_sine(1);
+        let oversampled = render_distorted_sine(2);
+
+        // Odd harmonics of 5kHz above Nyquist fold back to these frequencies at 1x
+        let aliases = [19100.0, 9100.0, 900.0, 10900.0];
+        let alias_energy =
+            |s: &[f64]| -> f64 { aliases.iter().map(|&f| goertzel_magnitude(s, f, sr)).sum() };
+        let plain_alias = alias_energy(&plain);
+        let oversampled_alias = alias_energy(&oversampled);
+        assert!(
+            oversampled_alias < plain_alias * 0.25,
+            "aliasing not reduced: {} vs {}",
+            oversampled_alias,
+            plain_alias
+        );
+
+        // Pitch is unchanged: the fundamental dominates in both renders
+        let plain_fund = goertzel_magnitude(&plain, 5000.0, sr);
+        let oversampled_fund = goertzel_magnitude(&oversampled, 5000.0, sr);
+        assert!(plain_fund > 0.5 && oversampled_fund > 0.5);
+        assert!((plain_fund - oversampled_fund).abs() / plain_fund < 0.1);
+        for f in [4900.0, 5100.0] {
+            assert!(goertzel_magnitude(&oversampled, f, sr) < oversampled_fund * 0.01);
+        }
+    }
 
-        let audio = patch.add("audio", Passthrough::new());
-        let gate = patch.add("gate", GateModule::new());
+    #[test]
+    fn test_oversampling_rates_and_context() {
+        use crate::modules::Lfo;
 
-        // Should fail with SignalMismatch error
-        let result = patch.connect(audio.out("out"), gate.in_("in"));
-        assert!(matches!(result, Err(PatchError::SignalMismatch { .. })));
+        let mut patch = Patch::new(48000.0);
+        patch.set_oversampling(4);
+        assert_eq!(patch.oversampling(), 4);
+        assert_eq!(patch.sample_rate(), 48000.0);
+        assert_eq!(patch.internal_sample_rate(), 192000.0);
+
+        // Modules added after enabling oversampling also run at the internal rate
+        let lfo = patch.add("lfo", Lfo::new(48000.0));
+        patch.set_output(lfo.id());
+        patch.compile().unwrap();
+
+        let mut transport = Transport::new(90.0, 44100.0);
+        transport.play();
+        patch.set_transport(transport);
+        for _ in 0..100 {
+            patch.tick();
+        }
+
+        // The transport still counts output samples at the base rate
+        let context = patch.process_context(64);
+        assert_eq!(context.sample_rate, 48000.0);
+        assert_eq!(context.internal_sample_rate(), 192000.0);
+        assert_eq!(context.tempo, Some(90.0));
+        assert_eq!(context.sample_position, 100);
+
+        patch.set_oversampling(0);
+        assert_eq!(patch.oversampling(), 1);
+        patch.set_oversampling(1000);
+        assert_eq!(patch.oversampling(), MAX_OVERSAMPLING);
     }
 
-    #[test]
-    fn test_same_signal_type_no_warning() {
+    /// Patch exercising the block overrides, a fallback module, and a normalled output
+    fn block_test_patch(block_size: usize) -> Patch {
+        use crate::modules::{Lfo, Mixer, StereoOutput, Svf, Vca, Vco};
+
         let mut patch = Patch::new(44100.0);
-        patch.set_validation_mode(ValidationMode::Warn);
+        let lfo = patch.add("lfo", Lfo::new(44100.0));
+        let master = patch.add("master", Vco::new(44100.0));
+        let slave = patch.add("slave", Vco::new(44100.0));
+        let vcf = patch.add("vcf", Svf::new(44100.0));
+        let vca = patch.add("vca", Vca::new());
+        let mix = patch.add("mix", Mixer::new(2));
+        let out = patch.add("out", StereoOutput::new());
+
+        patch.connect(lfo.out("sin"), slave.in_("fm")).unwrap();
+        patch.connect(master.out("sqr"), slave.in_("sync")).unwrap();
+        patch
+            .connect_attenuated(lfo.out("sin_uni"), vcf.in_("cutoff"), 0.05)
+            .unwrap();
+        patch.connect(slave.out("saw"), vcf.in_("in")).unwrap();
+        patch.connect(vcf.out("lp"), vca.in_("in")).unwrap();
+        patch.connect(lfo.out("sin_uni"), vca.in_("cv")).unwrap();
+        patch.connect(vca.out("out"), mix.in_("ch0")).unwrap();
+        patch.connect(master.out("tri"), mix.in_("ch1")).unwrap();
+        // Only the left input is cabled; the right follows it through its normal
+        patch.connect(mix.out("out"), out.in_("left")).unwrap();
+
+        patch.set_output(out.id());
+        patch.compile().unwrap();
+        patch.set_block_size(block_size);
+        patch
+    }
 
-        let a = patch.add("a", Passthrough::new());
-        let b = patch.add("b", Passthrough::new());
+    #[test]
+    fn test_block_processing_matches_per_sample() {
+        let mut per_sample = block_test_patch(1);
+        let mut blocked = block_test_patch(64);
+        assert_eq!(blocked.block_size(), 64);
+
+        // Not a whole number of blocks, so a partly handed-out block is read too
+        for i in 0..3000 {
+            let (left, right) = per_sample.tick();
+            let (block_left, block_right) = blocked.tick();
+            assert_eq!(
+                left.to_bits(),
+                block_left.to_bits(),
+                "left differs at {}",
+                i
+            );
+            assert_eq!(
+                right.to_bits(),
+                block_right.to_bits(),
+                "right differs at {}",
+                i
+            );
+        }
 
-        // Same type should not generate warning
-        let result = patch.connect(a.out("out"), b.in_("in"));
-        assert!(result.is_ok());
-        assert!(patch.warnings().is_empty());
+        // A reset drops the rest of the block and starts over in step
+        per_sample.reset();
+        blocked.reset();
+        for _ in 0..200 {
+            assert_eq!(per_sample.tick(), blocked.tick());
+        }
     }
 
     #[test]
-    fn test_connect_modulated() {
+    fn test_block_size_clamp_and_per_sample_fallbacks() {
+        use crate::modules::{Lfo, StereoOutput};
+
+        let mut patch = block_test_patch(0);
+        assert_eq!(patch.block_size(), 1);
+        patch.set_block_size(1 << 20);
+        assert_eq!(patch.block_size(), MAX_BLOCK_SIZE);
+
+        // With a transport attached every tick advances it, block size or not
+        let mut patch = block_test_patch(64);
+        let mut transport = Transport::new(120.0, 44100.0);
+        transport.play();
+        patch.set_transport(transport);
+        for _ in 0..10 {
+            patch.tick();
+        }
+        assert_eq!(patch.transport().unwrap().position_samples(), 10);
+
+        // Detaching mid-block drops samples rendered before the transport
+        let mut patch = Patch::new(1000.0);
+        let lfo = patch.add("lfo", Lfo::new(1000.0).with_tempo_sync(1.0));
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(lfo.out("saw"), out.in_("left")).unwrap();
+        patch.set_output(out.id());
+        patch.compile().unwrap();
+        patch.set_block_size(64);
+        patch.tick();
+
+        let mut transport = Transport::new(120.0, 1000.0);
+        transport.set_position_beats(0.5);
+        transport.play();
+        patch.set_transport(transport);
+        let mut last = 0.0;
+        for _ in 0..10 {
+            last = patch.tick().0;
+        }
+        patch.clear_transport();
+        let (next, _) = patch.tick();
+        assert!((next - last).abs() < 0.1, "jumped from {last} to {next}");
+    }
+
+    #[test]
+    fn test_solo_and_mute_nodes() {
+        use crate::modules::{Mixer, Offset, StereoOutput};
+
         let mut patch = Patch::new(44100.0);
-        let a = patch.add("a", Passthrough::new());
-        let b = patch.add("b", Passthrough::new());
+        let a = patch.add("a", Offset::new(1.0));
+        let b = patch.add("b", Offset::new(2.0));
+        let mix = patch.add("mix", Mixer::new(2));
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(a.out("out"), mix.in_("ch0")).unwrap();
+        patch.connect(b.out("out"), mix.in_("ch1")).unwrap();
+        patch.connect(mix.out("out"), out.in_("left")).unwrap();
+        patch.set_output(out.id());
+        patch.compile().unwrap();
 
-        // Connect with attenuation 0.5 and offset 1.0
-        let result = patch.connect_modulated(a.out("out"), b.in_("in"), 0.5, 1.0);
-        assert!(result.is_ok());
+        assert!((patch.tick().0 - 3.0).abs() < 1e-9);
 
-        let cables = patch.cables();
-        assert_eq!(cables.len(), 1);
-        assert_eq!(cables[0].attenuation, Some(0.5));
-        assert_eq!(cables[0].offset, Some(1.0));
+        // Soloing one branch leaves only that branch in the final output
+        patch.solo_node(Some(a.id()));
+        assert_eq!(patch.soloed_node(), Some(a.id()));
+        assert!((patch.tick().0 - 1.0).abs() < 1e-9);
+        patch.solo_node(Some(b.id()));
+        assert!((patch.tick().0 - 2.0).abs() < 1e-9);
+        patch.solo_node(None);
+
+        patch.mute_node(a.id(), true);
+        assert!(patch.is_muted(a.id()));
+        assert!((patch.tick().0 - 2.0).abs() < 1e-9);
+
+        // Solo wins over mute
+        patch.solo_node(Some(a.id()));
+        assert!((patch.tick().0 - 1.0).abs() < 1e-9);
+
+        patch.solo_node(None);
+        patch.mute_node(a.id(), false);
+        assert!((patch.tick().0 - 3.0).abs() < 1e-9);
+
+        patch.mute_node(out.id(), true);
+        assert_eq!(patch.tick(), (0.0, 0.0));
     }
 
     #[test]
-    fn test_modulated_signal_processing() {
+    fn test_bypassed_reverb_passes_dry_signal() {
+        use crate::modules::{Offset, Reverb, StereoOutput};
+
         let mut patch = Patch::new(44100.0);
+        let dry = patch.add("dry", Offset::new(0.5));
+        let reverb = patch.add("reverb", Reverb::new(44100.0));
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(dry.out("out"), reverb.in_("in")).unwrap();
+        patch.connect(reverb.out("left"), out.in_("left")).unwrap();
+        patch
+            .connect(reverb.out("right"), out.in_("right"))
+            .unwrap();
+        patch.set_output(out.id());
+        patch.compile().unwrap();
 
-        // Use a module that outputs a constant value
-        struct ConstModule {
-            spec: PortSpec,
-            value: f64,
+        for _ in 0..100 {
+            patch.tick();
         }
+        assert_ne!(patch.tick(), (0.5, 0.5));
 
-        impl ConstModule {
-            fn new(value: f64) -> Self {
-                Self {
-                    value,
-                    spec: PortSpec {
-                        inputs: vec![],
-                        outputs: vec![PortDef::new(10, "out", SignalKind::Audio)],
-                    },
-                }
-            }
-        }
+        // Toggled during playback, without recompiling
+        patch.set_bypass(reverb.id(), true).unwrap();
+        assert!(patch.is_bypassed(reverb.id()));
+        assert_eq!(patch.tick(), (0.5, 0.5));
 
-        impl GraphModule for ConstModule {
-            fn port_spec(&self) -> &PortSpec {
-                &self.spec
-            }
-            fn tick(&mut self, _: &PortValues, outputs: &mut PortValues) {
-                outputs.set(10, self.value);
-            }
-            fn reset(&mut self) {}
-            fn set_sample_rate(&mut self, _: f64) {}
+        patch.set_block_size(16);
+        for _ in 0..32 {
+            assert_eq!(patch.tick(), (0.5, 0.5));
         }
 
-        struct RecordModule {
-            spec: PortSpec,
-            last_value: f64,
+        patch.set_bypass(reverb.id(), false).unwrap();
+        assert_ne!(patch.tick(), (0.5, 0.5));
+    }
+
+    #[test]
+    fn test_muted_vco_emits_silence() {
+        use crate::modules::{StereoOutput, Vco};
+
+        let mut patch = Patch::new(44100.0);
+        let vco = patch.add("vco", Vco::new(44100.0));
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(vco.out("saw"), out.in_("left")).unwrap();
+        patch.set_output(out.id());
+        patch.compile().unwrap();
+
+        patch.set_hard_mute(vco.id(), true);
+        assert!(patch.is_hard_muted(vco.id()));
+        for _ in 0..100 {
+            assert_eq!(patch.tick(), (0.0, 0.0));
         }
+        patch.set_hard_mute(vco.id(), false);
+        assert!((0..100).any(|_| patch.tick().0 != 0.0));
+
+        // Four audio outputs: no single route to bypass through
+        assert!(matches!(
+            patch.set_bypass(vco.id(), true),
+            Err(PatchError::InvalidPort)
+        ));
+    }
 
-        impl RecordModule {
-            fn new() -> Self {
-                Self {
-                    spec: PortSpec {
-                        inputs: vec![PortDef::new(0, "in", SignalKind::Audio)],
-                        outputs: vec![],
-                    },
-                    last_value: 0.0,
-                }
+    #[test]
+    fn test_plain_tick_matches_monitored_tick() {
+        use crate::modules::{Lfo, Offset, StereoOutput, Vco};
+
+        // Muting an unconnected node sends the patch down the general path
+        let build = |monitored: bool| {
+            let mut patch = Patch::new(44100.0);
+            let lfo = patch.add("lfo", Lfo::new(44100.0));
+            let vco = patch.add("vco", Vco::new(44100.0));
+            let out = patch.add("out", StereoOutput::new());
+            patch
+                .connect_modulated(lfo.out("sin"), vco.in_("voct"), 0.3, 0.5)
+                .unwrap();
+            patch.connect(lfo.out("tri"), vco.in_("voct")).unwrap();
+            patch.connect(vco.out("saw"), out.in_("left")).unwrap();
+            patch.set_input_value(vco.in_("pw"), 0.2).unwrap();
+            patch.set_output(out.id());
+            if monitored {
+                let idle = patch.add("idle", Offset::new(1.0));
+                patch.mute_node(idle.id(), true);
             }
+            patch.compile().unwrap();
+            patch
+        };
+        let mut plain = build(false);
+        let mut monitored = build(true);
+
+        for i in 0..2000 {
+            let (left, right) = plain.tick();
+            assert_eq!((left, right), monitored.tick(), "sample {}", i);
+            assert_eq!(left, right);
         }
+    }
 
-        impl GraphModule for RecordModule {
-            fn port_spec(&self) -> &PortSpec {
-                &self.spec
-            }
-            fn tick(&mut self, inputs: &PortValues, _: &mut PortValues) {
-                self.last_value = inputs.get_or(0, 0.0);
+    #[test]
+    fn test_toggling_unrelated_node_keeps_block_output() {
+        use crate::modules::{Reverb, StereoOutput, Vco};
+
+        let build = || {
+            let mut patch = Patch::new(44100.0);
+            let vco = patch.add("vco", Vco::new(44100.0));
+            let reverb = patch.add("reverb", Reverb::new(44100.0));
+            let out = patch.add("out", StereoOutput::new());
+            patch.connect(vco.out("saw"), out.in_("left")).unwrap();
+            patch.connect(vco.out("saw"), reverb.in_("in")).unwrap();
+            patch.set_output(out.id());
+            patch.set_block_size(16);
+            patch.compile().unwrap();
+            (patch, reverb.id())
+        };
+        let (mut reference, _) = build();
+        let (mut toggled, reverb) = build();
+
+        for i in 0..200 {
+            if i % 7 == 3 {
+                toggled.set_bypass(reverb, i % 2 == 1).unwrap();
+                toggled.set_mute(reverb, i % 3 == 0);
             }
-            fn reset(&mut self) {}
-            fn set_sample_rate(&mut self, _: f64) {}
+            assert_eq!(toggled.tick(), reference.tick(), "sample {}", i);
         }
+    }
 
-        let source = patch.add("source", ConstModule::new(4.0));
-        let sink = patch.add("sink", RecordModule::new());
+    #[test]
+    fn test_replace_module_keeps_matching_cables() {
+        use crate::modules::{DiodeLadderFilter, Lfo, Offset, StereoOutput, Svf, Vco};
 
-        // Attenuation 0.5, offset 2.0: 4.0 * 0.5 + 2.0 = 4.0
+        let mut patch = Patch::new(44100.0);
+        let vco = patch.add("vco", Vco::new(44100.0));
+        let lfo = patch.add("lfo", Lfo::new(44100.0));
+        let filter = patch.add("filter", Svf::new(44100.0));
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(vco.out("saw"), filter.in_("in")).unwrap();
         patch
-            .connect_modulated(source.out("out"), sink.in_("in"), 0.5, 2.0)
+            .connect(lfo.out("sin_uni"), filter.in_("cutoff"))
             .unwrap();
-        patch.set_output(sink.id());
+        patch.connect(filter.out("lp"), out.in_("left")).unwrap();
+        patch.set_output(out.id());
         patch.compile().unwrap();
-        patch.tick();
 
-        // The value should be processed through attenuation and offset
-        // We can't easily check the internal value, but we verified the connection works
-    }
+        let report = patch
+            .replace_module(filter.id(), Box::new(DiodeLadderFilter::new(44100.0)))
+            .unwrap();
+        assert!(report.is_empty());
+        let filter = patch.get_handle_by_name("filter").unwrap();
+        assert_eq!(patch.cables().len(), 3);
+        assert!(patch.cables().iter().any(|c| c.to == filter.in_("in")));
+        assert!(patch.cables().iter().any(|c| c.to == filter.in_("cutoff")));
+        assert!((0..1000).any(|_| patch.tick().0 != 0.0));
+
+        // A passthrough has no cutoff input
+        let report = patch
+            .replace_module(filter.id(), Box::new(Passthrough::new()))
+            .unwrap();
+        assert_eq!(report.dropped_cables.len(), 1);
+        assert_eq!(report.dropped_cables[0].to, filter.in_("cutoff"));
+        assert_eq!(patch.cables().len(), 2);
 
-    #[test]
-    fn test_signal_compatibility() {
-        // Test specific compatibility cases
-        assert!(SignalKind::Audio
-            .is_compatible_with(&SignalKind::Audio)
-            .warning
-            .is_none());
-        assert!(SignalKind::Audio
-            .is_compatible_with(&SignalKind::CvBipolar)
-            .warning
-            .is_some());
-        assert!(SignalKind::Gate
-            .is_compatible_with(&SignalKind::Trigger)
-            .warning
-            .is_some());
-        assert!(SignalKind::Clock
-            .is_compatible_with(&SignalKind::Trigger)
-            .warning
-            .is_none());
+        // Strict validation drops a cable that no longer passes: the
+        // passthrough's audio input can't take the offset's bipolar CV
+        let mut patch = Patch::new(44100.0);
+        patch.set_validation_mode(ValidationMode::Strict);
+        let src = patch.add("src", Offset::new(1.0));
+        let dst = patch.add("dst", Offset::new(0.0));
+        patch.connect(src.out("out"), dst.in_("in")).unwrap();
+        let report = patch
+            .replace_module(dst.id(), Box::new(Passthrough::new()))
+            .unwrap();
+        assert_eq!(report.dropped_cables.len(), 1);
     }
 
     #[test]
-    fn test_patch_get_name() {
-        let mut patch = Patch::new(44100.0);
-        let a = patch.add("my_module", Passthrough::new());
+    fn test_sub_patch_matches_flattened_patch() {
+        use crate::modules::{Adsr, Lfo, StereoOutput, Svf, Vca, Vco};
+
+        let sr = 44100.0;
+        // Wires a voice into `patch`, returning its voct, gate, and output ports
+        let voice = |patch: &mut Patch| {
+            let vco = patch.add("vco", Vco::new(sr));
+            let env = patch.add("env", Adsr::new(sr));
+            let vcf = patch.add("vcf", Svf::new(sr));
+            let vca = patch.add("vca", Vca::new());
+            patch.connect(vco.out("saw"), vcf.in_("in")).unwrap();
+            patch.connect(vcf.out("lp"), vca.in_("in")).unwrap();
+            patch.connect(env.out("env"), vca.in_("cv")).unwrap();
+            (vco.in_("voct"), env.in_("gate"), vca.out("out"))
+        };
+        // Drives a voice's voct and gate from two LFOs
+        let drive = |patch: &mut Patch, voct: PortRef, gate: PortRef| {
+            let pitch = patch.add("pitch", Lfo::new(sr));
+            let clock = patch.add("clock", Lfo::new(sr));
+            patch.connect(pitch.out("sin"), voct).unwrap();
+            patch.connect(clock.out("sqr"), gate).unwrap();
+        };
 
-        let name = patch.get_name(a.id());
-        assert_eq!(name, Some("my_module"));
+        let mut flat = Patch::new(sr);
+        let (voct, gate, audio) = voice(&mut flat);
+        drive(&mut flat, voct, gate);
+        let out = flat.add("out", StereoOutput::new());
+        flat.connect(audio, out.in_("left")).unwrap();
+        flat.set_output(out.id());
+        flat.compile().unwrap();
+
+        let mut inner = Patch::new(sr);
+        let (voct, gate, audio) = voice(&mut inner);
+        let sub = SubPatch::new(inner)
+            .unwrap()
+            .expose_input(voct.node, voct.port, "voct")
+            .unwrap()
+            .expose_input(gate.node, gate.port, "gate")
+            .unwrap()
+            .expose_output(audio.node, audio.port, "out")
+            .unwrap();
 
-        // Non-existent node
-        use slotmap::DefaultKey;
-        let fake_id: NodeId = DefaultKey::default();
-        assert!(patch.get_name(fake_id).is_none());
+        let mut nested = Patch::new(sr);
+        let node = nested.add("voice", sub);
+        drive(&mut nested, node.in_("voct"), node.in_("gate"));
+        let out = nested.add("out", StereoOutput::new());
+        nested.connect(node.out("out"), out.in_("left")).unwrap();
+        nested.set_output(out.id());
+        nested.compile().unwrap();
+
+        let mut heard = false;
+        for _ in 0..4410 {
+            let (a, b) = (flat.tick(), nested.tick());
+            assert_eq!(a, b);
+            heard |= a.0 != 0.0;
+        }
+        assert!(heard);
+
+        // An internally cabled input can't be exposed
+        let mut inner = Patch::new(sr);
+        voice(&mut inner);
+        let vcf = inner.get_handle_by_name("vcf").unwrap();
+        assert!(SubPatch::new(inner)
+            .unwrap()
+            .expose_input(vcf.id(), vcf.in_("in").port, "in")
+            .is_err());
     }
 
     #[test]
-    fn test_patch_set_position() {
-        let mut patch = Patch::new(44100.0);
-        let a = patch.add("a", Passthrough::new());
+    fn test_sidechain_bus_ducks_compressor() {
+        use crate::modules::{Clock, Compressor, Offset, StereoOutput};
+
+        let sr = 1000.0;
+        let mut patch = Patch::new(sr);
+        let kick = patch.add("kick", Clock::new(sr));
+        let pad = patch.add("pad", Offset::new(2.0));
+        let comp = patch.add("comp", Compressor::new(sr));
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(pad.out("out"), comp.in_("in")).unwrap();
+        patch.connect(comp.out("out"), out.in_("left")).unwrap();
+        patch.set_output(out.id());
+
+        patch.register_sidechain("kick", kick.out("out")).unwrap();
+        patch
+            .subscribe_sidechain("kick", comp.in_("sidechain"))
+            .unwrap();
+        patch.compile().unwrap();
+        assert_eq!(patch.sidechain_buses().len(), 1);
+
+        // A twin clock tells us when the kick is sounding
+        let mut reference = Clock::new(sr);
+        let inputs = PortValues::new();
+        let mut kick_out = PortValues::new();
+        let (mut during_kick, mut kick_samples) = (0.0, 0);
+        let (mut between, mut between_samples) = (0.0, 0);
+        let mut since_kick = 0;
+        for _ in 0..10_000 {
+            reference.tick(&inputs, &mut kick_out);
+            let level = patch.tick().0;
+            if kick_out.get(10).unwrap() > 2.5 {
+                since_kick = 0;
+            } else {
+                since_kick += 1;
+            }
+            if since_kick == 0 {
+                during_kick += level;
+                kick_samples += 1;
+            } else if since_kick > 1500 {
+                between += level;
+                between_samples += 1;
+            }
+        }
 
-        patch.set_position(a.id(), (100.0, 200.0));
-        // Position is stored but not exposed directly in tests
+        let during_kick = during_kick / kick_samples as f64;
+        let between = between / between_samples as f64;
+        assert!(between > 1.9, "pad should recover between kicks: {between}");
+        assert!(during_kick < between * 0.9, "{during_kick} vs {between}");
     }
 
     #[test]
-    fn test_patch_clear_warnings() {
+    fn test_sidechain_unknown_bus_fails_compile() {
+        use crate::modules::Compressor;
+
         let mut patch = Patch::new(44100.0);
-        patch.set_validation_mode(ValidationMode::Warn);
+        let comp = patch.add("comp", Compressor::new(44100.0));
+        patch
+            .subscribe_sidechain("missing", comp.in_("sidechain"))
+            .unwrap();
+        assert!(matches!(
+            patch.compile(),
+            Err(PatchError::UnknownSidechain(name)) if name == "missing"
+        ));
 
-        let audio = patch.add("audio", Passthrough::new());
-        let gate = patch.add("gate", GateModule::new());
+        // Removing the source node removes its bus too
+        let source = patch.add("source", Passthrough::new());
+        patch
+            .register_sidechain("missing", source.out("out"))
+            .unwrap();
+        patch.compile().unwrap();
+        patch.remove(source.id()).unwrap();
+        assert!(patch.sidechain_buses().is_empty());
+        assert!(patch.compile().is_err());
+    }
 
-        patch.connect(audio.out("out"), gate.in_("in")).unwrap();
-        assert!(!patch.warnings().is_empty());
+    // Runaway feedback loop: the internal state grows without bound
+    struct Runaway {
+        state: f64,
+        spec: PortSpec,
+    }
 
-        patch.clear_warnings();
-        assert!(patch.warnings().is_empty());
+    impl GraphModule for Runaway {
+        fn port_spec(&self) -> &PortSpec {
+            &self.spec
+        }
+
+        fn tick(&mut self, _inputs: &PortValues, outputs: &mut PortValues) {
+            self.state *= 10.0;
+            // inf * 0 = NaN once the loop has blown up
+            outputs.set(10, self.state * 0.0);
+        }
+
+        fn reset(&mut self) {}
+
+        fn set_sample_rate(&mut self, _: f64) {}
     }
 
-    #[test]
-    fn test_patch_validation_mode_getter() {
+    fn runaway_patch() -> (Patch, NodeId) {
+        use crate::modules::StereoOutput;
+
         let mut patch = Patch::new(44100.0);
-        patch.set_validation_mode(ValidationMode::Strict);
-        assert_eq!(patch.validation_mode(), ValidationMode::Strict);
+        let runaway = patch.add(
+            "runaway",
+            Runaway {
+                state: 1.0,
+                spec: PortSpec {
+                    inputs: vec![],
+                    outputs: vec![PortDef::new(10, "out", SignalKind::Audio)],
+                },
+            },
+        );
+        let pass = patch.add("pass", Passthrough::new());
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(runaway.out("out"), pass.in_("in")).unwrap();
+        patch.connect(pass.out("out"), out.in_("left")).unwrap();
+        patch.set_output(out.id());
+        patch.compile().unwrap();
+        (patch, runaway.id())
     }
 
     #[test]
-    fn test_patch_sample_rate() {
-        let patch = Patch::new(48000.0);
-        assert_eq!(patch.sample_rate(), 48000.0);
+    fn test_nan_guard_flags_offending_node() {
+        // Unguarded, NaN reaches the output silently
+        let (mut patch, _) = runaway_patch();
+        let outputs: Vec<f64> = (0..400).map(|_| patch.tick().0).collect();
+        assert!(outputs.iter().any(|v| v.is_nan()));
+        assert!(patch.last_error().is_none());
+
+        // Output guard sanitizes and reports the output node
+        let (mut patch, _) = runaway_patch();
+        patch.set_nan_guard(NanGuard::Output);
+        assert!((0..400).all(|_| patch.tick().0.is_finite()));
+        assert!(matches!(
+            patch.last_error(),
+            Some(PatchError::NonFinite { port: 0, .. })
+        ));
+
+        // Per-node guard pinpoints the runaway module
+        let (mut patch, runaway) = runaway_patch();
+        patch.set_nan_guard(NanGuard::PerNode);
+        assert!((0..400).all(|_| patch.tick().0.is_finite()));
+        match patch.last_error() {
+            Some(PatchError::NonFinite { node, port }) => {
+                assert_eq!(*node, runaway);
+                assert_eq!(*port, 10);
+            }
+            other => panic!("expected non-finite error, got {other:?}"),
+        }
+        patch.clear_error();
+        assert!(patch.last_error().is_none());
     }
 
     #[test]
-    fn test_patch_execution_order() {
-        let mut patch = Patch::new(44100.0);
-        let a = patch.add("a", Passthrough::new());
-        let b = patch.add("b", Passthrough::new());
-        patch.connect(a.out("out"), b.in_("in")).unwrap();
+    fn test_fx_chain_reorder() {
+        use crate::modules::{Distortion, Reverb};
+        let sr = 44100.0;
+        let input = |i: usize| 4.0 * libm::sin(2.0 * core::f64::consts::PI * 220.0 * i as f64 / sr);
+        let render = |chain: &mut FxChain, range: core::ops::Range<usize>| -> Vec<f64> {
+            range.map(|i| chain.process(input(i))).collect()
+        };
+        let max_step = |out: &[f64]| {
+            out.windows(2)
+                .map(|w| (w[1] - w[0]).abs())
+                .fold(0.0f64, f64::max)
+        };
+
+        let mut chain = FxChain::new(sr)
+            .with_effect(Distortion::new(sr))
+            .with_effect(Reverb::new(sr));
+        let mut swapped = FxChain::new(sr)
+            .with_effect(Reverb::new(sr))
+            .with_effect(Distortion::new(sr));
+        let before = render(&mut chain, 0..4410);
+        let reference = render(&mut swapped, 0..4410);
+
+        // Distortion last squashes the reverb tail, so the orders sound different
+        let diff: f64 = before
+            .iter()
+            .zip(&reference)
+            .map(|(a, b)| (a - b).abs())
+            .sum::<f64>()
+            / before.len() as f64;
+        assert!(diff > 0.1, "orders should differ: {diff}");
+
+        // Reorder while running: the swap fades through the dry signal without a jump
+        chain.reorder(0, 1).unwrap();
+        assert_eq!(chain.effect(0).unwrap().type_id(), "reverb");
+        assert!(chain.is_swapping());
+        let during = render(&mut chain, 4410..5410);
+        assert!(!chain.is_swapping());
+        let allowed = max_step(&before).max(max_step(&reference)) * 1.5;
+        assert!(max_step(&during) <= allowed, "click during reorder");
+
+        // Once settled, the chain runs in the new order
+        chain.reset();
+        swapped.reset();
+        assert_eq!(render(&mut chain, 0..2000), render(&mut swapped, 0..2000));
+
+        // Bypassing everything fades to the dry input
+        chain.set_enabled(0, false).unwrap();
+        chain.set_amount(1, 0.0).unwrap();
+        let out = render(&mut chain, 0..2000);
+        assert!((out[1999] - input(1999)).abs() < 1e-12);
+        assert!(chain.reorder(0, 5).is_err());
+    }
+
+    #[test]
+    fn test_estimated_cost_reverb_dominates() {
+        use crate::modules::{Adsr, Mixer, Reverb, Vca, Vco};
+        let sr = 44100.0;
+        let mut patch = Patch::new(sr);
+        patch.add("vco", Vco::new(sr));
+        patch.add("env", Adsr::new(sr));
+        patch.add("vca", Vca::new());
+        let cheap = patch.estimated_cost();
+        assert_eq!(cheap, 3);
+
+        patch.add("reverb", Reverb::new(sr));
+        let total = patch.estimated_cost();
+        let reverb = total - cheap;
+        assert!(reverb > cheap * 4, "reverb cost {reverb} vs {cheap}");
+
+        // Variable-size modules scale their hint with their size
+        assert!(Mixer::new(16).cost_hint() > Mixer::new(2).cost_hint());
+
+        // Oversampling multiplies every module's cost
+        patch.set_oversampling(2);
+        assert_eq!(patch.estimated_cost(), total * 2);
+    }
+
+    /// Saw through a swept filter into a reverb, with oversampling and a transport
+    fn runtime_state_patch() -> Patch {
+        use crate::modules::{Lfo, Reverb, StereoOutput, Svf, Vco};
+
+        let sr = 44100.0;
+        let mut patch = Patch::new(sr);
+        patch.set_oversampling(2);
+        let mut transport = Transport::new(120.0, sr);
+        transport.play();
+        patch.set_transport(transport);
+        let vco = patch.add("vco", Vco::new(sr));
+        let lfo = patch.add("lfo", Lfo::new(sr).with_tempo_sync(1.0));
+        let svf = patch.add("svf", Svf::new(sr));
+        let reverb = patch.add("reverb", Reverb::new(sr));
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(vco.out("saw"), svf.in_("in")).unwrap();
+        patch
+            .connect(lfo.out("sin_uni"), svf.in_("cutoff"))
+            .unwrap();
+        patch.connect(svf.out("lp"), reverb.in_("in")).unwrap();
+        patch.connect(reverb.out("left"), out.in_("left")).unwrap();
+        patch
+            .connect(reverb.out("right"), out.in_("right"))
+            .unwrap();
+        patch.set_output(out.id());
         patch.compile().unwrap();
+        patch
+    }
 
-        let order = patch.execution_order();
-        assert_eq!(order.len(), 2);
+    #[test]
+    fn test_runtime_state_resumes_exactly() {
+        let render = |patch: &mut Patch, n: usize| -> Vec<(f64, f64)> {
+            (0..n).map(|_| patch.tick()).collect()
+        };
+
+        let mut reference = runtime_state_patch();
+        render(&mut reference, 3000);
+        let uninterrupted = render(&mut reference, 3000);
+
+        let mut patch = runtime_state_patch();
+        render(&mut patch, 3000);
+        let snapshot = patch.save_runtime_state().unwrap();
+        let first = render(&mut patch, 3000);
+        assert_eq!(first, uninterrupted);
+
+        // Run on, restore, and the same output follows again
+        render(&mut patch, 1000);
+        patch.restore_runtime_state(&snapshot).unwrap();
+        assert_eq!(render(&mut patch, 3000), uninterrupted);
+
+        // A fresh, identically built patch resumes from the snapshot too
+        let mut fresh = runtime_state_patch();
+        fresh.restore_runtime_state(&snapshot).unwrap();
+        assert_eq!(render(&mut fresh, 3000), uninterrupted);
+
+        // Corrupt or mismatched snapshots are rejected
+        assert!(matches!(
+            fresh.restore_runtime_state(&snapshot[..snapshot.len() - 1]),
+            Err(PatchError::RuntimeState(_))
+        ));
+        let mut other = Patch::new(44100.0);
+        other.add("vco", crate::modules::Vco::new(44100.0));
+        assert!(other.restore_runtime_state(&snapshot).is_err());
+        assert!(other.restore_runtime_state(b"nonsense").is_err());
     }
 
     #[test]
-    fn test_patch_mult() {
-        let mut patch = Patch::new(44100.0);
-        let a = patch.add("a", Passthrough::new());
-        let b = patch.add("b", Passthrough::new());
-        let c = patch.add("c", Passthrough::new());
+    fn test_runtime_state_requires_module_support() {
+        let snapshot = runtime_state_patch().save_runtime_state().unwrap();
+
+        // Passthrough keeps the default hooks, so its state can't be captured
+        let mut patch = runtime_state_patch();
+        patch.add("thru", Passthrough::new());
+        match patch.save_runtime_state() {
+            Err(PatchError::RuntimeState(msg)) => assert!(msg.contains("'thru'"), "{msg}"),
+            other => panic!("expected a runtime state error, got {:?}", other),
+        }
+        assert!(matches!(
+            patch.restore_runtime_state(&snapshot),
+            Err(PatchError::RuntimeState(_))
+        ));
+    }
 
-        // Connect one output to multiple inputs
-        let result = patch.mult(a.out("out"), &[b.in_("in"), c.in_("in")]);
-        assert!(result.is_ok());
-        assert_eq!(patch.cable_count(), 2);
+    #[test]
+    fn test_rejected_runtime_state_leaves_patch_unchanged() {
+        use crate::modules::{SampleHoldBank, StereoOutput, Vco};
+
+        let build = |channels: usize| {
+            let mut patch = Patch::new(44100.0);
+            let vco = patch.add("vco", Vco::new(44100.0));
+            patch.add("bank", SampleHoldBank::new(44100.0, channels));
+            let out = patch.add("out", StereoOutput::new());
+            patch.connect(vco.out("saw"), out.in_("left")).unwrap();
+            patch.connect(vco.out("sin"), out.in_("right")).unwrap();
+            patch.set_output(out.id());
+            patch.compile().unwrap();
+            patch
+        };
+
+        let mut source = build(4);
+        for _ in 0..1000 {
+            source.tick();
+        }
+        let snapshot = source.save_runtime_state().unwrap();
+
+        // The bank's payload has the wrong channel count, so the VCO state
+        // restored before it has to be rolled back
+        let mut patch = build(8);
+        let mut reference = build(8);
+        for _ in 0..10 {
+            assert_eq!(patch.tick(), reference.tick());
+        }
+        assert!(matches!(
+            patch.restore_runtime_state(&snapshot),
+            Err(PatchError::RuntimeState(_))
+        ));
+        for _ in 0..100 {
+            assert_eq!(patch.tick(), reference.tick());
+        }
     }
 
     #[test]
-    fn test_patch_reset() {
+    fn test_sync_group_resets_members_together() {
+        use crate::modules::{Lfo, StereoOutput};
+
         let mut patch = Patch::new(44100.0);
-        let a = patch.add("a", Passthrough::new());
-        patch.set_output(a.id());
+        let a = patch.add("lfo_a", Lfo::new(44100.0));
+        let b = patch.add("lfo_b", Lfo::new(44100.0));
+        let free = patch.add("lfo_free", Lfo::new(44100.0));
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(a.out("saw"), out.in_("left")).unwrap();
+        patch.connect(b.out("saw"), out.in_("right")).unwrap();
+        patch.set_output(out.id());
         patch.compile().unwrap();
 
-        for _ in 0..100 {
+        patch.set_sync_group(a.id(), Some(1));
+        patch.set_sync_group(b.id(), Some(1));
+        assert_eq!(patch.sync_group(a.id()), Some(1));
+        assert_eq!(patch.sync_group(free.id()), None);
+
+        for _ in 0..5000 {
             patch.tick();
         }
+        let saw = |patch: &Patch, node: NodeId| patch.get_output_value(node, 12).unwrap();
+        assert!(saw(&patch, a.id()) > -5.0);
 
-        patch.reset();
-        // Reset clears internal state
+        assert_eq!(patch.reset_sync_group(1), 2);
+        assert_eq!(patch.reset_sync_group(2), 0);
+        let free_before = saw(&patch, free.id());
+        patch.tick();
+
+        // Group members output phase zero (saw at its minimum); the free LFO runs on
+        assert_eq!(saw(&patch, a.id()), -5.0);
+        assert_eq!(saw(&patch, b.id()), -5.0);
+        assert!(saw(&patch, free.id()) > free_before);
+
+        // Leaving the group exempts a node from later resets
+        patch.set_sync_group(b.id(), None);
+        patch.tick();
+        assert_eq!(patch.reset_sync_group(1), 1);
     }
 
-    #[test]
-    fn test_patch_set_param_get_param() {
-        use crate::modules::Vco;
+    fn latency_test_patch(block_size: usize) -> (Patch, CableId, CableId) {
+        use crate::modules::{Mixer, Oversample, StereoOutput, Vca, Vco};
+
         let mut patch = Patch::new(44100.0);
         let vco = patch.add("vco", Vco::new(44100.0));
+        let wet = patch.add("wet", Oversample::new(Vca::new(), 4, 44100.0));
+        let mix = patch.add("mix", Mixer::new(2));
+        let out = patch.add("out", StereoOutput::new());
 
-        // Try to set/get param (may or may not have params)
-        patch.set_param(vco.id(), 0, 0.5);
-        let _ = patch.get_param(vco.id(), 0);
+        let dry_cable = patch.connect(vco.out("sin"), mix.in_("ch0")).unwrap();
+        patch.connect(vco.out("sin"), wet.in_("in")).unwrap();
+        let wet_cable = patch.connect(wet.out("out"), mix.in_("ch1")).unwrap();
+        patch.connect(mix.out("out"), out.in_("left")).unwrap();
+        patch.connect(wet.out("out"), out.in_("right")).unwrap();
+
+        patch.set_output(out.id());
+        patch.compile().unwrap();
+        patch.set_block_size(block_size);
+        (patch, dry_cable, wet_cable)
     }
 
     #[test]
-    fn test_node_handle_spec() {
-        let mut patch = Patch::new(44100.0);
-        let a = patch.add("a", Passthrough::new());
+    fn test_latency_compensation_aligns_parallel_paths() {
+        let (mut patch, dry_cable, wet_cable) = latency_test_patch(1);
+        let wet = patch.get_node_id_by_name("wet").unwrap();
+        let mix = patch.get_node_id_by_name("mix").unwrap();
+        let latency = patch.nodes[wet].module.latency_samples();
+        assert_eq!(latency, 32);
+
+        assert_eq!(patch.compensation_delay(dry_cable), Some(latency));
+        assert_eq!(patch.compensation_delay(wet_cable), Some(0));
+        assert_eq!(patch.node_latency(mix), Some(latency));
+        assert_eq!(patch.total_latency(), latency);
+
+        // The dry path is delayed to meet the wet one, so the mix is the wet
+        // signal doubled rather than a comb-filtered sum
+        let mut worst: f64 = 0.0;
+        for i in 0..2000 {
+            let (mixed, wet) = patch.tick();
+            if i >= 200 {
+                worst = worst.max((mixed - 2.0 * wet).abs());
+            }
+        }
+        assert!(worst < 0.01, "dry and wet misaligned by {worst}");
+
+        // Block processing delays the dry path identically, and the delay
+        // lines resume from a runtime snapshot
+        let (mut blocked, _, _) = latency_test_patch(64);
+        let (mut per_sample, _, _) = latency_test_patch(1);
+        for _ in 0..500 {
+            assert_eq!(blocked.tick(), per_sample.tick());
+        }
+        let snapshot = per_sample.save_runtime_state().unwrap();
+        let (mut restored, _, _) = latency_test_patch(1);
+        restored.restore_runtime_state(&snapshot).unwrap();
+        for _ in 0..100 {
+            assert_eq!(restored.tick(), per_sample.tick());
+        }
 
-        let spec = a.spec();
-        assert!(!spec.inputs.is_empty());
-        assert!(!spec.outputs.is_empty());
+        patch.set_oversampling(2);
+        patch.compile().unwrap();
+        assert_eq!(patch.node_latency(mix), Some(latency));
+        // 16 base-rate samples through the graph, about 16 in the decimator
+        assert_eq!(patch.total_latency(), 32);
     }
 
     #[test]
-    fn test_patch_validation_mode() {
+    fn test_same_seed_renders_identically() {
+        use crate::modules::{NoiseGenerator, StereoOutput};
+
+        let render = |seed: u64| {
+            let mut patch = Patch::new(44100.0);
+            let noise = patch.add("noise", NoiseGenerator::new());
+            let out = patch.add("out", StereoOutput::new());
+            patch.connect(noise.out("white"), out.in_("left")).unwrap();
+            patch.connect(noise.out("pink"), out.in_("right")).unwrap();
+            patch.set_output(out.id());
+            patch.set_seed(seed);
+            patch.compile().unwrap();
+            (0..256).map(|_| patch.tick()).collect::<Vec<_>>()
+        };
+
+        let first = render(7);
+        assert_eq!(first, render(7));
+        assert_ne!(first, render(8));
+    }
+
+    #[test]
+    fn test_wants_dc_block_strips_offset() {
+        use crate::modules::StereoOutput;
+
+        // Constant offset on an audio output, as an asymmetric shaper leaves
+        struct Offset {
+            spec: PortSpec,
+        }
+
+        impl GraphModule for Offset {
+            fn port_spec(&self) -> &PortSpec {
+                &self.spec
+            }
+            fn tick(&mut self, _: &PortValues, outputs: &mut PortValues) {
+                outputs.set(10, 2.0);
+            }
+            fn reset(&mut self) {}
+            fn set_sample_rate(&mut self, _: f64) {}
+            fn wants_dc_block(&self) -> bool {
+                true
+            }
+        }
+
+        for block_size in [1, 64] {
+            let mut patch = Patch::new(44100.0);
+            let offset = patch.add(
+                "offset",
+                Offset {
+                    spec: PortSpec {
+                        inputs: vec![],
+                        outputs: vec![PortDef::new(10, "out", SignalKind::Audio)],
+                    },
+                },
+            );
+            let out = patch.add("out", StereoOutput::new());
+            patch.connect(offset.out("out"), out.in_("left")).unwrap();
+            patch.set_output(out.id());
+            patch.set_block_size(block_size);
+            patch.compile().unwrap();
+
+            assert!((patch.tick().0 - 2.0).abs() < 1e-9);
+            // Ten time constants of the 35 Hz corner is about 45 ms
+            let left = (0..2000).map(|_| patch.tick().0).last().unwrap();
+            assert!(left.abs() < 1e-3, "offset left: {}", left);
+
+            patch.reset();
+            assert!((patch.tick().0 - 2.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_runtime_state_keeps_param_glides() {
+        use crate::modules::{StereoOutput, Svf, Vco};
+
+        let build = || {
+            let mut patch = Patch::new(44100.0);
+            let vco = patch.add("vco", Vco::new(44100.0));
+            let svf = patch.add("svf", Svf::new(44100.0));
+            let out = patch.add("out", StereoOutput::new());
+            patch.connect(vco.out("saw"), svf.in_("in")).unwrap();
+            patch.connect(svf.out("lp"), out.in_("left")).unwrap();
+            patch.set_output(out.id());
+            patch.compile().unwrap();
+            (patch, svf.id())
+        };
+
+        let (mut patch, svf) = build();
+        patch.set_param_smoothed(svf, 1, 5000.0, 20.0);
+        for _ in 0..100 {
+            patch.tick();
+        }
+        let snapshot = patch.save_runtime_state().unwrap();
+        let gliding: Vec<(f64, f64)> = (0..500).map(|_| patch.tick()).collect();
+
+        // The fresh patch has no glide of its own; the snapshot carries it
+        let (mut fresh, svf) = build();
+        fresh.restore_runtime_state(&snapshot).unwrap();
+        assert_eq!(fresh.get_param(svf, 1), Some(5000.0));
+        let resumed: Vec<(f64, f64)> = (0..500).map(|_| fresh.tick()).collect();
+        assert_eq!(resumed, gliding);
+    }
+
+    #[test]
+    fn test_set_param_smoothed_glides_module_param() {
+        use crate::modules::{StereoOutput, Svf};
+
         let mut patch = Patch::new(44100.0);
+        let svf = patch.add("svf", Svf::new(44100.0));
+        let out = patch.add("out", StereoOutput::new());
+        patch.connect(svf.out("lp"), out.in_("left")).unwrap();
+        patch.set_output(out.id());
+        patch.compile().unwrap();
 
-        patch.set_validation_mode(ValidationMode::Strict);
-        assert_eq!(patch.validation_mode(), ValidationMode::Strict);
+        let cutoff = |patch: &Patch| {
+            let (_, _, module) = patch.nodes().find(|(_, name, _)| *name == "svf").unwrap();
+            module.get_param(1).unwrap()
+        };
+        let start = cutoff(&patch);
 
-        patch.set_validation_mode(ValidationMode::Warn);
-        assert_eq!(patch.validation_mode(), ValidationMode::Warn);
+        // Svf leaves smoothing to the patch, which steps the knob per sample
+        patch.set_param_smoothed(svf.id(), 1, 5000.0, 10.0);
+        assert_eq!(patch.get_param(svf.id(), 1), Some(5000.0));
+        assert_eq!(cutoff(&patch), start);
+
+        let mut last = start;
+        for _ in 0..441 {
+            patch.tick();
+            let now = cutoff(&patch);
+            assert!(now > last && now < 5000.0);
+            last = now;
+        }
+        assert!(last > 4900.0);
+
+        // A plain set_param cancels the glide
+        patch.set_param(svf.id(), 1, 1000.0);
+        patch.tick();
+        assert!((cutoff(&patch) - 1000.0).abs() < 1e-9);
     }
 }
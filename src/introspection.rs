@@ -10,7 +10,7 @@ use alloc::string::ToString;
 use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
-use crate::port::GraphModule;
+use crate::port::{GraphModule, ParamDef};
 
 // =============================================================================
 // Parameter Value Formatting
@@ -350,6 +350,39 @@ impl ParamInfo {
             .with_format(ValueFormat::Decimal { places: 0 })
     }
 
+    /// Describe a module's [`ParamDef`] holding `value`
+    ///
+    /// The id is the definition's name and the display name title-cases it
+    /// (`pulse_width` becomes "Pulse Width"). Frequency, time, and decibel
+    /// formats also set the matching unit.
+    pub fn from_def(def: &ParamDef, value: f64) -> Self {
+        let (min, max) = def.range.bounds();
+        let name = def
+            .name
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let info = Self::new(def.name.clone(), name)
+            .with_range(min, max)
+            .with_default(def.default)
+            .with_value(value)
+            .with_curve(def.curve)
+            .with_format(def.format.clone());
+        match def.format {
+            ValueFormat::Frequency => info.with_unit("Hz"),
+            ValueFormat::Time => info.with_unit("s"),
+            ValueFormat::Decibels => info.with_unit("dB"),
+            _ => info,
+        }
+    }
+
     /// Get the normalized (0-1) value
     pub fn normalized(&self) -> f64 {
         self.curve.normalize(self.value, self.min, self.max)
@@ -378,9 +411,13 @@ pub trait ModuleIntrospection: GraphModule {
     /// Get all parameter descriptors for this module
     ///
     /// Returns a list of `ParamInfo` describing each controllable parameter.
-    /// The order should be consistent and reflect a logical grouping.
+    /// The order should be consistent and reflect a logical grouping. The
+    /// default describes `GraphModule::params` with their current values.
     fn param_infos(&self) -> Vec<ParamInfo> {
-        Vec::new()
+        self.params()
+            .iter()
+            .map(|def| ParamInfo::from_def(def, self.get_param(def.id).unwrap_or(def.default)))
+            .collect()
     }
 
     /// Get a specific parameter by its ID
@@ -391,8 +428,20 @@ pub trait ModuleIntrospection: GraphModule {
     /// Set a parameter value by its ID
     ///
     /// Returns true if the parameter was found and set, false otherwise.
-    fn set_param_by_id(&mut self, _id: &str, _value: f64) -> bool {
-        false
+    /// The default sets the `GraphModule::params` entry with that name.
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match self
+            .params()
+            .iter()
+            .find(|def| def.name == id)
+            .map(|def| def.id)
+        {
+            Some(param) => {
+                self.set_param(param, value);
+                true
+            }
+            None => false,
+        }
     }
 }
 
@@ -405,6 +454,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_value_format_decimal() {
         let fmt = ValueFormat::Decimal { places: 2 };
         assert_eq!(fmt.format(3.14159), "3.14");
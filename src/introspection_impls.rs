@@ -3,32 +3,42 @@
 //! This module provides `ModuleIntrospection` implementations for all built-in modules,
 //! enabling GUIs to discover and control module parameters automatically.
 //!
-//! Most modules are fully CV-controlled and use the default empty implementation.
+//! Most modules are fully CV-controlled and use the default implementation,
+//! which describes the panel knobs from `GraphModule::params` (none for many).
 //! Only modules with internal state parameters provide custom implementations.
 
 use alloc::vec;
 use alloc::vec::Vec;
+use libm::Libm;
 
 use crate::introspection::{ControlType, ModuleIntrospection, ParamCurve, ParamInfo, ValueFormat};
 
 use crate::analog::{AnalogVco, Saturator, Wavefolder};
 use crate::modules::{
-    Adsr, Arpeggiator, Attenuverter, BernoulliGate, ChordMemory, Clock, Comparator, Crossfader,
-    Crosstalk, DiodeLadderFilter, FormantOsc, Granular, GroundLoop, Lfo, LogicAnd, LogicNot,
-    LogicOr, LogicXor, Max, Min, Mixer, Multiple, NoiseGenerator, Offset, ParametricEq,
-    PitchShifter, PrecisionAdder, Quantizer, Rectifier, Reverb, RingModulator, SampleAndHold,
-    Scale, SlewLimiter, StepSequencer, StereoOutput, Svf, UnitDelay, VcSwitch, Vca, Vco, Vocoder,
-    Wavetable,
+    AWeight, Adsr, Arpeggiator, Attenuverter, BernoulliGate, CWeight, ChordMemory, Clock,
+    ClockGroove, ClockRatchet, CombFilter, Comparator, Convolution, Crossfader, Crosstalk,
+    CurveShape, DcBlocker, DeClick, DelayLine, DiodeLadderFilter, DrumLogic, FeedbackMixer,
+    FormantOsc, GateDropout, GateLengthSequencer, Granular, GroundLoop, LayerBlend, Lfo, LogicAnd,
+    LogicNot, LogicOr, LogicXor, LoudnessComp, Max, Min, Mixer, ModalResonator, MoogEnvelope,
+    MultibandImager, Multiple, NoiseGenerator, NoteRepeat, Offset, Panner, ParametricEq,
+    PdWaveform, PhaseDistortionOsc, PitchShifter, PrecisionAdder, Quantizer, RandomSource,
+    Rectifier, ResponseCurve, Reverb, RingModulator, SampleAndHold, SampleHoldBank, SamplePlayer,
+    Scale, ShapeLfo, SlewLimiter, SlewShape, SmartQuantizer, SmartWidener, SpectralMorph,
+    StepSequencer, StereoOutput, Supersaw, Svf, TableInterpolation, TableWaveshaper, Terrain,
+    TriggerMerge, TriggerUtil, UnitDelay, VcSlew, VcSwitch, Vca, Vco, VectorMix, VintageSampler,
+    Vocoder, WaveTerrain, Waveguide, Wavetable, WeightedQuantizer, WindModel,
 };
 
 // =============================================================================
-// CV-Controlled Modules (use default empty implementation)
+// CV-Controlled Modules (default implementation, over `GraphModule::params`)
 // =============================================================================
 
 // Oscillators
 impl ModuleIntrospection for Vco {}
 impl ModuleIntrospection for Lfo {}
+impl ModuleIntrospection for ShapeLfo {}
 impl ModuleIntrospection for AnalogVco {}
+impl ModuleIntrospection for ModalResonator {}
 
 // Filters
 impl ModuleIntrospection for Svf {}
@@ -36,48 +46,133 @@ impl ModuleIntrospection for DiodeLadderFilter {}
 
 // Envelopes & Amplifiers
 impl ModuleIntrospection for Adsr {}
+impl ModuleIntrospection for MoogEnvelope {}
 impl ModuleIntrospection for Vca {}
 
 // Utilities (CV-controlled)
 impl ModuleIntrospection for Mixer {}
 impl ModuleIntrospection for UnitDelay {}
+impl ModuleIntrospection for DcBlocker {}
 impl ModuleIntrospection for Attenuverter {}
 impl ModuleIntrospection for Multiple {}
 impl ModuleIntrospection for SlewLimiter {}
 impl ModuleIntrospection for SampleAndHold {}
+impl ModuleIntrospection for RandomSource {}
+impl ModuleIntrospection for SampleHoldBank {}
 impl ModuleIntrospection for PrecisionAdder {}
-impl ModuleIntrospection for VcSwitch {}
+impl ModuleIntrospection for VcSwitch {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        vec![
+            ParamInfo::toggle("equal_power", "Equal Power").with_value(if self.is_equal_power() {
+                1.0
+            } else {
+                0.0
+            }),
+        ]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "equal_power" => {
+                self.set_equal_power(value > 0.5);
+                true
+            }
+            _ => false,
+        }
+    }
+}
 impl ModuleIntrospection for Min {}
 impl ModuleIntrospection for Max {}
 impl ModuleIntrospection for Crossfader {}
+impl ModuleIntrospection for Panner {}
+impl ModuleIntrospection for VectorMix {}
+impl ModuleIntrospection for LayerBlend {}
 
 // Effects (CV-controlled)
-impl ModuleIntrospection for RingModulator {}
+impl ModuleIntrospection for RingModulator {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        vec![
+            ParamInfo::toggle("oversample", "Oversample").with_value(if self.is_oversampling() {
+                1.0
+            } else {
+                0.0
+            }),
+        ]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "oversample" => {
+                self.set_oversampling(value > 0.5);
+                true
+            }
+            _ => false,
+        }
+    }
+}
 impl ModuleIntrospection for Rectifier {}
 impl ModuleIntrospection for Crosstalk {}
+impl ModuleIntrospection for FeedbackMixer {}
 
 // Logic & Random
 impl ModuleIntrospection for LogicAnd {}
 impl ModuleIntrospection for LogicOr {}
 impl ModuleIntrospection for LogicXor {}
 impl ModuleIntrospection for LogicNot {}
+impl ModuleIntrospection for TriggerUtil {}
+impl ModuleIntrospection for TriggerMerge {}
 impl ModuleIntrospection for Comparator {}
 impl ModuleIntrospection for BernoulliGate {}
+impl ModuleIntrospection for DrumLogic {}
+impl ModuleIntrospection for GateDropout {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        vec![
+            ParamInfo::toggle("inverse", "Inverse").with_value(if self.is_inverse() {
+                1.0
+            } else {
+                0.0
+            }),
+        ]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "inverse" => {
+                self.set_inverse(value > 0.5);
+                true
+            }
+            _ => false,
+        }
+    }
+}
 
 // Sequencing & I/O
 impl ModuleIntrospection for Clock {}
+impl ModuleIntrospection for ClockGroove {}
+impl ModuleIntrospection for ClockRatchet {}
+impl ModuleIntrospection for NoteRepeat {}
 impl ModuleIntrospection for StereoOutput {}
+impl ModuleIntrospection for SmartWidener {}
+impl ModuleIntrospection for MultibandImager {}
 impl ModuleIntrospection for Arpeggiator {}
 
 // Phase 4: Advanced DSP Modules (all CV-controlled)
 impl ModuleIntrospection for ChordMemory {}
 impl ModuleIntrospection for ParametricEq {}
+impl ModuleIntrospection for AWeight {}
+impl ModuleIntrospection for CWeight {}
+impl ModuleIntrospection for LoudnessComp {}
 impl ModuleIntrospection for Wavetable {}
 impl ModuleIntrospection for FormantOsc {}
 impl ModuleIntrospection for PitchShifter {}
 impl ModuleIntrospection for Reverb {}
+impl ModuleIntrospection for DelayLine {}
 impl ModuleIntrospection for Vocoder {}
+impl ModuleIntrospection for SpectralMorph {}
+impl ModuleIntrospection for Convolution {}
+impl ModuleIntrospection for VintageSampler {}
 impl ModuleIntrospection for Granular {}
+impl ModuleIntrospection for SamplePlayer {}
 
 // =============================================================================
 // Modules with Parameters
@@ -126,9 +221,9 @@ impl ModuleIntrospection for NoiseGenerator {
 
 impl ModuleIntrospection for StepSequencer {
     fn param_infos(&self) -> Vec<ParamInfo> {
-        let mut params = Vec::with_capacity(16);
+        let mut params = Vec::with_capacity(self.length() * 3);
 
-        for i in 0..8 {
+        for i in 0..self.length() {
             if let Some((voltage, _gate)) = self.get_step(i) {
                 params.push(
                     ParamInfo::new(
@@ -146,7 +241,7 @@ impl ModuleIntrospection for StepSequencer {
             }
         }
 
-        for i in 0..8 {
+        for i in 0..self.length() {
             if let Some((_voltage, gate)) = self.get_step(i) {
                 params.push(
                     ParamInfo::toggle(
@@ -158,6 +253,24 @@ impl ModuleIntrospection for StepSequencer {
             }
         }
 
+        for i in 0..self.length() {
+            if let Some(count) = self.ratchet(i) {
+                params.push(
+                    ParamInfo::new(
+                        alloc::format!("step_{}_ratchet", i),
+                        alloc::format!("Step {} Ratchet", i + 1),
+                    )
+                    .with_range(1.0, StepSequencer::MAX_RATCHET as f64)
+                    .with_default(1.0)
+                    .with_value(count as f64)
+                    .with_curve(ParamCurve::Stepped {
+                        steps: StepSequencer::MAX_RATCHET as u32,
+                    })
+                    .with_format(ValueFormat::Decimal { places: 0 }),
+                );
+            }
+        }
+
         params
     }
 
@@ -165,7 +278,7 @@ impl ModuleIntrospection for StepSequencer {
         if let Some(rest) = id.strip_prefix("step_") {
             if let Some((num_str, param_type)) = rest.split_once('_') {
                 if let Ok(step_idx) = num_str.parse::<usize>() {
-                    if step_idx < 8 {
+                    if step_idx < self.length() {
                         if let Some((current_cv, current_gate)) = self.get_step(step_idx) {
                             match param_type {
                                 "cv" => {
@@ -176,6 +289,10 @@ impl ModuleIntrospection for StepSequencer {
                                     self.set_step(step_idx, current_cv, value > 0.5);
                                     return true;
                                 }
+                                "ratchet" => {
+                                    self.set_ratchet(step_idx, Libm::<f64>::round(value) as usize);
+                                    return true;
+                                }
                                 _ => {}
                             }
                         }
@@ -187,38 +304,138 @@ impl ModuleIntrospection for StepSequencer {
     }
 }
 
-impl ModuleIntrospection for Quantizer {
+impl ModuleIntrospection for GateLengthSequencer {
     fn param_infos(&self) -> Vec<ParamInfo> {
-        let scale_value = match self.scale {
-            Scale::Chromatic => 0.0,
-            Scale::Major => 1.0,
-            Scale::Minor => 2.0,
-            Scale::PentatonicMajor => 3.0,
-            Scale::PentatonicMinor => 4.0,
-            Scale::Dorian => 5.0,
-            Scale::Mixolydian => 6.0,
-            Scale::Blues => 7.0,
-        };
-        vec![ParamInfo::select("scale", "Scale", 8).with_value(scale_value)]
+        let mut params = Vec::with_capacity(16);
+
+        for i in 0..8 {
+            if let Some((voltage, _length)) = self.get_step(i) {
+                params.push(
+                    ParamInfo::new(
+                        alloc::format!("step_{}_cv", i),
+                        alloc::format!("Step {} CV", i + 1),
+                    )
+                    .with_range(-5.0, 5.0)
+                    .with_default(0.0)
+                    .with_value(voltage)
+                    .with_curve(ParamCurve::Linear)
+                    .with_control(ControlType::Slider)
+                    .with_unit("V")
+                    .with_format(ValueFormat::NoteName),
+                );
+            }
+        }
+
+        for i in 0..8 {
+            if let Some((_voltage, length)) = self.get_step(i) {
+                params.push(
+                    ParamInfo::new(
+                        alloc::format!("step_{}_length", i),
+                        alloc::format!("Step {} Length", i + 1),
+                    )
+                    .with_range(0.0, 4.0)
+                    .with_default(0.5)
+                    .with_value(length)
+                    .with_curve(ParamCurve::Linear)
+                    .with_format(ValueFormat::Percent),
+                );
+            }
+        }
+
+        params
     }
 
     fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
-        match id {
-            "scale" => {
-                let scale = match value as u8 {
-                    0 => Scale::Chromatic,
-                    1 => Scale::Major,
-                    2 => Scale::Minor,
-                    3 => Scale::PentatonicMajor,
-                    4 => Scale::PentatonicMinor,
-                    5 => Scale::Dorian,
-                    6 => Scale::Mixolydian,
-                    7 => Scale::Blues,
-                    _ => return false,
-                };
-                self.set_scale(scale);
+        let Some((num_str, param_type)) = id
+            .strip_prefix("step_")
+            .and_then(|rest| rest.split_once('_'))
+        else {
+            return false;
+        };
+        let Some((step_idx, (current_cv, current_length))) = num_str
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| self.get_step(i).map(|step| (i, step)))
+        else {
+            return false;
+        };
+        match param_type {
+            "cv" => {
+                self.set_step(step_idx, value, current_length);
                 true
             }
+            "length" => {
+                self.set_step(step_idx, current_cv, value);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Select index of a quantizer scale
+fn scale_index(scale: Scale) -> f64 {
+    scale.index() as f64
+}
+
+/// Quantizer scale for a select index
+fn scale_from_index(value: f64) -> Option<Scale> {
+    let idx = value as usize;
+    (value >= 0.0 && idx < Scale::ALL.len()).then(|| Scale::from_index(idx))
+}
+
+impl ModuleIntrospection for Quantizer {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        vec![ParamInfo::select("scale", "Scale", 8).with_value(scale_index(self.scale))]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "scale" => match scale_from_index(value) {
+                Some(scale) => {
+                    self.set_scale(scale);
+                    true
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl ModuleIntrospection for SmartQuantizer {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        vec![ParamInfo::select("scale", "Scale", 8).with_value(scale_index(self.scale()))]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "scale" => match scale_from_index(value) {
+                Some(scale) => {
+                    self.set_scale(scale);
+                    true
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl ModuleIntrospection for WeightedQuantizer {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        vec![ParamInfo::select("scale", "Scale", 8).with_value(scale_index(self.scale()))]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "scale" => match scale_from_index(value) {
+                Some(scale) => {
+                    self.set_scale(scale);
+                    true
+                }
+                None => false,
+            },
             _ => false,
         }
     }
@@ -287,6 +504,236 @@ impl ModuleIntrospection for Wavefolder {
     }
 }
 
+impl ModuleIntrospection for TableWaveshaper {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        let value = match self.interpolation() {
+            TableInterpolation::Linear => 0.0,
+            TableInterpolation::Cubic => 1.0,
+        };
+        vec![ParamInfo::select("interpolation", "Interpolation", 2).with_value(value)]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "interpolation" => {
+                self.set_interpolation(if value > 0.5 {
+                    TableInterpolation::Cubic
+                } else {
+                    TableInterpolation::Linear
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ModuleIntrospection for Supersaw {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        let value = match self.voices() {
+            3 => 0.0,
+            5 => 1.0,
+            _ => 2.0,
+        };
+        vec![ParamInfo::select("voices", "Voices", 3)
+            .with_default(2.0)
+            .with_value(value)]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "voices" => {
+                self.set_voices(3 + 2 * (Libm::<f64>::round(value).clamp(0.0, 2.0) as usize));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ModuleIntrospection for PhaseDistortionOsc {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        vec![ParamInfo::select("waveform", "Waveform", 4)
+            .with_default(0.0)
+            .with_value(self.waveform().index() as f64)]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "waveform" => {
+                let idx = Libm::<f64>::round(value).clamp(0.0, 3.0) as usize;
+                self.set_waveform(PdWaveform::from_index(idx));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ModuleIntrospection for WaveTerrain {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        let terrain = self.terrain().map_or(0, Terrain::index);
+        vec![
+            ParamInfo::select("terrain", "Terrain", 3)
+                .with_default(0.0)
+                .with_value(terrain as f64),
+            ParamInfo::new("ratio", "Y Ratio")
+                .with_range(WaveTerrain::MIN_RATIO, WaveTerrain::MAX_RATIO)
+                .with_default(2.0)
+                .with_value(self.ratio())
+                .with_curve(ParamCurve::Exponential)
+                .with_control(ControlType::Knob)
+                .with_format(ValueFormat::Ratio),
+        ]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "terrain" => {
+                let idx = Libm::<f64>::round(value).clamp(0.0, 2.0) as usize;
+                self.set_terrain(Terrain::from_index(idx));
+                true
+            }
+            "ratio" => {
+                self.set_ratio(value);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ModuleIntrospection for Waveguide {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        vec![ParamInfo::select("model", "Model", 2)
+            .with_default(0.0)
+            .with_value(self.model().index() as f64)]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "model" => {
+                let idx = Libm::<f64>::round(value).clamp(0.0, 1.0) as usize;
+                self.set_model(WindModel::from_index(idx));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ModuleIntrospection for ResponseCurve {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        let (curve, gamma) = match self.shape() {
+            CurveShape::Linear => (0.0, 1.0),
+            CurveShape::Exponential => (1.0, 1.0),
+            CurveShape::Logarithmic => (2.0, 1.0),
+            CurveShape::SCurve => (3.0, 1.0),
+            CurveShape::Gamma(gamma) => (4.0, gamma),
+        };
+        vec![
+            ParamInfo::select("curve", "Curve", 5).with_value(curve),
+            ParamInfo::new("gamma", "Gamma")
+                .with_range(0.1, 10.0)
+                .with_default(1.0)
+                .with_value(gamma)
+                .with_curve(ParamCurve::Exponential)
+                .with_control(ControlType::Knob)
+                .with_format(ValueFormat::Ratio),
+        ]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "curve" => {
+                let gamma = match self.shape() {
+                    CurveShape::Gamma(gamma) => gamma,
+                    _ => 1.0,
+                };
+                self.set_shape(match Libm::<f64>::round(value) as i64 {
+                    i64::MIN..=0 => CurveShape::Linear,
+                    1 => CurveShape::Exponential,
+                    2 => CurveShape::Logarithmic,
+                    3 => CurveShape::SCurve,
+                    _ => CurveShape::Gamma(gamma),
+                });
+                true
+            }
+            "gamma" => {
+                self.set_shape(CurveShape::Gamma(value));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ModuleIntrospection for VcSlew {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        let shape = match self.shape() {
+            SlewShape::Linear => 0.0,
+            SlewShape::Exponential => 1.0,
+            SlewShape::Logarithmic => 2.0,
+        };
+        vec![ParamInfo::select("shape", "Shape", 3).with_value(shape)]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "shape" => {
+                self.set_shape(match Libm::<f64>::round(value) as i64 {
+                    i64::MIN..=0 => SlewShape::Linear,
+                    1 => SlewShape::Exponential,
+                    _ => SlewShape::Logarithmic,
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ModuleIntrospection for CombFilter {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        vec![ParamInfo::frequency("base_freq", "Tune")
+            .with_range(CombFilter::MIN_FREQ, 20000.0)
+            .with_default(CombFilter::DEFAULT_FREQ)
+            .with_value(self.base_freq())]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "base_freq" => {
+                self.set_base_freq(value);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ModuleIntrospection for DeClick {
+    fn param_infos(&self) -> Vec<ParamInfo> {
+        vec![ParamInfo::time("window", "Window")
+            .with_range(
+                DeClick::MIN_WINDOW_MS / 1000.0,
+                DeClick::MAX_WINDOW_MS / 1000.0,
+            )
+            .with_default(0.002)
+            .with_value(self.window_ms() / 1000.0)]
+    }
+
+    fn set_param_by_id(&mut self, id: &str, value: f64) -> bool {
+        match id {
+            "window" => {
+                self.set_window_ms(value * 1000.0);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -294,6 +741,7 @@ impl ModuleIntrospection for Wavefolder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::port::GraphModule;
 
     #[test]
     fn test_offset_introspection() {
@@ -310,12 +758,12 @@ mod tests {
 
     #[test]
     fn test_step_sequencer_introspection() {
-        let mut seq = StepSequencer::new();
+        let mut seq = StepSequencer::new(8);
         seq.set_step(0, 1.0, true);
         seq.set_step(1, -0.5, false);
 
         let params = seq.param_infos();
-        assert_eq!(params.len(), 16);
+        assert_eq!(params.len(), 24);
 
         let step0_cv = params.iter().find(|p| p.id == "step_0_cv").unwrap();
         assert_eq!(step0_cv.value, 1.0);
@@ -327,6 +775,23 @@ mod tests {
         assert_eq!(seq.get_step(2).unwrap().0, 2.5);
     }
 
+    #[test]
+    fn test_gate_length_sequencer_introspection() {
+        let mut seq = GateLengthSequencer::new();
+        seq.set_step(0, 1.0, 1.5);
+
+        let params = seq.param_infos();
+        assert_eq!(params.len(), 16);
+        let step0_length = params.iter().find(|p| p.id == "step_0_length").unwrap();
+        assert_eq!(step0_length.value, 1.5);
+
+        assert!(seq.set_param_by_id("step_3_length", 0.25));
+        assert_eq!(seq.gate_length(3), Some(0.25));
+        assert!(seq.set_param_by_id("step_3_cv", 2.0));
+        assert_eq!(seq.get_step(3), Some((2.0, 0.25)));
+        assert!(!seq.set_param_by_id("step_9_cv", 0.0));
+    }
+
     #[test]
     fn test_quantizer_introspection() {
         let mut quant = Quantizer::major();
@@ -372,13 +837,111 @@ mod tests {
         assert_eq!(gl.param_infos()[0].value, 1.0);
     }
 
+    #[test]
+    fn test_table_waveshaper_introspection() {
+        let mut shaper = TableWaveshaper::new();
+        assert_eq!(shaper.param_infos()[0].value, 0.0);
+
+        assert!(shaper.set_param_by_id("interpolation", 1.0));
+        assert_eq!(shaper.interpolation(), TableInterpolation::Cubic);
+        assert!(!shaper.set_param_by_id("invalid", 0.0));
+    }
+
+    #[test]
+    fn test_supersaw_introspection() {
+        let mut supersaw = Supersaw::new(44100.0);
+        assert_eq!(supersaw.param_infos()[0].value, 2.0);
+
+        assert!(supersaw.set_param_by_id("voices", 0.0));
+        assert_eq!(supersaw.voices(), 3);
+        assert!(supersaw.set_param_by_id("voices", 1.0));
+        assert_eq!(supersaw.voices(), 5);
+    }
+
+    #[test]
+    fn test_response_curve_introspection() {
+        let mut curve = ResponseCurve::new();
+        assert_eq!(curve.param_infos().len(), 2);
+
+        assert!(curve.set_param_by_id("curve", 3.0));
+        assert_eq!(curve.shape(), CurveShape::SCurve);
+        assert!(curve.set_param_by_id("gamma", 2.2));
+        assert_eq!(curve.shape(), CurveShape::Gamma(2.2));
+        assert_eq!(curve.param_infos()[0].value, 4.0);
+    }
+
+    #[test]
+    fn test_declick_introspection() {
+        let mut declick = DeClick::new(44100.0);
+        assert!((declick.param_infos()[0].value - 0.002).abs() < 1e-12);
+
+        assert!(declick.set_param_by_id("window", 0.01));
+        assert!((declick.window_ms() - 10.0).abs() < 1e-9);
+        assert_eq!(declick.latency(), 441);
+    }
+
+    #[test]
+    fn test_vco_introspection() {
+        let mut vco = Vco::new(44100.0);
+        let params = vco.param_infos();
+        let ids: Vec<&str> = params.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, ["frequency", "pulse_width", "glide"]);
+
+        let freq = &params[0];
+        assert_eq!(freq.name, "Frequency");
+        assert_eq!(freq.curve, ParamCurve::Exponential);
+        assert_eq!(freq.format, ValueFormat::Frequency);
+        assert_eq!(freq.unit.as_deref(), Some("Hz"));
+        assert!((freq.min - 261.63 / 32.0).abs() < 1e-9);
+        assert!((freq.max - 261.63 * 32.0).abs() < 1e-9);
+        assert!((freq.value - 261.63).abs() < 1e-9);
+
+        let pw = &params[1];
+        assert_eq!(pw.name, "Pulse Width");
+        assert_eq!((pw.min, pw.max, pw.value), (0.0, 1.0, 0.5));
+        assert_eq!(pw.curve, ParamCurve::Linear);
+        assert_eq!(pw.format, ValueFormat::Percent);
+
+        let glide = &params[2];
+        assert_eq!((glide.min, glide.max), (0.0, Vco::MAX_GLIDE_SECS));
+        assert_eq!(glide.format, ValueFormat::Time);
+
+        // The frequency knob is the V/Oct input's unpatched default
+        assert!(vco.set_param_by_id("frequency", 523.26));
+        let voct = vco.port_spec().inputs[0].default;
+        assert!((voct - 1.0).abs() < 1e-9);
+        assert!((vco.param_infos()[0].value - 523.26).abs() < 1e-9);
+        assert!(!vco.set_param_by_id("cutoff", 1.0));
+    }
+
+    #[test]
+    fn test_knobs_follow_module_cv_scaling() {
+        // The knob default matches the input default through the range
+        let svf = Svf::new(44100.0);
+        let cutoff = svf.get_param_info("cutoff").unwrap();
+        assert!((cutoff.value - 20.0 * 1000f64.sqrt()).abs() < 1e-9);
+
+        let mut adsr = Adsr::new(44100.0);
+        assert!(adsr.set_param_by_id("attack", 0.1));
+        assert!((adsr.port_spec().inputs[2].default - 0.5).abs() < 1e-9);
+        // Values beyond the range clamp to it
+        assert!(adsr.set_param_by_id("sustain", 2.0));
+        assert_eq!(adsr.get_param_info("sustain").unwrap().value, 1.0);
+
+        let names = |infos: Vec<ParamInfo>| infos.into_iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_eq!(names(Lfo::default().param_infos()), ["rate"]);
+        assert_eq!(
+            names(DelayLine::default().param_infos()),
+            ["time", "feedback", "mix"]
+        );
+        assert_eq!(
+            names(Reverb::new(44100.0).param_infos()),
+            ["size", "damping", "mix", "predelay"]
+        );
+    }
+
     #[test]
     fn test_cv_controlled_modules_have_no_params() {
-        assert!(Vco::default().param_infos().is_empty());
-        assert!(Lfo::default().param_infos().is_empty());
-        assert!(Svf::default().param_infos().is_empty());
-        assert!(Adsr::default().param_infos().is_empty());
-        assert!(Vca::default().param_infos().is_empty());
         assert!(Clock::default().param_infos().is_empty());
         assert!(LogicAnd::default().param_infos().is_empty());
     }
@@ -3,7 +3,12 @@
 //! This module provides components for bridging the patch graph with
 //! external systems: MIDI controllers, audio interfaces, etc.
 
-use crate::port::{GraphModule, PortDef, PortSpec, PortValues, SignalKind};
+use crate::port::{
+    GraphModule, PortDef, PortSpec, PortValues, RuntimeReader, RuntimeWriter, SignalKind,
+};
+use crate::transport::Transport;
+use alloc::collections::VecDeque;
+use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
@@ -128,6 +133,10 @@ impl GraphModule for ExternalInput {
     fn type_id(&self) -> &'static str {
         "external_input"
     }
+
+    fn supports_runtime_state(&self) -> bool {
+        true
+    }
 }
 
 /// MIDI state that can be updated from a MIDI thread
@@ -160,8 +169,61 @@ pub struct MidiState {
     /// Expression pedal (0-10V)
     pub expression: Arc<AtomicF64>,
 
+    /// Tempo derived from incoming MIDI clock (BPM, 0 until measured)
+    pub clock_bpm: Arc<AtomicF64>,
+
+    /// MIDI clock run state (5V after Start/Continue, 0V after Stop)
+    pub clock_running: Arc<AtomicF64>,
+
+    /// 24 PPQN clock pulse (5V for half a clock interval)
+    pub clock_pulse: Arc<AtomicF64>,
+
+    /// Quarter-note pulse, every 24 clocks (5V for half a quarter note)
+    pub quarter_pulse: Arc<AtomicF64>,
+
     // Internal state for note handling
     held_notes: Vec<u8>,
+    /// Note-ons received, shared with `MidiToCv` to detect retriggers
+    note_ons: Arc<AtomicU64>,
+
+    // Internal state for MIDI clock
+    clock: MidiClockState,
+}
+
+/// Bookkeeping for MIDI clock parsing (times in samples)
+#[derive(Debug, Clone)]
+struct MidiClockState {
+    sample_rate: f64,
+    now: u64,
+    /// Queued real-time messages, at most `MAX_PENDING` so queuing never allocates
+    pending: VecDeque<(u64, u8)>,
+    running: bool,
+    count: u64,
+    last_clock: Option<u64>,
+    /// Smoothed samples per clock
+    interval: Option<f64>,
+    pulse_until: u64,
+    quarter_until: u64,
+}
+
+impl MidiClockState {
+    /// Real-time messages held ahead of `process_clock`: over a second of
+    /// 24 PPQN clock at 300 BPM
+    const MAX_PENDING: usize = 256;
+
+    fn new(sample_rate: f64) -> Self {
+        Self {
+            sample_rate,
+            now: 0,
+            pending: VecDeque::with_capacity(Self::MAX_PENDING),
+            running: false,
+            count: 0,
+            last_clock: None,
+            interval: None,
+            pulse_until: 0,
+            quarter_until: 0,
+        }
+    }
 }
 
 impl MidiState {
@@ -176,7 +238,168 @@ impl MidiState {
             aftertouch: Arc::new(AtomicF64::new(0.0)),
             sustain: Arc::new(AtomicF64::new(0.0)),
             expression: Arc::new(AtomicF64::new(10.0)),
+            clock_bpm: Arc::new(AtomicF64::new(0.0)),
+            clock_running: Arc::new(AtomicF64::new(0.0)),
+            clock_pulse: Arc::new(AtomicF64::new(0.0)),
+            quarter_pulse: Arc::new(AtomicF64::new(0.0)),
             held_notes: Vec::new(),
+            note_ons: Arc::new(AtomicU64::new(0)),
+            clock: MidiClockState::new(44100.0),
+        }
+    }
+
+    /// MIDI clock pulses per quarter note
+    pub const CLOCKS_PER_QUARTER: u64 = 24;
+
+    /// Weight of each new clock interval in the tempo estimate
+    const CLOCK_SMOOTHING: f64 = 0.1;
+
+    /// Set the sample rate used to convert clock timestamps to tempo
+    pub fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.clock.sample_rate = sample_rate;
+        self.update_clock_bpm();
+    }
+
+    /// Process a MIDI message received at a sample timestamp
+    ///
+    /// System real-time messages (clock, start, continue, stop) are queued
+    /// and take effect when [`process_clock`](Self::process_clock) reaches
+    /// their timestamp, so pulses land on the exact sample. Other messages
+    /// are applied immediately. The queue holds a fixed number of messages;
+    /// when it is full the oldest is applied early rather than dropped.
+    pub fn handle_message_at(&mut self, msg: &[u8], timestamp: u64) {
+        match msg.first() {
+            Some(&status @ (0xF8 | 0xFA | 0xFB | 0xFC)) => {
+                if self.clock.pending.len() >= MidiClockState::MAX_PENDING {
+                    if let Some((oldest, oldest_status)) = self.clock.pending.pop_front() {
+                        self.apply_realtime(oldest_status, oldest);
+                    }
+                }
+                // Keep the queue ordered if timestamps arrive out of order
+                let idx = self
+                    .clock
+                    .pending
+                    .iter()
+                    .position(|&(t, _)| t > timestamp)
+                    .unwrap_or(self.clock.pending.len());
+                self.clock.pending.insert(idx, (timestamp, status));
+            }
+            _ => self.handle_message(msg),
+        }
+    }
+
+    /// Advance MIDI clock processing to the sample time `now`
+    ///
+    /// Applies queued real-time messages up to `now` and updates the clock
+    /// outputs. Call once per sample (or per block) from the audio thread.
+    pub fn process_clock(&mut self, now: u64) {
+        self.clock.now = now;
+        while let Some(&(timestamp, status)) = self.clock.pending.front() {
+            if timestamp > now {
+                break;
+            }
+            self.clock.pending.pop_front();
+            self.apply_realtime(status, timestamp);
+        }
+
+        let active = self.clock.running;
+        self.clock_pulse
+            .set(if active && now < self.clock.pulse_until {
+                5.0
+            } else {
+                0.0
+            });
+        self.quarter_pulse
+            .set(if active && now < self.clock.quarter_until {
+                5.0
+            } else {
+                0.0
+            });
+    }
+
+    fn apply_realtime(&mut self, status: u8, timestamp: u64) {
+        let clock = &mut self.clock;
+        match status {
+            // Timing clock
+            0xF8 => {
+                if let Some(last) = clock.last_clock {
+                    let raw = timestamp.saturating_sub(last) as f64;
+                    clock.interval = match clock.interval {
+                        // Long gaps (tempo jumps, paused senders) restart the estimate
+                        Some(interval) if raw > 0.0 && raw < interval * 4.0 => {
+                            Some(interval + (raw - interval) * Self::CLOCK_SMOOTHING)
+                        }
+                        _ if raw > 0.0 => Some(raw),
+                        other => other,
+                    };
+                }
+                clock.last_clock = Some(timestamp);
+
+                if clock.running {
+                    let interval = clock.interval.unwrap_or(0.0);
+                    clock.pulse_until = timestamp + ((interval * 0.5) as u64).max(1);
+                    if clock.count % Self::CLOCKS_PER_QUARTER == 0 {
+                        let quarter = interval * Self::CLOCKS_PER_QUARTER as f64;
+                        clock.quarter_until = timestamp + ((quarter * 0.5) as u64).max(1);
+                    }
+                    clock.count += 1;
+                }
+                self.update_clock_bpm();
+            }
+            // Start: restart from the top of the song
+            0xFA => {
+                clock.running = true;
+                clock.count = 0;
+                self.clock_running.set(5.0);
+            }
+            // Continue: resume from the current position
+            0xFB => {
+                clock.running = true;
+                self.clock_running.set(5.0);
+            }
+            // Stop
+            0xFC => {
+                clock.running = false;
+                self.clock_running.set(0.0);
+            }
+            _ => {}
+        }
+    }
+
+    fn update_clock_bpm(&mut self) {
+        if let Some(interval) = self.clock.interval {
+            let per_quarter = interval * Self::CLOCKS_PER_QUARTER as f64;
+            self.clock_bpm
+                .set(60.0 * self.clock.sample_rate / per_quarter);
+        }
+    }
+
+    /// Tempo derived from MIDI clock, if enough clocks have been received
+    pub fn clock_tempo(&self) -> Option<f64> {
+        self.clock.interval.map(|_| self.clock_bpm.get())
+    }
+
+    /// Whether MIDI clock is running (after Start/Continue, before Stop)
+    pub fn clock_is_running(&self) -> bool {
+        self.clock.running
+    }
+
+    /// Number of clocks received since the last Start
+    pub fn clock_count(&self) -> u64 {
+        self.clock.count
+    }
+
+    /// Follow the MIDI clock tempo and run state with a transport
+    pub fn sync_transport(&self, transport: &mut Transport) {
+        if let Some(bpm) = self.clock_tempo() {
+            transport.set_bpm(bpm);
+        }
+        if self.clock.running != transport.is_playing() {
+            if self.clock.running {
+                transport.play();
+            } else {
+                transport.stop();
+            }
         }
     }
 
@@ -191,6 +414,13 @@ impl MidiState {
         let status = msg[0] & 0xF0;
         let _channel = msg[0] & 0x0F;
 
+        // System real-time messages apply at the current clock time
+        if matches!(msg[0], 0xF8 | 0xFA | 0xFB | 0xFC) {
+            let now = self.clock.now;
+            self.apply_realtime(msg[0], now);
+            return;
+        }
+
         match (status, msg.len()) {
             // Note On (with velocity > 0)
             (0x90, 3) if msg[2] > 0 => {
@@ -201,6 +431,7 @@ impl MidiState {
                 self.pitch.set(Self::note_to_voct(note));
                 self.velocity.set(vel as f64 / 127.0 * 10.0);
                 self.gate.set(5.0);
+                self.note_ons.fetch_add(1, Ordering::Release);
             }
 
             // Note Off (or Note On with velocity 0)
@@ -274,6 +505,11 @@ impl MidiState {
         !self.held_notes.is_empty()
     }
 
+    /// Number of note-ons received since creation
+    pub fn note_on_count(&self) -> u64 {
+        self.note_ons.load(Ordering::Acquire)
+    }
+
     /// Reset all state
     pub fn reset(&mut self) {
         self.pitch.set(0.0);
@@ -284,7 +520,12 @@ impl MidiState {
         self.aftertouch.set(0.0);
         self.sustain.set(0.0);
         self.expression.set(10.0);
+        self.clock_bpm.set(0.0);
+        self.clock_running.set(0.0);
+        self.clock_pulse.set(0.0);
+        self.quarter_pulse.set(0.0);
         self.held_notes.clear();
+        self.clock = MidiClockState::new(self.clock.sample_rate);
     }
 
     /// All notes off
@@ -311,11 +552,166 @@ impl Clone for MidiState {
             aftertouch: Arc::new(AtomicF64::new(self.aftertouch.get())),
             sustain: Arc::new(AtomicF64::new(self.sustain.get())),
             expression: Arc::new(AtomicF64::new(self.expression.get())),
+            clock_bpm: Arc::new(AtomicF64::new(self.clock_bpm.get())),
+            clock_running: Arc::new(AtomicF64::new(self.clock_running.get())),
+            clock_pulse: Arc::new(AtomicF64::new(self.clock_pulse.get())),
+            quarter_pulse: Arc::new(AtomicF64::new(self.quarter_pulse.get())),
             held_notes: self.held_notes.clone(),
+            note_ons: Arc::new(AtomicU64::new(self.note_on_count())),
+            clock: self.clock.clone(),
         }
     }
 }
 
+/// MIDI to CV converter
+///
+/// Turns a `MidiState` into patchable voltages for a monophonic voice. The
+/// module shares the state's atomic values, so the MIDI thread keeps
+/// calling `handle_message` on its `MidiState` while the audio thread reads
+/// the latest values here without locking.
+///
+/// Pitch follows the last note pressed; releasing it falls back to the most
+/// recent note still held. A note pressed while another is held retriggers:
+/// the gate drops for one sample so envelopes restart, and `trig` fires.
+/// With retriggering off, overlapping notes play legato on a held gate. The
+/// gate drops when the last held note is released.
+///
+/// # Ports
+/// - Output 10: Pitch (V/Oct, 0V = C4)
+/// - Output 11: Gate (0 or 5V)
+/// - Output 12: Velocity (0-10V)
+/// - Output 13: Mod wheel (0-10V)
+/// - Output 14: Pitch bend (±2 semitones as V/Oct)
+/// - Output 15: Note-on trigger
+pub struct MidiToCv {
+    pitch: Arc<AtomicF64>,
+    gate: Arc<AtomicF64>,
+    velocity: Arc<AtomicF64>,
+    mod_wheel: Arc<AtomicF64>,
+    pitch_bend: Arc<AtomicF64>,
+    note_ons: Arc<AtomicU64>,
+    seen_note_ons: u64,
+    last_gate: f64,
+    retrigger: bool,
+    trigger_countdown: usize,
+    sample_rate: f64,
+    spec: PortSpec,
+}
+
+impl MidiToCv {
+    const TRIGGER_MS: f64 = 1.0;
+
+    /// Read voltages from `midi`, which stays usable by the MIDI thread
+    pub fn new(midi: &MidiState, sample_rate: f64) -> Self {
+        let note_ons = Arc::clone(&midi.note_ons);
+        Self {
+            pitch: Arc::clone(&midi.pitch),
+            gate: Arc::clone(&midi.gate),
+            velocity: Arc::clone(&midi.velocity),
+            mod_wheel: Arc::clone(&midi.mod_wheel),
+            pitch_bend: Arc::clone(&midi.pitch_bend),
+            seen_note_ons: note_ons.load(Ordering::Acquire),
+            note_ons,
+            last_gate: 0.0,
+            retrigger: true,
+            trigger_countdown: 0,
+            sample_rate,
+            spec: PortSpec {
+                inputs: vec![],
+                outputs: vec![
+                    PortDef::new(10, "voct", SignalKind::VoltPerOctave),
+                    PortDef::new(11, "gate", SignalKind::Gate),
+                    PortDef::new(12, "velocity", SignalKind::CvUnipolar),
+                    PortDef::new(13, "mod_wheel", SignalKind::CvUnipolar),
+                    PortDef::new(14, "pitch_bend", SignalKind::CvBipolar),
+                    PortDef::new(15, "trig", SignalKind::Trigger),
+                ],
+            },
+        }
+    }
+
+    /// Builder: retrigger the gate on overlapping notes (on by default)
+    pub fn with_retrigger(mut self, retrigger: bool) -> Self {
+        self.retrigger = retrigger;
+        self
+    }
+
+    pub fn set_retrigger(&mut self, retrigger: bool) {
+        self.retrigger = retrigger;
+    }
+
+    pub fn retrigger(&self) -> bool {
+        self.retrigger
+    }
+}
+
+impl GraphModule for MidiToCv {
+    fn port_spec(&self) -> &PortSpec {
+        &self.spec
+    }
+
+    fn tick(&mut self, _inputs: &PortValues, outputs: &mut PortValues) {
+        let mut gate = self.gate.get();
+
+        let note_ons = self.note_ons.load(Ordering::Acquire);
+        if note_ons != self.seen_note_ons {
+            // Gate already high from a held note: drop it for this sample
+            if self.retrigger && self.last_gate > 2.5 {
+                gate = 0.0;
+            }
+            self.seen_note_ons = note_ons;
+            self.trigger_countdown =
+                ((Self::TRIGGER_MS * self.sample_rate / 1000.0) as usize).max(1);
+        }
+
+        let trig = if self.trigger_countdown > 0 {
+            self.trigger_countdown -= 1;
+            5.0
+        } else {
+            0.0
+        };
+
+        self.last_gate = gate;
+        outputs.set(10, self.pitch.get());
+        outputs.set(11, gate);
+        outputs.set(12, self.velocity.get());
+        outputs.set(13, self.mod_wheel.get());
+        outputs.set(14, self.pitch_bend.get());
+        outputs.set(15, trig);
+    }
+
+    fn reset(&mut self) {
+        self.seen_note_ons = self.note_ons.load(Ordering::Acquire);
+        self.last_gate = 0.0;
+        self.trigger_countdown = 0;
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+    }
+
+    fn type_id(&self) -> &'static str {
+        "midi_to_cv"
+    }
+
+    fn supports_runtime_state(&self) -> bool {
+        true
+    }
+
+    fn serialize_runtime(&self, state: &mut RuntimeWriter) {
+        state.put_f64(self.last_gate);
+        state.put_usize(self.trigger_countdown);
+    }
+
+    fn deserialize_runtime(&mut self, state: &mut RuntimeReader<'_>) -> Result<(), String> {
+        self.last_gate = state.f64()?;
+        self.trigger_countdown = state.usize()?;
+        // The note-on counter belongs to the host: resync so a restore fires no trigger
+        self.seen_note_ons = self.note_ons.load(Ordering::Acquire);
+        Ok(())
+    }
+}
+
 /// External output - writes to an atomic value for reading by another thread
 ///
 /// Useful for sending CV values out to external systems.
@@ -359,6 +755,10 @@ impl GraphModule for ExternalOutput {
     fn type_id(&self) -> &'static str {
         "external_output"
     }
+
+    fn supports_runtime_state(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -617,4 +1017,134 @@ mod tests {
         midi.handle_message(&[0x90, 60, 0]);
         assert!(midi.gate.get().abs() < 0.001);
     }
+
+    #[test]
+    fn test_midi_to_cv_last_note_priority_and_note_off() {
+        let mut midi = MidiState::new();
+        let mut cv = MidiToCv::new(&midi, 1000.0);
+        let inputs = PortValues::new();
+        let mut outputs = PortValues::new();
+        let tick = |cv: &mut MidiToCv, outputs: &mut PortValues| {
+            cv.tick(&inputs, outputs);
+            (outputs.get(10).unwrap(), outputs.get(11).unwrap())
+        };
+
+        assert_eq!(tick(&mut cv, &mut outputs), (0.0, 0.0));
+
+        midi.handle_message(&[0x90, 72, 127]);
+        assert_eq!(tick(&mut cv, &mut outputs), (1.0, 5.0));
+        assert_eq!(outputs.get(12), Some(10.0));
+        assert_eq!(outputs.get(15), Some(5.0));
+
+        // A second note takes over the pitch, dropping the gate for one sample
+        midi.handle_message(&[0x90, 48, 64]);
+        assert_eq!(tick(&mut cv, &mut outputs), (-1.0, 0.0));
+        assert_eq!(tick(&mut cv, &mut outputs), (-1.0, 5.0));
+
+        // Releasing it falls back to the note still held, without a retrigger
+        midi.handle_message(&[0x80, 48, 0]);
+        assert_eq!(tick(&mut cv, &mut outputs), (1.0, 5.0));
+
+        // Releasing the last note drops the gate and holds the pitch
+        midi.handle_message(&[0x80, 72, 0]);
+        assert_eq!(tick(&mut cv, &mut outputs), (1.0, 0.0));
+
+        midi.handle_message(&[0xB0, 1, 127]);
+        midi.handle_message(&[0xE0, 0, 0]);
+        tick(&mut cv, &mut outputs);
+        assert_eq!(outputs.get(13), Some(10.0));
+        assert!((outputs.get(14).unwrap() + 2.0 / 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_midi_to_cv_legato_without_retrigger() {
+        let mut midi = MidiState::new();
+        let mut cv = MidiToCv::new(&midi, 1000.0).with_retrigger(false);
+        let inputs = PortValues::new();
+        let mut outputs = PortValues::new();
+
+        midi.handle_message(&[0x90, 60, 100]);
+        cv.tick(&inputs, &mut outputs);
+        midi.handle_message(&[0x90, 67, 100]);
+        cv.tick(&inputs, &mut outputs);
+        assert_eq!(outputs.get(11), Some(5.0));
+        assert!((outputs.get(10).unwrap() - 7.0 / 12.0).abs() < 1e-9);
+        // The note-on trigger still fires for each new note
+        assert_eq!(outputs.get(15), Some(5.0));
+    }
+
+    #[test]
+    fn test_midi_clock_queue_is_bounded() {
+        let mut midi = MidiState::new();
+        midi.set_sample_rate(48000.0);
+        let capacity = midi.clock.pending.capacity();
+
+        midi.handle_message_at(&[0xFA], 0);
+        for i in 0..1000u64 {
+            midi.handle_message_at(&[0xF8], 500 + i * 1000);
+        }
+        assert!(midi.clock.pending.len() <= MidiClockState::MAX_PENDING);
+        assert_eq!(midi.clock.pending.capacity(), capacity);
+
+        // Overflowed clocks were applied, not lost
+        midi.process_clock(2_000_000);
+        assert_eq!(midi.clock.count, 1000);
+        assert!((midi.clock_bpm.get() - 120.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_midi_clock_tempo_and_quarter_pulse() {
+        // 120 BPM at 48kHz = 24000 samples per quarter = 1000 samples per clock
+        let mut midi = MidiState::new();
+        midi.set_sample_rate(48000.0);
+        midi.handle_message_at(&[0xFA], 0);
+
+        // Jittery clock: +-30 samples around the true interval
+        let jitter = [0i64, 30, -20, 10, -30, 25, -15, 0];
+        for i in 0..96u64 {
+            let timestamp = (i as i64 * 1000 + 500 + jitter[i as usize % 8]) as u64;
+            midi.handle_message_at(&[0xF8], timestamp);
+        }
+
+        let mut quarter_edges = Vec::new();
+        let mut clock_edges = 0;
+        let (mut last_quarter, mut last_clock) = (0.0, 0.0);
+        for now in 0..96_000u64 {
+            midi.process_clock(now);
+            let quarter = midi.quarter_pulse.get();
+            let clock = midi.clock_pulse.get();
+            if quarter > 2.5 && last_quarter <= 2.5 {
+                quarter_edges.push(now);
+            }
+            if clock > 2.5 && last_clock <= 2.5 {
+                clock_edges += 1;
+            }
+            last_quarter = quarter;
+            last_clock = clock;
+        }
+
+        assert!(midi.clock_is_running());
+        assert_eq!(midi.clock_count(), 96);
+        assert_eq!(clock_edges, 96);
+
+        // A quarter-note pulse on clock 0, 24, 48 and 72, at the exact clock sample
+        assert_eq!(quarter_edges, vec![500, 24_500, 48_500, 72_500]);
+
+        let bpm = midi.clock_tempo().unwrap();
+        assert!((bpm - 120.0).abs() < 1.0, "bpm {bpm}");
+
+        let mut transport = Transport::new(90.0, 48000.0);
+        midi.sync_transport(&mut transport);
+        assert!(transport.is_playing());
+        assert!((transport.bpm() - bpm).abs() < 1e-9);
+
+        // Stop silences the pulses
+        midi.handle_message(&[0xFC]);
+        midi.handle_message_at(&[0xF8], 96_500);
+        midi.process_clock(96_500);
+        assert!(!midi.clock_is_running());
+        assert_eq!(midi.clock_pulse.get(), 0.0);
+        midi.sync_transport(&mut transport);
+        assert!(!transport.is_playing());
+    }
 }
@@ -31,16 +31,16 @@ pub(crate) type StdMap<K, V> = alloc::collections::BTreeMap<K, V>;
 pub mod analog;
 pub mod combinator;
 pub mod graph;
+pub mod introspection;
 pub mod modules;
 pub mod polyphony;
 pub mod port;
 pub mod rng;
 pub mod simd;
+pub mod transport;
 
 // Alloc-tier modules (work with no_std + alloc)
 #[cfg(feature = "alloc")]
-pub mod introspection;
-#[cfg(feature = "alloc")]
 mod introspection_impls; // ModuleIntrospection implementations for all modules
 #[cfg(feature = "alloc")]
 pub mod io;
@@ -73,27 +73,29 @@ pub mod prelude {
 
     // Layer 2: Port System
     pub use crate::port::{
-        ports_compatible, BlockPortValues, Compatibility, GraphModule, ModulatedParam, ParamDef,
-        ParamId, ParamRange, PortDef, PortId, PortInfo, PortSpec, PortValues, SignalColors,
-        SignalKind,
+        ports_compatible, BlockPortValues, Compatibility, FrequencyResponse, GraphModule,
+        MergeMode, ModulatedParam, ParamDef, ParamId, ParamRange, PortDef, PortId, PortInfo,
+        PortSpec, PortStore, PortValues, RuntimeReader, RuntimeWriter, SignalColors, SignalKind,
+        SmoothedParam, StackPortValues,
     };
 
     // Layer 3: Patch Graph
     pub use crate::graph::{
-        Cable, CableId, CompatibilityResult, NodeHandle, NodeId, Patch, PatchError, PortRef,
-        ValidationMode,
+        Cable, CableId, CompatibilityResult, FxChain, NanGuard, NodeHandle, NodeId, Patch,
+        PatchError, PortRef, ReplaceReport, SidechainBus, SubPatch, ValidationMode,
     };
 
     // Core DSP Modules
     pub use crate::modules::{
-        Adsr, Attenuverter, Clock, Lfo, Mixer, Multiple, NoiseGenerator, Offset, Quantizer,
-        SampleAndHold, Scale, SlewLimiter, StepSequencer, StereoOutput, Svf, UnitDelay, Vca, Vco,
+        Adsr, Attenuverter, Clock, DcBlocker, Lfo, Mixer, Multiple, NoiseGenerator, Offset,
+        Quantizer, RepeatDivision, SampleAndHold, Scale, SlewLimiter, StepSequencer, StereoOutput,
+        Svf, UnitDelay, Vca, Vco,
     };
 
     // Phase 2 Modules
     pub use crate::modules::{
         BernoulliGate, Comparator, Crossfader, LogicAnd, LogicNot, LogicOr, LogicXor, Max, Min,
-        PrecisionAdder, Rectifier, RingModulator, VcSwitch,
+        PanLaw, Panner, PrecisionAdder, Rectifier, RingModulator, VcSwitch,
     };
 
     // Phase 3 Modules
@@ -113,8 +115,8 @@ pub mod prelude {
 
     // Phase 4: Polyphony Support
     pub use crate::polyphony::{
-        AllocationMode, PolyPatch, UnisonConfig, Voice, VoiceAllocator, VoiceInput, VoiceMixer,
-        VoiceState,
+        AllocationMode, MidiVoiceRouter, PolyPatch, UnisonConfig, Voice, VoiceAllocator,
+        VoiceHumanize, VoiceInput, VoiceMixer, VoicePanMode, VoiceState,
     };
 
     // Phase 4: SIMD and Block Processing
@@ -123,6 +125,9 @@ pub mod prelude {
         DEFAULT_BLOCK_SIZE, SIMD_BLOCK_SIZE,
     };
 
+    // Musical transport
+    pub use crate::transport::Transport;
+
     // RNG (no_std compatible)
     pub use crate::rng::{Rng, SeedableRng};
 
@@ -132,10 +137,9 @@ pub mod prelude {
 
     // External I/O (works with alloc via core::sync::atomic + alloc::sync::Arc)
     #[cfg(feature = "alloc")]
-    pub use crate::io::{AtomicF64, ExternalInput, ExternalOutput, MidiState};
+    pub use crate::io::{AtomicF64, ExternalInput, ExternalOutput, MidiState, MidiToCv};
 
     // Introspection API (GUI parameter discovery)
-    #[cfg(feature = "alloc")]
     pub use crate::introspection::{
         ControlType, ModuleIntrospection, ParamCurve, ParamInfo, ValueFormat,
     };
@@ -150,14 +154,14 @@ pub mod prelude {
     // Serialization (works with alloc via serde_json alloc feature)
     #[cfg(feature = "alloc")]
     pub use crate::serialize::{
-        CableDef, CatalogResponse, ModuleCatalogEntry, ModuleDef, ModuleMetadata, ModuleRegistry,
-        PatchDef, PortSummary, ValidationError, ValidationResult,
+        CableDef, CatalogResponse, FxChainDef, FxSlotDef, ModuleCatalogEntry, ModuleDef,
+        ModuleMetadata, ModuleRegistry, PatchDef, PortSummary, ValidationError, ValidationResult,
     };
 
     // Preset Library (works with alloc - just data structures)
     #[cfg(feature = "alloc")]
     pub use crate::presets::{
-        ClassicPresets, PresetCategory, PresetInfo, PresetLibrary, SoundDesignPresets,
+        ClassicPresets, PresetCategory, PresetId, PresetInfo, PresetLibrary, SoundDesignPresets,
         TutorialPresets,
     };
 
@@ -168,9 +172,10 @@ pub mod prelude {
     // Extended I/O (requires std for network, plugins, etc.)
     #[cfg(feature = "std")]
     pub use crate::extended_io::{
-        AudioBusConfig, OscBinding, OscInput, OscMessage, OscPattern, OscReceiver, OscValue,
-        PluginCategory, PluginInfo, PluginParameter, PluginWrapper, WebAudioConfig,
-        WebAudioProcessor, WebAudioWorklet,
+        render_to_wav, render_to_wav_with, AudioBusConfig, OscBinding, OscBundle, OscInput,
+        OscMessage, OscPattern, OscReceiver, OscSender, OscSource, OscValue, PluginCategory,
+        PluginInfo, PluginParameter, PluginWrapper, RenderError, RenderOptions, WavBitDepth,
+        WebAudioConfig, WebAudioProcessor, WebAudioWorklet,
     };
 
     // Module Development Kit (requires std)
@@ -183,8 +188,9 @@ pub mod prelude {
     // Visual Tools (requires std)
     #[cfg(feature = "std")]
     pub use crate::visual::{
-        AutomationData, AutomationPoint, AutomationRecorder, AutomationTrack, DotExporter,
-        DotStyle, LevelMeter, Scope, SpectrumAnalyzer, TriggerMode,
+        AutomationData, AutomationInterpolation, AutomationPoint, AutomationRecorder,
+        AutomationTrack, CvMonitor, CvSpan, DotExporter, DotStyle, LevelMeter, Scope,
+        SpectrumAnalyzer, SweepMode, TriggerMode,
     };
 
     // WASM bindings (requires wasm feature)
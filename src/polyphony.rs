@@ -8,12 +8,17 @@
 //! - `VoiceAllocator` - Manages which voices get assigned to incoming notes
 //! - `Voice` - A single voice with its own state and modules
 //! - `PolyPatch` - A polyphonic patch containing multiple voice instances
+//! - `MidiVoiceRouter` - Routes live MIDI notes onto a `PolyPatch`'s voices
 //! - `UnisonVoice` - Stacked voices with detuning for thick unison sounds
+//! - `VoiceMixer` - Sums voices, optionally spreading them across the stereo field
 
 use crate::graph::{Patch, PatchError};
-use crate::port::{GraphModule, PortDef, PortSpec, PortValues, SignalKind};
+use crate::port::{
+    GraphModule, PortDef, PortSpec, PortValues, RuntimeReader, RuntimeWriter, SignalKind,
+};
 use alloc::collections::VecDeque;
 use alloc::format;
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 use libm::Libm;
@@ -68,6 +73,13 @@ pub struct Voice {
     pub age: u64,
     /// Current envelope level (for quiet-steal algorithm)
     pub envelope_level: f64,
+    /// Whether `voct` holds an earlier note's pitch to glide from
+    pitched: bool,
+    /// Current glide: start and target V/Oct, length and progress in samples
+    glide_from: f64,
+    glide_to: f64,
+    glide_len: u64,
+    glide_pos: u64,
 }
 
 impl Voice {
@@ -83,6 +95,11 @@ impl Voice {
             trigger: 0.0,
             age: 0,
             envelope_level: 0.0,
+            pitched: false,
+            glide_from: 0.0,
+            glide_to: 0.0,
+            glide_len: 0,
+            glide_pos: 0,
         }
     }
 
@@ -95,6 +112,31 @@ impl Voice {
         self.gate = 1.0;
         self.trigger = 1.0; // Will be cleared after one sample
         self.age = 0;
+        self.pitched = true;
+        self.glide_len = 0;
+        self.glide_pos = 0;
+    }
+
+    /// Trigger the voice with a new note, gliding from its previous pitch
+    ///
+    /// The V/Oct moves linearly from wherever the voice was (mid-glide
+    /// included) to the new note over `glide_samples` ticks. A voice that has
+    /// not played since it was created or panicked starts on the note.
+    pub fn note_on_glide(&mut self, note: u8, velocity: f64, glide_samples: u64) {
+        let from = self.voct;
+        let pitched = self.pitched;
+        self.note_on(note, velocity);
+        if pitched && glide_samples > 0 && from != self.voct {
+            self.glide_from = from;
+            self.glide_to = self.voct;
+            self.glide_len = glide_samples;
+            self.voct = from;
+        }
+    }
+
+    /// Whether the V/Oct is still gliding toward the note
+    pub fn is_gliding(&self) -> bool {
+        self.glide_pos < self.glide_len
     }
 
     /// Release the voice
@@ -120,6 +162,16 @@ impl Voice {
         self.age = self.age.saturating_add(1);
         self.trigger = 0.0; // Clear trigger after one sample
 
+        if self.is_gliding() {
+            self.glide_pos += 1;
+            self.voct = if self.glide_pos == self.glide_len {
+                self.glide_to
+            } else {
+                let t = self.glide_pos as f64 / self.glide_len as f64;
+                self.glide_from + (self.glide_to - self.glide_from) * t
+            };
+        }
+
         // Auto-free releasing voices when envelope is done
         if self.state == VoiceState::Releasing && self.envelope_level < 0.0001 {
             self.free();
@@ -161,6 +213,8 @@ pub struct VoiceAllocator {
     voices: Vec<Voice>,
     /// LRU queue for round-robin voice allocation
     lru_queue: VecDeque<usize>,
+    /// Per-voice portamento time in samples (0 = off)
+    glide_samples: u64,
 }
 
 impl VoiceAllocator {
@@ -181,9 +235,23 @@ impl VoiceAllocator {
             mode: AllocationMode::RoundRobin,
             voices,
             lru_queue,
+            glide_samples: 0,
         }
     }
 
+    /// Set the per-voice glide time in samples (0 disables glide)
+    ///
+    /// A voice reassigned to a new note glides from its own previous pitch,
+    /// so in polyphonic playing each voice slides independently.
+    pub fn set_glide_samples(&mut self, samples: u64) {
+        self.glide_samples = samples;
+    }
+
+    /// Get the per-voice glide time in samples
+    pub fn glide_samples(&self) -> u64 {
+        self.glide_samples
+    }
+
     /// Set the allocation mode
     pub fn set_mode(&mut self, mode: AllocationMode) {
         self.mode = mode;
@@ -231,23 +299,24 @@ impl VoiceAllocator {
     /// Returns the voice index if successful
     pub fn note_on(&mut self, note: u8, velocity: f64) -> Option<usize> {
         // First check if this note is already playing (retrigger)
+        let glide = self.glide_samples;
         for voice in &mut self.voices {
             if voice.is_playing_note(note) {
-                voice.note_on(note, velocity);
+                voice.note_on_glide(note, velocity, glide);
                 return Some(voice.index);
             }
         }
 
         // Try to find a free voice
         if let Some(voice_idx) = self.find_free_voice() {
-            self.voices[voice_idx].note_on(note, velocity);
+            self.voices[voice_idx].note_on_glide(note, velocity, glide);
             self.update_lru(voice_idx);
             return Some(voice_idx);
         }
 
         // No free voices - attempt voice stealing based on mode
         if let Some(voice_idx) = self.find_steal_voice(note) {
-            self.voices[voice_idx].note_on(note, velocity);
+            self.voices[voice_idx].note_on_glide(note, velocity, glide);
             self.update_lru(voice_idx);
             return Some(voice_idx);
         }
@@ -275,9 +344,15 @@ impl VoiceAllocator {
     }
 
     /// Kill all voices immediately (panic)
+    ///
+    /// Voices also forget their last pitch, so the next notes start without
+    /// a glide.
     pub fn panic(&mut self) {
         for voice in &mut self.voices {
             voice.free();
+            voice.pitched = false;
+            voice.glide_len = 0;
+            voice.glide_pos = 0;
         }
     }
 
@@ -304,11 +379,20 @@ impl VoiceAllocator {
     }
 
     fn find_steal_voice(&self, note: u8) -> Option<usize> {
+        if self.mode == AllocationMode::NoSteal {
+            return None;
+        }
+
+        // A voice whose key is already up is only playing its release tail
+        if let Some(index) = self.oldest_voice(|v| v.state == VoiceState::Releasing) {
+            return Some(index);
+        }
+
         match self.mode {
             AllocationMode::NoSteal => None,
             AllocationMode::RoundRobin | AllocationMode::OldestSteal => {
                 // Find oldest voice
-                self.voices.iter().max_by_key(|v| v.age).map(|v| v.index)
+                self.oldest_voice(|_| true)
             }
             AllocationMode::QuietestSteal => {
                 // Find voice with lowest envelope level
@@ -340,6 +424,18 @@ impl VoiceAllocator {
         }
     }
 
+    /// Oldest voice passing `filter`, ties going to the least recently assigned
+    fn oldest_voice(&self, filter: impl Fn(&Voice) -> bool) -> Option<usize> {
+        let mut oldest: Option<&Voice> = None;
+        for &index in &self.lru_queue {
+            let voice = &self.voices[index];
+            if filter(voice) && oldest.map_or(true, |o| voice.age > o.age) {
+                oldest = Some(voice);
+            }
+        }
+        oldest.map(|v| v.index)
+    }
+
     fn update_lru(&mut self, used_idx: usize) {
         // Move used voice to back of LRU queue
         if let Some(pos) = self.lru_queue.iter().position(|&x| x == used_idx) {
@@ -458,11 +554,30 @@ impl GraphModule for PolyVoice {
     fn type_id(&self) -> &'static str {
         "poly_voice"
     }
+
+    fn supports_runtime_state(&self) -> bool {
+        true
+    }
+}
+
+/// Random per-note offsets applied to a voice by `PolyPatch` humanize
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct VoiceHumanize {
+    /// Detune in V/Oct
+    pub detune: f64,
+    /// Samples the gate is held back after note on
+    pub delay: u64,
 }
 
 /// Polyphonic patch container
 ///
 /// Manages multiple voice instances and handles voice allocation.
+///
+/// With a non-zero `humanize` amount, every note on draws a fresh random
+/// detune (up to ±10 cents) and gate delay (up to 5ms) for its voice from a
+/// seeded RNG, so repeated notes don't start phase-identical and chords get
+/// a natural spread. The same seed always produces the same offsets; zero
+/// humanize leaves every voice exactly on pitch and on time.
 pub struct PolyPatch {
     /// Voice allocator
     allocator: VoiceAllocator,
@@ -472,6 +587,14 @@ pub struct PolyPatch {
     voice_inputs: Vec<VoiceInput>,
     /// Unison configuration
     unison: UnisonConfig,
+    /// Humanize amount (0.0 = off, 1.0 = full range)
+    humanize: f64,
+    humanize_seed: u64,
+    humanize_rng: crate::rng::Rng,
+    /// Per-voice offsets drawn at the voice's last note on
+    voice_humanize: Vec<VoiceHumanize>,
+    /// Per-voice glide time in seconds
+    glide: f64,
     /// Sample rate
     sample_rate: f64,
     /// Output buffers (left, right)
@@ -480,6 +603,11 @@ pub struct PolyPatch {
 }
 
 impl PolyPatch {
+    /// Detune at full humanize, in cents either side
+    const HUMANIZE_CENTS: f64 = 10.0;
+    /// Gate delay at full humanize, in ms
+    const HUMANIZE_DELAY_MS: f64 = 5.0;
+
     /// Create a new polyphonic patch
     pub fn new(num_voices: usize, sample_rate: f64) -> Self {
         let allocator = VoiceAllocator::new(num_voices);
@@ -491,6 +619,11 @@ impl PolyPatch {
             voice_patches,
             voice_inputs,
             unison: UnisonConfig::default(),
+            humanize: 0.0,
+            humanize_seed: 0,
+            humanize_rng: crate::rng::Rng::from_seed(0),
+            voice_humanize: vec![VoiceHumanize::default(); num_voices],
+            glide: 0.0,
             sample_rate,
             output_left: 0.0,
             output_right: 0.0,
@@ -505,6 +638,7 @@ impl PolyPatch {
     /// Set the sample rate for all voice patches
     pub fn set_sample_rate(&mut self, sample_rate: f64) {
         self.sample_rate = sample_rate;
+        self.set_glide(self.glide);
         // Note: Individual patches would need to be recompiled after this
         // This is typically done by recreating the patches
     }
@@ -539,6 +673,54 @@ impl PolyPatch {
         &self.unison
     }
 
+    /// Set the humanize amount (0.0 to 1.0)
+    pub fn set_humanize(&mut self, amount: f64) {
+        self.humanize = amount.clamp(0.0, 1.0);
+    }
+
+    /// Get the humanize amount
+    pub fn humanize(&self) -> f64 {
+        self.humanize
+    }
+
+    /// Reseed the humanize RNG (also applied on reset)
+    pub fn set_humanize_seed(&mut self, seed: u64) {
+        self.humanize_seed = seed;
+        self.humanize_rng = crate::rng::Rng::from_seed(seed);
+    }
+
+    /// Seed every voice patch's modules from one master seed
+    ///
+    /// Each voice gets a different seed (see `Patch::set_seed`), so voices
+    /// built from the same modules do not play identical noise.
+    pub fn set_seed(&mut self, seed: u64) {
+        let mut rng = crate::rng::Rng::from_seed(seed);
+        for patch in &mut self.voice_patches {
+            patch.set_seed(rng.next_u64());
+        }
+    }
+
+    /// Set the per-voice glide time in seconds (0 disables glide)
+    ///
+    /// Each voice glides from its own previous pitch when it is given a new
+    /// note, so overlapping notes slide independently; a voice that has not
+    /// played yet starts on its note.
+    pub fn set_glide(&mut self, seconds: f64) {
+        self.glide = seconds.max(0.0);
+        let samples = Libm::<f64>::round(self.glide * self.sample_rate) as u64;
+        self.allocator.set_glide_samples(samples);
+    }
+
+    /// Get the per-voice glide time in seconds
+    pub fn glide(&self) -> f64 {
+        self.glide
+    }
+
+    /// Get the humanize offsets drawn for a voice's current note
+    pub fn voice_humanize(&self, index: usize) -> Option<VoiceHumanize> {
+        self.voice_humanize.get(index).copied()
+    }
+
     /// Get a voice patch for configuration
     pub fn voice_patch(&self, index: usize) -> Option<&Patch> {
         self.voice_patches.get(index)
@@ -562,7 +744,21 @@ impl PolyPatch {
     /// Handle MIDI note on
     pub fn note_on(&mut self, note: u8, velocity: u8) {
         let velocity_f = velocity as f64 / 127.0;
-        self.allocator.note_on(note, velocity_f);
+        if let Some(index) = self.allocator.note_on(note, velocity_f) {
+            self.voice_humanize[index] = self.draw_humanize();
+        }
+    }
+
+    fn draw_humanize(&mut self) -> VoiceHumanize {
+        if self.humanize <= 0.0 {
+            return VoiceHumanize::default();
+        }
+        let cents = self.humanize_rng.next_f64_bipolar() * Self::HUMANIZE_CENTS * self.humanize;
+        let delay_ms = self.humanize_rng.next_f64() * Self::HUMANIZE_DELAY_MS * self.humanize;
+        VoiceHumanize {
+            detune: cents / 1200.0,
+            delay: (delay_ms * self.sample_rate / 1000.0) as u64,
+        }
     }
 
     /// Handle MIDI note off
@@ -599,6 +795,16 @@ impl PolyPatch {
         for (i, voice) in self.allocator.voices().iter().enumerate() {
             if let Some(input) = self.voice_inputs.get_mut(i) {
                 input.set_from_voice(voice);
+                // Hold a humanized note's gate back, then trigger as it opens
+                let delay = self.voice_humanize[i].delay;
+                if delay > 0 && voice.state == VoiceState::Active {
+                    if voice.age <= delay {
+                        input.set_gate(0.0);
+                        input.set_trigger(0.0);
+                    } else if voice.age == delay + 1 {
+                        input.set_trigger(1.0);
+                    }
+                }
             }
         }
 
@@ -612,7 +818,7 @@ impl PolyPatch {
             let unison_gain = self.unison.voice_gain();
             for u in 0..self.unison.voices {
                 // Calculate detune offset in V/Oct
-                let detune = self.unison.detune_offset(u);
+                let detune = self.unison.detune_offset(u) + self.voice_humanize[i].detune;
                 let pan = self.unison.pan_position(u);
 
                 // Apply detune to voice input V/Oct
@@ -652,11 +858,245 @@ impl PolyPatch {
             patch.reset();
         }
         self.allocator.panic();
+        self.voice_humanize.fill(VoiceHumanize::default());
+        self.set_humanize_seed(self.humanize_seed);
         self.output_left = 0.0;
         self.output_right = 0.0;
     }
 }
 
+/// Routes live MIDI note messages onto the voices of a `PolyPatch`
+///
+/// The router tracks which keys are held and feeds note-ons and note-offs to
+/// the patch's `VoiceAllocator`, so voice assignment and stealing follow the
+/// allocator's `AllocationMode`. Voices still ringing out a release are
+/// stolen before ones whose key is held. A stolen voice restarts with a
+/// one-sample trigger, so patch it into the envelope's `retrig` input to
+/// hear each stolen note re-attack. The sustain pedal (CC 64) holds released
+/// notes until it is lifted; All Notes Off (CC 123) releases every voice and
+/// All Sound Off (CC 120) silences them.
+///
+/// ```rust,ignore
+/// let mut router = MidiVoiceRouter::new();
+/// router.handle_message(&mut poly, &[0x90, 60, 100]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MidiVoiceRouter {
+    /// Only messages on this channel are routed (`None` = omni)
+    channel: Option<u8>,
+    /// Keys currently held down, oldest first
+    held: Vec<u8>,
+    /// Released keys kept sounding by the sustain pedal
+    sustained: Vec<u8>,
+    /// Held or sustained notes left without a voice, in the order lost
+    stolen: Vec<u8>,
+    /// Sustain pedal state
+    sustain: bool,
+}
+
+/// The voice-owning side of a `MidiVoiceRouter`
+trait NoteSink {
+    fn play(&mut self, note: u8, velocity: u8);
+    fn release(&mut self, note: u8);
+    fn release_all(&mut self);
+    fn silence(&mut self);
+    fn voices(&self) -> &[Voice];
+}
+
+impl NoteSink for VoiceAllocator {
+    fn play(&mut self, note: u8, velocity: u8) {
+        self.note_on(note, velocity as f64 / 127.0);
+    }
+
+    fn release(&mut self, note: u8) {
+        self.note_off(note);
+    }
+
+    fn release_all(&mut self) {
+        self.all_notes_off();
+    }
+
+    fn silence(&mut self) {
+        self.panic();
+    }
+
+    fn voices(&self) -> &[Voice] {
+        VoiceAllocator::voices(self)
+    }
+}
+
+impl NoteSink for PolyPatch {
+    fn play(&mut self, note: u8, velocity: u8) {
+        self.note_on(note, velocity);
+    }
+
+    fn release(&mut self, note: u8) {
+        self.note_off(note);
+    }
+
+    fn release_all(&mut self) {
+        self.all_notes_off();
+    }
+
+    fn silence(&mut self) {
+        self.panic();
+    }
+
+    fn voices(&self) -> &[Voice] {
+        self.allocator.voices()
+    }
+}
+
+impl MidiVoiceRouter {
+    /// Create a router listening on all channels
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only route messages on `channel` (0-15)
+    pub fn with_channel(mut self, channel: u8) -> Self {
+        self.set_channel(Some(channel));
+        self
+    }
+
+    /// Set the channel to route (`None` listens on all channels)
+    pub fn set_channel(&mut self, channel: Option<u8>) {
+        self.channel = channel.map(|c| c & 0x0F);
+    }
+
+    /// Get the routed channel (`None` = omni)
+    pub fn channel(&self) -> Option<u8> {
+        self.channel
+    }
+
+    /// Route a MIDI message (3-byte format) into a polyphonic patch
+    pub fn handle_message(&mut self, poly: &mut PolyPatch, msg: &[u8]) {
+        self.route(poly, msg);
+    }
+
+    /// Route a MIDI message (3-byte format) into a bare voice allocator
+    ///
+    /// Use `write_voice_inputs` to copy the voices into `VoiceInput`s when
+    /// driving voice patches without a `PolyPatch`.
+    pub fn handle_allocator_message(&mut self, allocator: &mut VoiceAllocator, msg: &[u8]) {
+        self.route(allocator, msg);
+    }
+
+    /// Copy each voice's pitch, gate, trigger and velocity into its input
+    pub fn write_voice_inputs(allocator: &VoiceAllocator, inputs: &mut [VoiceInput]) {
+        for (input, voice) in inputs.iter_mut().zip(allocator.voices()) {
+            input.set_from_voice(voice);
+        }
+    }
+
+    fn route(&mut self, sink: &mut impl NoteSink, msg: &[u8]) {
+        if msg.len() != 3 {
+            return;
+        }
+        if self.channel.is_some_and(|c| c != msg[0] & 0x0F) {
+            return;
+        }
+
+        match msg[0] & 0xF0 {
+            // Note On (with velocity > 0)
+            0x90 if msg[2] > 0 => self.note_on(sink, msg[1] & 0x7F, msg[2]),
+            // Note Off (or Note On with velocity 0)
+            0x80 | 0x90 => self.note_off(sink, msg[1] & 0x7F),
+            // Sustain pedal
+            0xB0 if msg[1] == 64 => self.set_sustain(sink, msg[2] >= 64),
+            // All Sound Off
+            0xB0 if msg[1] == 120 => {
+                sink.silence();
+                self.clear();
+            }
+            // All Notes Off
+            0xB0 if msg[1] == 123 => {
+                sink.release_all();
+                self.clear();
+            }
+            _ => {}
+        }
+    }
+
+    fn note_on(&mut self, sink: &mut impl NoteSink, note: u8, velocity: u8) {
+        self.sustained.retain(|&n| n != note);
+        self.stolen.retain(|&n| n != note);
+        if !self.held.contains(&note) {
+            self.held.push(note);
+        }
+
+        sink.play(note, velocity);
+
+        // A sounding note that no voice plays any more was just stolen; with
+        // stealing off it is the new note that goes without
+        let voices = sink.voices();
+        let sounding = self.held.iter().chain(&self.sustained);
+        if let Some(&victim) = sounding
+            .filter(|&n| !self.stolen.contains(n))
+            .find(|&&n| !voices.iter().any(|v| v.is_playing_note(n)))
+        {
+            self.stolen.push(victim);
+        }
+    }
+
+    fn note_off(&mut self, sink: &mut impl NoteSink, note: u8) {
+        let Some(pos) = self.held.iter().position(|&n| n == note) else {
+            return;
+        };
+        self.held.remove(pos);
+
+        if self.sustain {
+            self.sustained.push(note);
+        } else {
+            self.stolen.retain(|&n| n != note);
+            sink.release(note);
+        }
+    }
+
+    fn set_sustain(&mut self, sink: &mut impl NoteSink, on: bool) {
+        self.sustain = on;
+        if !on {
+            for note in self.sustained.drain(..) {
+                sink.release(note);
+            }
+            let held = &self.held;
+            self.stolen.retain(|n| held.contains(n));
+        }
+    }
+
+    fn clear(&mut self) {
+        self.held.clear();
+        self.sustained.clear();
+        self.stolen.clear();
+    }
+
+    /// Keys currently held down, oldest first
+    pub fn held_notes(&self) -> &[u8] {
+        &self.held
+    }
+
+    /// Held or sustained notes left without a voice, in the order lost
+    ///
+    /// A note loses its voice when a newer note steals it, or never gets one
+    /// when no voice can be stolen.
+    pub fn stolen_notes(&self) -> &[u8] {
+        &self.stolen
+    }
+
+    /// Whether the sustain pedal is down
+    pub fn sustain(&self) -> bool {
+        self.sustain
+    }
+
+    /// Forget all held notes and lift the sustain pedal
+    ///
+    /// The voices are left alone; reset or panic the patch separately.
+    pub fn reset(&mut self) {
+        self.clear();
+        self.sustain = false;
+    }
+}
+
 /// Voice input module for injecting per-voice CV into a patch
 ///
 /// This module provides the per-voice signals (V/Oct, gate, trigger, velocity)
@@ -748,15 +1188,64 @@ impl GraphModule for VoiceInput {
     fn type_id(&self) -> &'static str {
         "voice_input"
     }
+
+    fn supports_runtime_state(&self) -> bool {
+        true
+    }
+
+    fn serialize_runtime(&self, state: &mut RuntimeWriter) {
+        state.put_f64(self.voct);
+        state.put_f64(self.gate);
+        state.put_f64(self.trigger);
+        state.put_f64(self.velocity);
+    }
+
+    fn deserialize_runtime(&mut self, state: &mut RuntimeReader<'_>) -> Result<(), String> {
+        self.voct = state.f64()?;
+        self.gate = state.f64()?;
+        self.trigger = state.f64()?;
+        self.velocity = state.f64()?;
+        Ok(())
+    }
+}
+
+/// How `VoiceMixer` orders voices across the stereo field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VoicePanMode {
+    /// Lowest active voice index left, highest right
+    #[default]
+    ByIndex,
+    /// Lowest active pitch left, highest right
+    ByPitch,
 }
 
 /// Voice mixer for summing polyphonic voices
+///
+/// With a non-zero spread the active voices are fanned out evenly across
+/// the stereo field, ordered by voice index or by pitch, and panned with a
+/// balance law (the centre is unity gain). A single active voice always
+/// sits in the centre. The mixer learns which voices are active and their
+/// pitches from `set_from_allocator` or `set_voice`; until then every voice
+/// counts as active at 0V.
+///
+/// Optionally each panned voice is also exposed on its own output pair,
+/// `out{i}_l`/`out{i}_r`, for external per-voice effects.
 pub struct VoiceMixer {
     num_voices: usize,
+    spread: f64,
+    pan_mode: VoicePanMode,
+    voice_outputs: bool,
+    /// Per-voice (V/Oct, active)
+    voices: Vec<(f64, bool)>,
+    /// Per-voice pan position (-1 = left, 1 = right)
+    pans: Vec<f64>,
     spec: PortSpec,
 }
 
 impl VoiceMixer {
+    /// Id of the first per-voice output (left of voice 0)
+    pub const VOICE_OUTPUT_BASE: u32 = 102;
+
     /// Create a voice mixer for the given number of voices
     pub fn new(num_voices: usize) -> Self {
         let mut inputs = Vec::with_capacity(num_voices * 2);
@@ -773,15 +1262,115 @@ impl VoiceMixer {
             ));
         }
 
-        Self {
+        let mut mixer = Self {
             num_voices,
+            spread: 0.0,
+            pan_mode: VoicePanMode::ByIndex,
+            voice_outputs: false,
+            voices: vec![(0.0, true); num_voices],
+            pans: vec![0.0; num_voices],
             spec: PortSpec {
                 inputs,
-                outputs: vec![
-                    PortDef::new(100, "left", SignalKind::Audio),
-                    PortDef::new(101, "right", SignalKind::Audio),
-                ],
+                outputs: Vec::new(),
             },
+        };
+        mixer.build_outputs();
+        mixer
+    }
+
+    /// Builder: set the stereo spread (0 = all centred, 1 = full width)
+    pub fn with_spread(mut self, spread: f64) -> Self {
+        self.set_spread(spread);
+        self
+    }
+
+    pub fn set_spread(&mut self, spread: f64) {
+        self.spread = spread.clamp(0.0, 1.0);
+        self.update_pans();
+    }
+
+    pub fn spread(&self) -> f64 {
+        self.spread
+    }
+
+    /// Builder: set how voices are ordered across the stereo field
+    pub fn with_pan_mode(mut self, mode: VoicePanMode) -> Self {
+        self.set_pan_mode(mode);
+        self
+    }
+
+    pub fn set_pan_mode(&mut self, mode: VoicePanMode) {
+        self.pan_mode = mode;
+        self.update_pans();
+    }
+
+    pub fn pan_mode(&self) -> VoicePanMode {
+        self.pan_mode
+    }
+
+    /// Builder: expose each voice on its own output pair
+    pub fn with_voice_outputs(mut self, enabled: bool) -> Self {
+        self.voice_outputs = enabled;
+        self.build_outputs();
+        self
+    }
+
+    pub fn has_voice_outputs(&self) -> bool {
+        self.voice_outputs
+    }
+
+    /// Update one voice's pitch and activity
+    pub fn set_voice(&mut self, index: usize, voct: f64, active: bool) {
+        if let Some(voice) = self.voices.get_mut(index) {
+            *voice = (voct, active);
+            self.update_pans();
+        }
+    }
+
+    /// Update every voice's pitch and activity from an allocator
+    pub fn set_from_allocator(&mut self, allocator: &VoiceAllocator) {
+        for (slot, voice) in self.voices.iter_mut().zip(allocator.voices()) {
+            *slot = (voice.voct, voice.state != VoiceState::Free);
+        }
+        self.update_pans();
+    }
+
+    /// Current pan position of a voice (-1 = left, 0 = centre, 1 = right)
+    pub fn pan(&self, index: usize) -> Option<f64> {
+        self.pans.get(index).copied()
+    }
+
+    fn build_outputs(&mut self) {
+        let mut outputs = vec![
+            PortDef::new(100, "left", SignalKind::Audio),
+            PortDef::new(101, "right", SignalKind::Audio),
+        ];
+        if self.voice_outputs {
+            for i in 0..self.num_voices {
+                let base = Self::VOICE_OUTPUT_BASE + i as u32 * 2;
+                outputs.push(PortDef::new(base, format!("out{}_l", i), SignalKind::Audio));
+                outputs.push(PortDef::new(
+                    base + 1,
+                    format!("out{}_r", i),
+                    SignalKind::Audio,
+                ));
+            }
+        }
+        self.spec.outputs = outputs;
+    }
+
+    fn update_pans(&mut self) {
+        let mut active: Vec<usize> = (0..self.num_voices).filter(|&i| self.voices[i].1).collect();
+        if self.pan_mode == VoicePanMode::ByPitch {
+            active.sort_by(|&a, &b| self.voices[a].0.total_cmp(&self.voices[b].0));
+        }
+
+        self.pans.fill(0.0);
+        if active.len() > 1 {
+            let last = (active.len() - 1) as f64;
+            for (rank, &voice) in active.iter().enumerate() {
+                self.pans[voice] = (rank as f64 / last * 2.0 - 1.0) * self.spread;
+            }
         }
     }
 }
@@ -796,8 +1385,17 @@ impl GraphModule for VoiceMixer {
         let mut right = 0.0;
 
         for i in 0..self.num_voices {
-            left += inputs.get_or(i as u32 * 2, 0.0);
-            right += inputs.get_or(i as u32 * 2 + 1, 0.0);
+            let pan = self.pans[i];
+            let l = inputs.get_or(i as u32 * 2, 0.0) * (1.0 - pan).min(1.0);
+            let r = inputs.get_or(i as u32 * 2 + 1, 0.0) * (1.0 + pan).min(1.0);
+            left += l;
+            right += r;
+
+            if self.voice_outputs {
+                let base = Self::VOICE_OUTPUT_BASE + i as u32 * 2;
+                outputs.set(base, l);
+                outputs.set(base + 1, r);
+            }
         }
 
         outputs.set(100, left);
@@ -811,6 +1409,30 @@ impl GraphModule for VoiceMixer {
     fn type_id(&self) -> &'static str {
         "voice_mixer"
     }
+
+    fn cost_hint(&self) -> u32 {
+        (self.num_voices as u32).div_ceil(4).max(1)
+    }
+
+    fn supports_runtime_state(&self) -> bool {
+        true
+    }
+
+    fn serialize_runtime(&self, state: &mut RuntimeWriter) {
+        // Pans are derived from the learned voice pitches
+        for &(pitch, active) in &self.voices {
+            state.put_f64(pitch);
+            state.put_bool(active);
+        }
+    }
+
+    fn deserialize_runtime(&mut self, state: &mut RuntimeReader<'_>) -> Result<(), String> {
+        for voice in &mut self.voices {
+            *voice = (state.f64()?, state.bool()?);
+        }
+        self.update_pans();
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -869,6 +1491,44 @@ mod tests {
         assert_eq!(stolen, Some(0));
     }
 
+    #[test]
+    fn test_voice_glides_from_previous_pitch_when_reassigned() {
+        let mut allocator = VoiceAllocator::new(2);
+        allocator.set_glide_samples(100);
+
+        // Fresh voices start on their notes
+        allocator.note_on(60, 0.8);
+        allocator.tick();
+        allocator.note_on(48, 0.8);
+        assert_eq!(allocator.voice(0).unwrap().voct, 0.0);
+        assert_eq!(allocator.voice(1).unwrap().voct, -1.0);
+        assert!(!allocator.voice(0).unwrap().is_gliding());
+        allocator.tick();
+
+        // Stealing voice 0 for C5 slides it up an octave over 100 samples
+        assert_eq!(allocator.note_on(72, 0.8), Some(0));
+        assert_eq!(allocator.voice(0).unwrap().voct, 0.0);
+        for i in 1..=100 {
+            allocator.tick();
+            let expected = i as f64 / 100.0;
+            assert!((allocator.voice(0).unwrap().voct - expected).abs() < 1e-12);
+            // The other voice glides (or holds) on its own
+            assert_eq!(allocator.voice(1).unwrap().voct, -1.0);
+        }
+        assert!(!allocator.voice(0).unwrap().is_gliding());
+        allocator.tick();
+        assert_eq!(allocator.voice(0).unwrap().voct, 1.0);
+
+        // After a panic the voices have no pitch to glide from
+        allocator.panic();
+        for note in [67, 69] {
+            let index = allocator.note_on(note, 0.8).unwrap();
+            let voice = allocator.voice(index).unwrap();
+            assert_eq!(voice.voct, midi_note_to_voct(note));
+            assert!(!voice.is_gliding());
+        }
+    }
+
     #[test]
     fn test_no_steal_mode() {
         let mut allocator = VoiceAllocator::new(2);
@@ -1107,6 +1767,85 @@ mod tests {
         assert_eq!(poly.sample_rate(), 48000.0);
     }
 
+    #[test]
+    fn test_poly_patch_humanize_is_seeded_per_note() {
+        let play_twice = |humanize: f64| {
+            let mut poly = PolyPatch::new(4, 44100.0);
+            poly.set_humanize(humanize);
+            poly.set_humanize_seed(7);
+            poly.note_on(60, 100);
+            let first = poly.voice_humanize(0).unwrap();
+            poly.note_off(60);
+            poly.note_on(60, 100);
+            let second = poly.voice_humanize(1).unwrap();
+            (first, second)
+        };
+
+        let (first, second) = play_twice(1.0);
+        assert_ne!(first.detune, second.detune);
+        for offsets in [first, second] {
+            assert!(offsets.detune.abs() <= 10.0 / 1200.0);
+            assert!(offsets.delay <= 221);
+        }
+        // Same seed, same offsets
+        assert_eq!(play_twice(1.0), (first, second));
+
+        // No humanize: identical voices, exactly on pitch and time
+        let (a, b) = play_twice(0.0);
+        assert_eq!(a, VoiceHumanize::default());
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_poly_patch_humanize_delays_gate_and_resets() {
+        let mut poly = PolyPatch::new(1, 44100.0);
+        poly.set_humanize(1.0);
+        poly.set_humanize_seed(3);
+        poly.note_on(60, 100);
+        let offsets = poly.voice_humanize(0).unwrap();
+        assert!(offsets.delay > 0);
+
+        let mut opened_at = None;
+        for sample in 0..400 {
+            poly.tick();
+            let input = poly.voice_input(0).unwrap();
+            if input.gate > 0.5 && opened_at.is_none() {
+                opened_at = Some(sample);
+                assert!(input.trigger > 0.5);
+            }
+        }
+        assert_eq!(opened_at, Some(offsets.delay as usize));
+
+        // Reset reseeds, so the next note draws the first offsets again
+        poly.reset();
+        assert_eq!(poly.voice_humanize(0), Some(VoiceHumanize::default()));
+        poly.note_on(60, 100);
+        assert_eq!(poly.voice_humanize(0), Some(offsets));
+    }
+
+    #[test]
+    fn test_poly_patch_glide_time() {
+        let mut poly = PolyPatch::new(1, 1000.0);
+        poly.set_glide(0.05);
+        assert_eq!(poly.glide(), 0.05);
+        assert_eq!(poly.allocator().glide_samples(), 50);
+        poly.set_sample_rate(2000.0);
+        assert_eq!(poly.allocator().glide_samples(), 100);
+
+        poly.note_on(60, 100);
+        poly.tick();
+        poly.note_on(64, 100);
+        for _ in 0..50 {
+            poly.tick();
+        }
+        // Halfway through the 100-sample glide up four semitones
+        let voct = poly.allocator().voice(0).unwrap().voct;
+        assert!((voct - 2.0 / 12.0).abs() < 1e-12);
+
+        poly.set_glide(-1.0);
+        assert_eq!(poly.allocator().glide_samples(), 0);
+    }
+
     #[test]
     fn test_poly_patch_voice_input_access() {
         let mut poly = PolyPatch::new(4, 44100.0);
@@ -1242,4 +1981,172 @@ mod tests {
         mixer.set_sample_rate(48000.0);
         assert_eq!(mixer.type_id(), "voice_mixer");
     }
+
+    #[test]
+    fn test_voice_mixer_pitch_spread() {
+        let mut allocator = VoiceAllocator::new(4);
+        for note in [64, 48, 72, 60] {
+            allocator.note_on(note, 1.0);
+        }
+        let mut mixer = VoiceMixer::new(4)
+            .with_spread(1.0)
+            .with_pan_mode(VoicePanMode::ByPitch);
+        mixer.set_from_allocator(&allocator);
+
+        // Voice 2 holds the highest note and goes hard right, voice 1 hard left
+        assert_eq!(mixer.pan(2), Some(1.0));
+        assert_eq!(mixer.pan(1), Some(-1.0));
+        assert!(mixer.pan(0).unwrap() > mixer.pan(3).unwrap());
+
+        let mut inputs = PortValues::new();
+        let mut outputs = PortValues::new();
+        for port in 0..8 {
+            inputs.set(port, 1.0);
+        }
+        mixer.tick(&inputs, &mut outputs);
+        assert!((outputs.get(100).unwrap() - (3.0 - 1.0 / 3.0)).abs() < 1e-12);
+
+        // By index, voice 3 is furthest right instead
+        mixer.set_pan_mode(VoicePanMode::ByIndex);
+        assert_eq!(mixer.pan(3), Some(1.0));
+    }
+
+    #[test]
+    fn test_voice_mixer_single_voice_centered_and_voice_outputs() {
+        let mut allocator = VoiceAllocator::new(4);
+        allocator.note_on(72, 1.0);
+        allocator.note_on(60, 1.0);
+        allocator.voices_mut()[0].free();
+
+        let mut mixer = VoiceMixer::new(4)
+            .with_spread(1.0)
+            .with_pan_mode(VoicePanMode::ByPitch)
+            .with_voice_outputs(true);
+        mixer.set_from_allocator(&allocator);
+        assert_eq!(mixer.pan(1), Some(0.0));
+        assert_eq!(mixer.port_spec().outputs.len(), 10);
+
+        let mut inputs = PortValues::new();
+        let mut outputs = PortValues::new();
+        inputs.set(2, 0.5);
+        inputs.set(3, 0.25);
+        mixer.tick(&inputs, &mut outputs);
+        assert_eq!(outputs.get(VoiceMixer::VOICE_OUTPUT_BASE + 2), Some(0.5));
+        assert_eq!(outputs.get(VoiceMixer::VOICE_OUTPUT_BASE + 3), Some(0.25));
+        assert_eq!(outputs.get(100), Some(0.5));
+        assert_eq!(outputs.get(101), Some(0.25));
+    }
+
+    fn play_chord(mode: AllocationMode, notes: &[u8]) -> (MidiVoiceRouter, VoiceAllocator) {
+        let mut allocator = VoiceAllocator::new(4);
+        allocator.set_mode(mode);
+        let mut router = MidiVoiceRouter::new();
+        for &note in notes {
+            router.handle_allocator_message(&mut allocator, &[0x90, note, 100]);
+        }
+        (router, allocator)
+    }
+
+    fn sounding(allocator: &VoiceAllocator) -> Vec<u8> {
+        let mut notes: Vec<u8> = allocator.voices().iter().filter_map(|v| v.note).collect();
+        notes.sort_unstable();
+        notes
+    }
+
+    #[test]
+    fn test_midi_router_steals_two_of_six_notes_per_mode() {
+        let chord = [60, 64, 67, 72, 48, 79];
+
+        // The two earliest notes go first, even when struck together
+        for mode in [AllocationMode::OldestSteal, AllocationMode::RoundRobin] {
+            let (router, allocator) = play_chord(mode, &chord);
+            assert_eq!(router.stolen_notes(), &[60, 64]);
+            assert_eq!(sounding(&allocator), vec![48, 67, 72, 79]);
+        }
+
+        // Low notes win: 48 takes the top note, 79 has nothing higher to steal
+        let (router, allocator) = play_chord(AllocationMode::LowestPriority, &chord);
+        assert_eq!(router.stolen_notes(), &[72, 79]);
+        assert_eq!(sounding(&allocator), vec![48, 60, 64, 67]);
+        let (router, allocator) =
+            play_chord(AllocationMode::LowestPriority, &[60, 64, 67, 72, 55, 48]);
+        assert_eq!(router.stolen_notes(), &[72, 67]);
+        assert_eq!(sounding(&allocator), vec![48, 55, 60, 64]);
+
+        // High notes win: the lowest held notes are dropped
+        let (router, allocator) =
+            play_chord(AllocationMode::HighestPriority, &[60, 64, 67, 72, 76, 79]);
+        assert_eq!(router.stolen_notes(), &[60, 64]);
+        assert_eq!(sounding(&allocator), vec![67, 72, 76, 79]);
+
+        // Without stealing the extra notes never sound
+        let (router, allocator) = play_chord(AllocationMode::NoSteal, &chord);
+        assert_eq!(router.stolen_notes(), &[48, 79]);
+        assert_eq!(sounding(&allocator), vec![60, 64, 67, 72]);
+        assert_eq!(router.held_notes(), &chord);
+    }
+
+    #[test]
+    fn test_midi_router_steals_release_tails_first_and_sustains() {
+        let (mut router, mut allocator) =
+            play_chord(AllocationMode::OldestSteal, &[60, 64, 67, 72]);
+        for i in 0..4 {
+            allocator.set_envelope_level(i, 0.5);
+        }
+
+        // 67 is released but still ringing, so it goes before the older 60
+        router.handle_allocator_message(&mut allocator, &[0x80, 67, 0]);
+        allocator.tick();
+        assert_eq!(allocator.voice(2).unwrap().state, VoiceState::Releasing);
+        router.handle_allocator_message(&mut allocator, &[0x90, 74, 90]);
+        assert_eq!(allocator.voice(2).unwrap().note, Some(74));
+        assert!(router.stolen_notes().is_empty());
+
+        // Stealing retriggers the voice and the inputs follow it
+        let mut inputs: Vec<VoiceInput> = (0..4).map(|_| VoiceInput::new()).collect();
+        MidiVoiceRouter::write_voice_inputs(&allocator, &mut inputs);
+        let mut outputs = PortValues::new();
+        inputs[2].tick(&PortValues::new(), &mut outputs);
+        assert!((outputs.get(0).unwrap() - midi_note_to_voct(74)).abs() < 1e-12);
+        assert_eq!(outputs.get(1), Some(5.0));
+        assert_eq!(outputs.get(2), Some(5.0));
+        assert!((outputs.get(3).unwrap() - 90.0 / 127.0 * 10.0).abs() < 1e-12);
+
+        // The sustain pedal holds note-offs until it lifts
+        router.handle_allocator_message(&mut allocator, &[0xB0, 64, 127]);
+        router.handle_allocator_message(&mut allocator, &[0x80, 60, 0]);
+        assert_eq!(allocator.voice(0).unwrap().state, VoiceState::Active);
+        router.handle_allocator_message(&mut allocator, &[0xB0, 64, 0]);
+        assert_eq!(allocator.voice(0).unwrap().state, VoiceState::Releasing);
+
+        // Other channels are ignored once a channel is set
+        let mut router = router.with_channel(1);
+        router.handle_allocator_message(&mut allocator, &[0x80, 64, 0]);
+        assert_eq!(allocator.voice(1).unwrap().state, VoiceState::Active);
+        router.handle_allocator_message(&mut allocator, &[0xB1, 123, 0]);
+        assert!(router.held_notes().is_empty());
+        assert_eq!(allocator.voice(1).unwrap().state, VoiceState::Releasing);
+    }
+
+    #[test]
+    fn test_midi_router_plays_chord_into_poly_patch() {
+        let mut poly = PolyPatch::new(4, 44100.0);
+        poly.allocator_mut().set_mode(AllocationMode::OldestSteal);
+        let mut router = MidiVoiceRouter::new();
+
+        for note in 60..68 {
+            router.handle_message(&mut poly, &[0x90, note, 100]);
+        }
+        assert_eq!(router.stolen_notes(), &[60, 61, 62, 63]);
+        assert_eq!(sounding(poly.allocator()), vec![64, 65, 66, 67]);
+
+        // Releasing a stolen key leaves the sounding voices alone
+        router.handle_message(&mut poly, &[0x90, 61, 0]);
+        assert_eq!(router.stolen_notes(), &[60, 62, 63]);
+        assert_eq!(poly.allocator().active_count(), 4);
+
+        router.handle_message(&mut poly, &[0xB0, 120, 0]);
+        assert_eq!(poly.allocator().active_count(), 0);
+        assert!(router.stolen_notes().is_empty());
+    }
 }
@@ -3,6 +3,10 @@
 //! This module defines the signal types, port definitions, and type-erased interfaces
 //! that bridge the typed combinator layer with the graph-based patching system.
 
+use crate::introspection::{ParamCurve, ValueFormat};
+use crate::rng::Rng;
+use crate::simd::ProcessContext;
+use crate::transport::Transport;
 use crate::StdMap;
 use alloc::string::String;
 #[cfg(feature = "wasm")]
@@ -242,6 +246,42 @@ pub fn ports_compatible(from: SignalKind, to: SignalKind) -> Compatibility {
     }
 }
 
+/// How an input combines several cables patched into it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub enum MergeMode {
+    /// Add the cables, like stacked cables on a hardware jack
+    #[default]
+    Sum,
+    /// Mean of the cables
+    Average,
+    /// Largest value, which ORs gates and triggers
+    Max,
+    /// The most recently connected cable wins
+    Replace,
+}
+
+impl MergeMode {
+    /// Fold the value of the `index`th cable into the running result
+    #[inline]
+    pub fn fold(self, acc: f64, value: f64, index: usize) -> f64 {
+        match self {
+            MergeMode::Sum | MergeMode::Average => acc + value,
+            MergeMode::Max if index > 0 => acc.max(value),
+            MergeMode::Max | MergeMode::Replace => value,
+        }
+    }
+
+    /// The merged value once `count` cables have been folded in
+    #[inline]
+    pub fn finish(self, acc: f64, count: usize) -> f64 {
+        match self {
+            MergeMode::Average if count > 0 => acc / count as f64,
+            _ => acc,
+        }
+    }
+}
+
 /// Definition of a single port (input or output)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
@@ -263,6 +303,10 @@ pub struct PortDef {
 
     /// Whether this input has an associated attenuverter control
     pub has_attenuverter: bool,
+
+    /// For inputs: how several cables into the port combine
+    #[serde(default)]
+    pub merge_mode: MergeMode,
 }
 
 impl PortDef {
@@ -274,6 +318,7 @@ impl PortDef {
             default: 0.0,
             normalled_to: None,
             has_attenuverter: false,
+            merge_mode: MergeMode::Sum,
         }
     }
 
@@ -291,6 +336,12 @@ impl PortDef {
         self.normalled_to = Some(port);
         self
     }
+
+    /// Combine multiple cables with `mode` instead of summing them
+    pub fn with_merge_mode(mut self, mode: MergeMode) -> Self {
+        self.merge_mode = mode;
+        self
+    }
 }
 
 /// Specification of all ports for a module
@@ -361,6 +412,341 @@ impl PortValues {
     }
 }
 
+/// Shared interface over port value storage
+///
+/// Implemented by the heap-backed [`PortValues`] and the fixed-capacity
+/// [`StackPortValues`]. Modules whose per-sample logic is written against
+/// this trait run unchanged on either, so embedded targets can process
+/// without touching the allocator.
+///
+/// This is only a stack store for two helper functions, `Vca::process` and
+/// `Offset::process`. `GraphModule::tick` and the `Patch` engine are not
+/// generic over it and always take `PortValues`, so a patch still allocates
+/// its port values.
+pub trait PortStore {
+    fn get(&self, id: PortId) -> Option<f64>;
+
+    fn set(&mut self, id: PortId, value: f64);
+
+    fn clear(&mut self);
+
+    fn get_or(&self, id: PortId, default: f64) -> f64 {
+        self.get(id).unwrap_or(default)
+    }
+
+    /// Accumulate (sum) a value into a port (for input mixing)
+    fn accumulate(&mut self, id: PortId, value: f64) {
+        let current = self.get(id).unwrap_or(0.0);
+        self.set(id, current + value);
+    }
+
+    fn has(&self, id: PortId) -> bool {
+        self.get(id).is_some()
+    }
+}
+
+impl PortStore for PortValues {
+    fn get(&self, id: PortId) -> Option<f64> {
+        PortValues::get(self, id)
+    }
+
+    fn set(&mut self, id: PortId, value: f64) {
+        PortValues::set(self, id, value);
+    }
+
+    fn clear(&mut self) {
+        PortValues::clear(self);
+    }
+}
+
+/// Frequency response of a filter at its current settings
+///
+/// Evaluated from the coefficients of the most recent `tick` (or the input
+/// defaults before the first one) without touching the audio state, so a UI
+/// can redraw a filter or EQ curve while it is being tweaked. Nonlinear
+/// stages such as drive are linearized around zero, giving the small-signal
+/// response.
+pub trait FrequencyResponse {
+    /// Linear magnitude of the main output at each frequency in Hz
+    fn magnitude_response(&self, freqs: &[f64]) -> Vec<f64>;
+}
+
+/// Writer for a module's runtime state (see `GraphModule::serialize_runtime`)
+///
+/// Values are stored as little-endian bytes without field names, so a module
+/// must read them back in the order it wrote them.
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeWriter {
+    bytes: Vec<u8>,
+}
+
+impl RuntimeWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn put_f64(&mut self, value: f64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn put_u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn put_usize(&mut self, value: usize) {
+        self.put_u64(value as u64);
+    }
+
+    pub fn put_bool(&mut self, value: bool) {
+        self.bytes.push(value as u8);
+    }
+
+    /// Write a length-prefixed run of values (e.g. a delay buffer)
+    pub fn put_f64s(&mut self, values: &[f64]) {
+        self.put_usize(values.len());
+        for &value in values {
+            self.put_f64(value);
+        }
+    }
+
+    /// Write an optional value as a presence flag and the value (0 if absent)
+    pub fn put_opt_f64(&mut self, value: Option<f64>) {
+        self.put_bool(value.is_some());
+        self.put_f64(value.unwrap_or(0.0));
+    }
+
+    /// Write an optional counter as a presence flag and the count (0 if absent)
+    pub fn put_opt_u64(&mut self, value: Option<u64>) {
+        self.put_bool(value.is_some());
+        self.put_u64(value.unwrap_or(0));
+    }
+
+    /// Write a generator's position, so it continues the same sequence
+    pub fn put_rng(&mut self, rng: &Rng) {
+        let (s0, s1) = rng.state();
+        self.put_u64(s0);
+        self.put_u64(s1);
+    }
+
+    /// Write a length-prefixed byte string
+    pub fn put_bytes(&mut self, bytes: &[u8]) {
+        self.put_usize(bytes.len());
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reader for state written by a [`RuntimeWriter`]
+#[derive(Debug, Clone)]
+pub struct RuntimeReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> RuntimeReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| String::from("runtime state is truncated"))?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn take_array(&mut self) -> Result<[u8; 8], String> {
+        let mut array = [0; 8];
+        array.copy_from_slice(self.take(8)?);
+        Ok(array)
+    }
+
+    pub fn f64(&mut self) -> Result<f64, String> {
+        Ok(f64::from_le_bytes(self.take_array()?))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take_array()?))
+    }
+
+    pub fn usize(&mut self) -> Result<usize, String> {
+        usize::try_from(self.u64()?).map_err(|_| String::from("runtime state value out of range"))
+    }
+
+    pub fn bool(&mut self) -> Result<bool, String> {
+        Ok(self.take(1)?[0] != 0)
+    }
+
+    /// Read a value written by `put_opt_f64`
+    pub fn opt_f64(&mut self) -> Result<Option<f64>, String> {
+        let present = self.bool()?;
+        let value = self.f64()?;
+        Ok(Some(value).filter(|_| present))
+    }
+
+    /// Read a counter written by `put_opt_u64`
+    pub fn opt_u64(&mut self) -> Result<Option<u64>, String> {
+        let present = self.bool()?;
+        let value = self.u64()?;
+        Ok(Some(value).filter(|_| present))
+    }
+
+    /// Read a generator written by `put_rng`
+    pub fn rng(&mut self) -> Result<Rng, String> {
+        let (s0, s1) = (self.u64()?, self.u64()?);
+        Ok(Rng::new(s0, s1))
+    }
+
+    /// Read a run written by `put_f64s`
+    pub fn f64s(&mut self) -> Result<Vec<f64>, String> {
+        let len = self.usize()?;
+        if len > self.remaining() / 8 {
+            return Err(String::from("runtime state is truncated"));
+        }
+        (0..len).map(|_| self.f64()).collect()
+    }
+
+    /// Read a run written by `put_f64s` into `out`, which must have the same length
+    pub fn f64s_into(&mut self, out: &mut [f64]) -> Result<(), String> {
+        let len = self.usize()?;
+        if len != out.len() {
+            return Err(alloc::format!(
+                "runtime state holds {} values, expected {}",
+                len,
+                out.len()
+            ));
+        }
+        for value in out.iter_mut() {
+            *value = self.f64()?;
+        }
+        Ok(())
+    }
+
+    /// Read a byte string written by `put_bytes`
+    pub fn bytes(&mut self) -> Result<&'a [u8], String> {
+        let len = self.usize()?;
+        self.take(len)
+    }
+
+    /// Bytes not yet read
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    /// Fail if bytes are left unread, e.g. after a wrapped module's state
+    pub fn finish(&self) -> Result<(), String> {
+        match self.remaining() {
+            0 => Ok(()),
+            n => Err(alloc::format!("{} bytes of runtime state left unread", n)),
+        }
+    }
+}
+
+/// Fixed-capacity port values stored inline (no allocation)
+///
+/// Holds up to `N` (id, value) pairs. Writing a new port once full is a
+/// logic error: it panics in debug builds and is dropped in release builds,
+/// so the audio loop never allocates or grows.
+///
+/// Accepted only by `Vca::process` and `Offset::process`, never by a `Patch`.
+#[derive(Debug, Clone, Copy)]
+pub struct StackPortValues<const N: usize> {
+    ids: [PortId; N],
+    values: [f64; N],
+    len: usize,
+}
+
+impl<const N: usize> StackPortValues<N> {
+    pub const fn new() -> Self {
+        Self {
+            ids: [0; N],
+            values: [0.0; N],
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Iterate over the stored (id, value) pairs in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (PortId, f64)> + '_ {
+        self.ids[..self.len]
+            .iter()
+            .copied()
+            .zip(self.values[..self.len].iter().copied())
+    }
+
+    fn position(&self, id: PortId) -> Option<usize> {
+        self.ids[..self.len].iter().position(|&p| p == id)
+    }
+}
+
+impl<const N: usize> Default for StackPortValues<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> PortStore for StackPortValues<N> {
+    fn get(&self, id: PortId) -> Option<f64> {
+        self.position(id).map(|i| self.values[i])
+    }
+
+    fn set(&mut self, id: PortId, value: f64) {
+        if let Some(i) = self.position(id) {
+            self.values[i] = value;
+        } else if self.len < N {
+            self.ids[self.len] = id;
+            self.values[self.len] = value;
+            self.len += 1;
+        } else {
+            debug_assert!(false, "StackPortValues capacity {N} exceeded by port {id}");
+        }
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> From<&StackPortValues<N>> for PortValues {
+    fn from(stack: &StackPortValues<N>) -> Self {
+        let mut values = PortValues::new();
+        for (id, value) in stack.iter() {
+            values.set(id, value);
+        }
+        values
+    }
+}
+
 /// Block-oriented port values for efficient processing
 pub struct BlockPortValues {
     buffers: StdMap<PortId, Vec<f64>>,
@@ -408,6 +794,34 @@ impl BlockPortValues {
         }
     }
 
+    /// Remove a port's buffer to write it alongside others, zeroed if absent
+    ///
+    /// Hand it back with `put_buffer`; the pair reuses the allocation.
+    pub fn take_buffer(&mut self, port: PortId) -> Vec<f64> {
+        self.buffers
+            .remove(&port)
+            .unwrap_or_else(|| vec![0.0; self.block_size])
+    }
+
+    /// Store a buffer taken with `take_buffer`
+    pub fn put_buffer(&mut self, port: PortId, buffer: Vec<f64>) {
+        self.buffers.insert(port, buffer);
+    }
+
+    /// Iterate over the port buffers
+    pub fn buffers(&self) -> impl Iterator<Item = (PortId, &[f64])> + '_ {
+        self.buffers
+            .iter()
+            .map(|(&port, buffer)| (port, buffer.as_slice()))
+    }
+
+    /// Iterate over the port buffers mutably
+    pub fn buffers_mut(&mut self) -> impl Iterator<Item = (PortId, &mut [f64])> + '_ {
+        self.buffers
+            .iter_mut()
+            .map(|(&port, buffer)| (port, buffer.as_mut_slice()))
+    }
+
     pub fn clear(&mut self) {
         for buffer in self.buffers.values_mut() {
             buffer.fill(0.0);
@@ -446,6 +860,31 @@ impl ParamRange {
             }
         }
     }
+
+    /// Inverse of [`apply`](Self::apply): the input that produces `value`
+    pub fn normalize(&self, value: f64) -> f64 {
+        let (min, max) = self.bounds();
+        match self {
+            ParamRange::Linear { .. } if max > min => ((value - min) / (max - min)).clamp(0.0, 1.0),
+            ParamRange::Exponential { .. } if min > 0.0 && max > min => {
+                (Libm::<f64>::log(value.max(min) / min) / Libm::<f64>::log(max / min))
+                    .clamp(0.0, 1.0)
+            }
+            ParamRange::Exponential { .. } if max > 0.0 => (value / max).clamp(0.0, 1.0),
+            ParamRange::VoltPerOctave { base_freq } => {
+                Libm::<f64>::log2(value.clamp(min, max) / base_freq)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Smallest and largest output; a V/Oct range spans ±5V
+    pub fn bounds(&self) -> (f64, f64) {
+        match *self {
+            ParamRange::Linear { min, max } | ParamRange::Exponential { min, max } => (min, max),
+            ParamRange::VoltPerOctave { base_freq } => (base_freq / 32.0, base_freq * 32.0),
+        }
+    }
 }
 
 /// A parameter that combines a base value (knob) with CV modulation
@@ -493,6 +932,101 @@ impl ModulatedParam {
     }
 }
 
+/// A parameter that glides to new values instead of jumping
+///
+/// One-pole smoothing toward a target: every sample covers a fixed fraction
+/// of the remaining distance, so a host slider moved in steps produces a
+/// smooth curve instead of zipper noise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothedParam {
+    current: f64,
+    target: f64,
+    coef: f64,
+}
+
+impl SmoothedParam {
+    /// Distance from the target at which the glide snaps to it
+    const SETTLE: f64 = 1e-9;
+
+    pub fn new(value: f64) -> Self {
+        Self {
+            current: value,
+            target: value,
+            coef: 0.0,
+        }
+    }
+
+    /// Jump straight to `value`
+    pub fn set(&mut self, value: f64) {
+        self.current = value;
+        self.target = value;
+    }
+
+    /// Glide to `value`, covering 99% of the distance in `ms` milliseconds
+    pub fn set_target(&mut self, value: f64, ms: f64, sample_rate: f64) {
+        let samples = ms * 0.001 * sample_rate;
+        if samples < 1.0 {
+            self.set(value);
+            return;
+        }
+        self.target = value;
+        self.coef = Libm::<f64>::exp(-Libm::<f64>::log(100.0) / samples);
+    }
+
+    /// Advance one sample and return the new value
+    #[inline]
+    pub fn tick(&mut self) -> f64 {
+        self.advance(1)
+    }
+
+    /// Advance `samples` samples at once and return the new value
+    pub fn advance(&mut self, samples: usize) -> f64 {
+        if self.current != self.target {
+            let decay = match samples {
+                1 => self.coef,
+                n => Libm::<f64>::pow(self.coef, n as f64),
+            };
+            let remaining = (self.current - self.target) * decay;
+            self.current = if remaining.abs() < Self::SETTLE {
+                self.target
+            } else {
+                self.target + remaining
+            };
+        }
+        self.current
+    }
+
+    /// Current value along the glide
+    pub fn value(&self) -> f64 {
+        self.current
+    }
+
+    /// Value the glide is heading for
+    pub fn target(&self) -> f64 {
+        self.target
+    }
+
+    /// Whether the glide has reached its target
+    pub fn is_settled(&self) -> bool {
+        self.current == self.target
+    }
+
+    /// Write the glide's position for `GraphModule::serialize_runtime`
+    pub fn serialize_runtime(&self, state: &mut RuntimeWriter) {
+        state.put_f64(self.current);
+        state.put_f64(self.target);
+        state.put_f64(self.coef);
+    }
+
+    /// Restore state written by `serialize_runtime`
+    pub fn deserialize_runtime(&mut self, state: &mut RuntimeReader<'_>) -> Result<(), String> {
+        self.current = state.f64()?;
+        self.target = state.f64()?;
+        self.coef = state.f64()?;
+        Ok(())
+    }
+}
+
 /// Parameter definition for UI binding
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParamDef {
@@ -500,6 +1034,48 @@ pub struct ParamDef {
     pub name: String,
     pub default: f64,
     pub range: ParamRange,
+    /// How a control sweeps the range
+    #[serde(default)]
+    pub curve: ParamCurve,
+    /// How the value is displayed
+    #[serde(default)]
+    pub format: ValueFormat,
+}
+
+impl ParamDef {
+    /// A parameter defaulting to the bottom of its range, swept with the
+    /// range's own curve and shown as a plain number
+    pub fn new(id: ParamId, name: impl Into<String>, range: ParamRange) -> Self {
+        let curve = match range {
+            ParamRange::Linear { .. } => ParamCurve::Linear,
+            ParamRange::Exponential { .. } | ParamRange::VoltPerOctave { .. } => {
+                ParamCurve::Exponential
+            }
+        };
+        Self {
+            id,
+            name: name.into(),
+            default: range.bounds().0,
+            range,
+            curve,
+            format: ValueFormat::default(),
+        }
+    }
+
+    pub fn with_default(mut self, default: f64) -> Self {
+        self.default = default;
+        self
+    }
+
+    pub fn with_curve(mut self, curve: ParamCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    pub fn with_format(mut self, format: ValueFormat) -> Self {
+        self.format = format;
+        self
+    }
 }
 
 /// Type-erased module interface for graph-based patching
@@ -525,12 +1101,39 @@ pub trait GraphModule: Send + Sync {
         }
     }
 
+    /// Process `ctx.block_size` samples at once
+    ///
+    /// `Patch` calls this instead of `tick` when it runs with a block size
+    /// above 1. The default loops `tick` over the frames, which produces
+    /// exactly the per-sample output; hot modules override it to walk whole
+    /// buffers and compute their coefficients once per block while the
+    /// controlling inputs hold still.
+    fn tick_block(
+        &mut self,
+        inputs: &BlockPortValues,
+        outputs: &mut BlockPortValues,
+        ctx: &ProcessContext,
+    ) {
+        self.process_block(inputs, outputs, ctx.block_size);
+    }
+
     /// Reset internal state
     fn reset(&mut self);
 
     /// Set sample rate
     fn set_sample_rate(&mut self, sample_rate: f64);
 
+    /// Receive the patch transport before each tick (only called when the
+    /// patch has a transport set via `Patch::set_transport`)
+    fn sync_transport(&mut self, _transport: &Transport) {}
+
+    /// Take over a random stream handed out by `Patch::set_seed`
+    ///
+    /// Modules that draw random numbers reseed from `rng` (their `reset`
+    /// returns to this point), so a patch renders identically for the same
+    /// master seed. The default ignores it, which suits deterministic modules.
+    fn set_rng(&mut self, _rng: Rng) {}
+
     /// Get parameter definitions for UI binding
     fn params(&self) -> &[ParamDef] {
         &[]
@@ -544,11 +1147,117 @@ pub trait GraphModule: Send + Sync {
     /// Set a parameter value
     fn set_param(&mut self, _id: ParamId, _value: f64) {}
 
+    /// Glide a parameter to `value` over about `ms` milliseconds
+    ///
+    /// Returns `true` when the module smooths the change itself, usually
+    /// with a [`SmoothedParam`] ticked in `tick`. The default returns
+    /// `false` without touching the parameter; `Patch::set_param_smoothed`
+    /// then glides it by calling `set_param` every sample.
+    fn set_param_smoothed(&mut self, _id: ParamId, _value: f64, _ms: f64) -> bool {
+        false
+    }
+
     /// Get module type identifier for serialization
     fn type_id(&self) -> &'static str {
         "unknown"
     }
 
+    /// Whether `serialize_runtime` captures the module's complete runtime state
+    ///
+    /// `Patch::save_runtime_state` and `restore_runtime_state` fail on a
+    /// patch holding a module that returns `false`, the default, rather than
+    /// resuming it from the wrong state. Stateless modules return `true`
+    /// without writing anything; stateful ones alongside the two hooks below.
+    fn supports_runtime_state(&self) -> bool {
+        false
+    }
+
+    /// Write the module's complete runtime state for `Patch::save_runtime_state`
+    ///
+    /// Covers everything that evolves while running (filter memories, delay
+    /// buffers, phases, envelope stages, RNG positions), not parameters.
+    fn serialize_runtime(&self, _state: &mut RuntimeWriter) {}
+
+    /// Restore state written by `serialize_runtime`
+    fn deserialize_runtime(&mut self, _state: &mut RuntimeReader<'_>) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Freeze generated randomness into fixed state, for `Patch::freeze_to_params`
+    ///
+    /// A stochastic module swaps its random draws for the values it last
+    /// generated, so it repeats them from then on. Returns whether anything
+    /// was frozen; the default is a no-op for modules with nothing random.
+    fn freeze_to_params(&mut self) -> bool {
+        false
+    }
+
+    /// Relative per-sample processing cost, used by `Patch::estimated_cost`
+    ///
+    /// A static estimate in units of a simple module such as a `Vca` or
+    /// `Vco`. Heavy modules (reverbs, FFT and convolution processors)
+    /// report more; variable-size modules scale with their size.
+    fn cost_hint(&self) -> u32 {
+        1
+    }
+
+    /// Delay in samples between an input and the matching output
+    ///
+    /// Reported by modules that buffer their audio, such as lookahead,
+    /// oversampling, or FFT processors. `Patch::compile` adds it up along
+    /// each path and delays the shorter branches into a node to match.
+    fn latency_samples(&self) -> usize {
+        0
+    }
+
+    /// Whether the module's outputs depend only on earlier inputs
+    ///
+    /// A module that returns `true`, like `UnitDelay`, may close a feedback
+    /// loop. It must also implement `feedback_outputs`: the patch writes
+    /// those before the rest of the loop runs, then ticks the module once
+    /// the loop has produced its input.
+    fn breaks_feedback(&self) -> bool {
+        false
+    }
+
+    /// The outputs the next `tick` will write, for a module that `breaks_feedback`
+    fn feedback_outputs(&self, _outputs: &mut PortValues) {}
+
+    /// Whether the patch should strip DC from the module's audio outputs
+    ///
+    /// `Patch::compile` puts a [`DcBlocker`](crate::modules::DcBlocker) on
+    /// every audio output of a module that returns `true`, for modules whose
+    /// output drifts off zero, such as asymmetric waveshapers.
+    fn wants_dc_block(&self) -> bool {
+        false
+    }
+
+    /// Input and output a bypassed module passes its signal between
+    ///
+    /// Used by `Patch::set_bypass`. The default finds effect-style modules
+    /// from the port spec: one audio input and one audio output. Modules
+    /// with a different shape, such as a mono-in, stereo-out reverb, opt in
+    /// by naming the pair; `None` means the module cannot be bypassed.
+    fn bypass_route(&self) -> Option<(PortId, PortId)> {
+        let spec = self.port_spec();
+        let mut inputs = spec.inputs.iter().filter(|p| p.kind == SignalKind::Audio);
+        let mut outputs = spec.outputs.iter().filter(|p| p.kind == SignalKind::Audio);
+        match (inputs.next(), inputs.next(), outputs.next(), outputs.next()) {
+            (Some(input), None, Some(output), None) => Some((input.id, output.id)),
+            _ => None,
+        }
+    }
+
+    /// Structural arguments the module was constructed with (alloc feature only)
+    ///
+    /// Captured in the patch definition and handed back to the registry
+    /// factory on load, so e.g. a 6-channel `Mixer` is rebuilt with 6
+    /// channels. `Null` means the default construction is sufficient.
+    #[cfg(feature = "alloc")]
+    fn construction_args(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
     /// Serialize module state (alloc feature only)
     #[cfg(feature = "alloc")]
     fn serialize_state(&self) -> Option<serde_json::Value> {
@@ -889,4 +1598,58 @@ mod tests {
         assert!(json.contains("warning"));
         assert!(json.contains("test"));
     }
+
+    #[test]
+    fn test_stack_port_values() {
+        let mut pv = StackPortValues::<3>::new();
+        assert!(pv.is_empty());
+        pv.set(0, 1.0);
+        pv.set(10, 2.0);
+        pv.set(0, 1.5);
+        assert_eq!(pv.len(), 2);
+        assert_eq!(pv.get(0), Some(1.5));
+        assert_eq!(pv.get_or(5, 7.0), 7.0);
+
+        pv.accumulate(10, 0.5);
+        assert_eq!(pv.get(10), Some(2.5));
+        pv.set(11, 3.0);
+        assert!(pv.is_full());
+
+        let heap = PortValues::from(&pv);
+        assert_eq!(heap.get(11), Some(3.0));
+
+        pv.clear();
+        assert!(!pv.has(0));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "capacity")]
+    fn test_stack_port_values_overflow_asserts() {
+        let mut pv = StackPortValues::<1>::new();
+        pv.set(0, 1.0);
+        pv.set(1, 2.0);
+    }
+
+    #[test]
+    fn test_smoothed_param_glides_to_target() {
+        let mut param = SmoothedParam::new(0.0);
+        param.set_target(1.0, 10.0, 44100.0);
+        assert_eq!(param.value(), 0.0);
+
+        let first = param.tick();
+        assert!(first > 0.0 && first < 0.02);
+        // 99% of the way after 10ms
+        let at_10ms = param.advance(440);
+        assert!((at_10ms - 0.99).abs() < 1e-3, "{}", at_10ms);
+        assert!(!param.is_settled());
+
+        param.advance(44100);
+        assert!(param.is_settled());
+        assert_eq!(param.value(), 1.0);
+
+        // Too short to glide: jump
+        param.set_target(0.5, 0.0, 44100.0);
+        assert_eq!(param.value(), 0.5);
+    }
 }
@@ -22,10 +22,16 @@
 //!
 //! // Get and build a preset
 //! let patch = library.get("Moog Bass")?.build(44100.0)?;
+//!
+//! // Save the current patch as a user preset and load it back
+//! let mut library = PresetLibrary::new();
+//! let id = library.add_from_patch(&patch, PresetInfo::new("My Bass", PresetCategory::Bass))?;
+//! let patch = library.load_user(id, 44100.0)?;
 //! ```
 
 use crate::graph::Patch;
 use crate::serialize::{CableDef, ModuleDef, ModuleRegistry, PatchDef};
+use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
@@ -93,6 +99,9 @@ impl PresetInfo {
     }
 }
 
+/// Identifier of a user preset added to a [`PresetLibrary`]
+pub type PresetId = usize;
+
 /// Error type for preset operations
 #[derive(Debug, Clone)]
 pub enum PresetError {
@@ -100,6 +109,8 @@ pub enum PresetError {
     NotFound(String),
     /// Failed to build patch from preset
     BuildError(String),
+    /// A preset with this name already exists
+    DuplicateName(String),
 }
 
 impl core::fmt::Display for PresetError {
@@ -107,6 +118,7 @@ impl core::fmt::Display for PresetError {
         match self {
             PresetError::NotFound(name) => write!(f, "Preset not found: {}", name),
             PresetError::BuildError(msg) => write!(f, "Failed to build preset: {}", msg),
+            PresetError::DuplicateName(name) => write!(f, "Preset already exists: {}", name),
         }
     }
 }
@@ -159,9 +171,12 @@ impl Preset {
 }
 
 /// Preset library containing all available presets
+///
+/// Built-in presets are always available; user presets captured from live
+/// patches with [`PresetLibrary::add_from_patch`] are stored per instance.
 #[derive(Debug, Clone, Default)]
 pub struct PresetLibrary {
-    _private: (),
+    user_presets: Vec<Preset>,
 }
 
 impl PresetLibrary {
@@ -175,7 +190,9 @@ impl PresetLibrary {
     /// }
     /// ```
     pub fn new() -> Self {
-        Self { _private: () }
+        Self {
+            user_presets: Vec::new(),
+        }
     }
 
     /// Get a preset by name, ready to build
@@ -188,11 +205,66 @@ impl PresetLibrary {
     /// }
     /// ```
     pub fn get(&self, name: &str) -> Option<Preset> {
+        if let Some(preset) = self.user_presets.iter().find(|p| p.info.name == name) {
+            return Some(preset.clone());
+        }
         let info = Self::all_presets().into_iter().find(|p| p.name == name)?;
         let def = Self::load(name)?;
         Some(Preset { info, def })
     }
 
+    /// Capture a patch as a new user preset
+    ///
+    /// The patch topology is stored as a [`PatchDef`] carrying the preset's
+    /// name, description, and tags. Names must be unique across built-in and
+    /// user presets.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut library = PresetLibrary::new();
+    /// let info = PresetInfo::new("My Lead", PresetCategory::Lead).with_tag("bright");
+    /// let id = library.add_from_patch(&patch, info)?;
+    /// ```
+    pub fn add_from_patch(
+        &mut self,
+        patch: &Patch,
+        info: PresetInfo,
+    ) -> Result<PresetId, PresetError> {
+        let exists = Self::all_presets().iter().any(|p| p.name == info.name)
+            || self.user_presets.iter().any(|p| p.info.name == info.name);
+        if exists {
+            return Err(PresetError::DuplicateName(info.name));
+        }
+
+        let mut def = patch.to_def(&info.name);
+        if !info.description.is_empty() {
+            def.description = Some(info.description.clone());
+        }
+        def.tags = info.tags.clone();
+
+        self.user_presets.push(Preset { info, def });
+        Ok(self.user_presets.len() - 1)
+    }
+
+    /// Build a user preset into a ready-to-use Patch
+    pub fn load_user(&self, id: PresetId, sample_rate: f64) -> Result<Patch, PresetError> {
+        let preset = self
+            .user_presets
+            .get(id)
+            .ok_or_else(|| PresetError::NotFound(format!("user preset #{}", id)))?;
+        preset.clone().build(sample_rate)
+    }
+
+    /// Get a user preset by id
+    pub fn get_user(&self, id: PresetId) -> Option<&Preset> {
+        self.user_presets.get(id)
+    }
+
+    /// All user presets in the order they were added
+    pub fn user_presets(&self) -> &[Preset] {
+        &self.user_presets
+    }
+
     /// Search presets by multiple tags (matches any)
     ///
     /// Returns presets that match ANY of the provided tags.
@@ -205,6 +277,7 @@ impl PresetLibrary {
     pub fn search_tags(&self, tags: &[&str]) -> Vec<PresetInfo> {
         Self::all_presets()
             .into_iter()
+            .chain(self.user_presets.iter().map(|p| p.info.clone()))
             .filter(|p| {
                 tags.iter().any(|search_tag| {
                     let search_lower = search_tag.to_lowercase();
@@ -1107,4 +1180,92 @@ mod tests {
         let err = PresetError::BuildError("failed".into());
         assert!(err.to_string().contains("failed"));
     }
+
+    #[test]
+    fn test_add_from_patch_round_trip() {
+        use crate::modules::{StereoOutput, Svf, Vco};
+
+        let mut patch = Patch::new(44100.0);
+        let vco = patch.add("vco", Vco::new(44100.0));
+        let vcf = patch.add("vcf", Svf::new(44100.0));
+        let output = patch.add("output", StereoOutput::new());
+        patch.connect(vco.out("saw"), vcf.in_("in")).unwrap();
+        patch
+            .connect_attenuated(vcf.out("lp"), output.in_("left"), 0.5)
+            .unwrap();
+        patch.set_output(output.id());
+        patch.compile().unwrap();
+
+        let mut library = PresetLibrary::new();
+        let info = PresetInfo::new("My Saw", PresetCategory::Lead)
+            .with_description("Filtered saw")
+            .with_tag("user");
+        let id = library.add_from_patch(&patch, info).unwrap();
+
+        let preset = library.get_user(id).unwrap();
+        assert_eq!(preset.def.name, "My Saw");
+        assert_eq!(preset.def.description.as_deref(), Some("Filtered saw"));
+        assert!(library
+            .search_tags(&["user"])
+            .iter()
+            .any(|p| p.name == "My Saw"));
+        assert!(library.get("My Saw").is_some());
+
+        let mut loaded = library.load_user(id, 48000.0).unwrap();
+        assert_eq!(loaded.sample_rate(), 48000.0);
+        assert_eq!(loaded.node_count(), patch.node_count());
+        assert_eq!(loaded.cable_count(), patch.cable_count());
+
+        // Same modules and cables (including attenuation) as the original
+        let original_def = patch.to_def("My Saw");
+        let loaded_def = loaded.to_def("My Saw");
+        let mut original_modules: Vec<_> = original_def
+            .modules
+            .iter()
+            .map(|m| (m.name.clone(), m.module_type.clone()))
+            .collect();
+        let mut loaded_modules: Vec<_> = loaded_def
+            .modules
+            .iter()
+            .map(|m| (m.name.clone(), m.module_type.clone()))
+            .collect();
+        original_modules.sort();
+        loaded_modules.sort();
+        assert_eq!(original_modules, loaded_modules);
+        let cable_key = |c: &CableDef| (c.from.clone(), c.to.clone(), c.attenuation, c.offset);
+        assert_eq!(
+            original_def
+                .cables
+                .iter()
+                .map(cable_key)
+                .collect::<Vec<_>>(),
+            loaded_def.cables.iter().map(cable_key).collect::<Vec<_>>()
+        );
+
+        assert!(loaded.tick().0.is_finite());
+    }
+
+    #[test]
+    fn test_add_from_patch_rejects_duplicate_names() {
+        let patch = Patch::new(44100.0);
+        let mut library = PresetLibrary::new();
+
+        let err = library
+            .add_from_patch(&patch, PresetInfo::new("Moog Bass", PresetCategory::Bass))
+            .unwrap_err();
+        assert!(matches!(err, PresetError::DuplicateName(_)));
+
+        library
+            .add_from_patch(&patch, PresetInfo::new("Mine", PresetCategory::Pad))
+            .unwrap();
+        assert!(library
+            .add_from_patch(&patch, PresetInfo::new("Mine", PresetCategory::Pad))
+            .is_err());
+        assert_eq!(library.user_presets().len(), 1);
+
+        assert!(matches!(
+            library.load_user(5, 44100.0),
+            Err(PresetError::NotFound(_))
+        ));
+    }
 }
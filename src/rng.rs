@@ -43,6 +43,12 @@ impl Rng {
         Self { s0, s1 }
     }
 
+    /// Current internal state, restorable with [`Rng::new`]
+    #[inline]
+    pub const fn state(&self) -> (u64, u64) {
+        (self.s0, self.s1)
+    }
+
     /// Create a new RNG from a single 64-bit seed.
     ///
     /// The seed is split into two state values using a mixing function.
@@ -201,6 +207,25 @@ pub fn random() -> f64 {
     }
 }
 
+/// Get a random u64 from the thread-local RNG, e.g. to seed an [`Rng`].
+#[inline]
+pub fn random_u64() -> u64 {
+    #[cfg(feature = "std")]
+    {
+        RNG_STATE.with(|cell| {
+            let mut rng = cell.get();
+            let value = rng.next_u64();
+            cell.set(rng);
+            value
+        })
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        // Safety: Single-threaded no_std contexts only
+        unsafe { RNG_STATE.next_u64() }
+    }
+}
+
 /// Get a random f64 in the range [-1.0, 1.0) from the thread-local RNG.
 #[inline]
 pub fn random_bipolar() -> f64 {
@@ -4,7 +4,7 @@
 //! including module registry and patch definitions.
 
 use crate::analog::{AnalogVco, Saturator, Wavefolder};
-use crate::graph::{NodeHandle, Patch, PatchError};
+use crate::graph::{FxChain, NodeHandle, Patch, PatchError};
 use crate::modules::*;
 use crate::port::{GraphModule, PortSpec};
 use crate::StdMap;
@@ -15,6 +15,8 @@ use alloc::vec;
 use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
+pub mod vcv;
+
 /// Serializable patch definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
@@ -104,6 +106,14 @@ pub struct ModuleDef {
 
     /// Module-specific state
     pub state: Option<serde_json::Value>,
+
+    /// Structural construction arguments (e.g. mixer channel count)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<serde_json::Value>,
+
+    /// Sync group the module restarts with (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_group: Option<u32>,
 }
 
 impl ModuleDef {
@@ -113,13 +123,25 @@ impl ModuleDef {
             module_type: module_type.into(),
             position: None,
             state: None,
+            args: None,
+            sync_group: None,
         }
     }
 
+    pub fn with_args(mut self, args: serde_json::Value) -> Self {
+        self.args = Some(args);
+        self
+    }
+
     pub fn with_position(mut self, x: f32, y: f32) -> Self {
         self.position = Some((x, y));
         self
     }
+
+    pub fn with_sync_group(mut self, group: u32) -> Self {
+        self.sync_group = Some(group);
+        self
+    }
 }
 
 /// Serializable cable definition
@@ -166,9 +188,58 @@ impl CableDef {
     }
 }
 
+/// Serializable effect chain definition
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct FxChainDef {
+    /// Effects in processing order
+    pub effects: Vec<FxSlotDef>,
+}
+
+impl FxChainDef {
+    /// Serialize to JSON string
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserialize from JSON string
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Serializable effect slot within an `FxChainDef`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct FxSlotDef {
+    /// Module type identifier
+    pub module_type: String,
+
+    /// Dry/wet amount (0-1)
+    pub amount: f64,
+
+    /// Whether the effect is active
+    pub enabled: bool,
+
+    /// Module-specific state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<serde_json::Value>,
+
+    /// Structural construction arguments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<serde_json::Value>,
+}
+
 /// Module factory function type
 pub type ModuleFactory = Box<dyn Fn(f64) -> Box<dyn GraphModule> + Send + Sync>;
 
+/// Module factory that also receives the module's construction arguments
+///
+/// Arguments are `Null` when none were stored; factories should fall back to
+/// their defaults for any missing field.
+pub type ModuleArgsFactory =
+    Box<dyn Fn(f64, &serde_json::Value) -> Box<dyn GraphModule> + Send + Sync>;
+
 /// Metadata about a registered module type
 #[derive(Debug, Clone)]
 pub struct ModuleMetadata {
@@ -266,7 +337,7 @@ pub struct CatalogResponse {
 
 /// Registry of available module types for instantiation
 pub struct ModuleRegistry {
-    factories: StdMap<String, ModuleFactory>,
+    factories: StdMap<String, ModuleArgsFactory>,
     metadata: StdMap<String, ModuleMetadata>,
 }
 
@@ -339,6 +410,24 @@ impl ModuleRegistry {
             |sr| Box::new(Lfo::new(sr)),
         );
 
+        self.register_factory_with_keywords(
+            "shape_lfo",
+            "Shape LFO",
+            "Modulation",
+            "LFO looping a custom breakpoint shape, with phase offset and sync",
+            &[
+                "lfo",
+                "modulation",
+                "shape",
+                "envelope",
+                "loop",
+                "phase",
+                "custom",
+            ],
+            &[],
+            |sr| Box::new(ShapeLfo::new(sr)),
+        );
+
         // =====================================================================
         // Filters
         // =====================================================================
@@ -393,6 +482,24 @@ impl ModuleRegistry {
             |sr| Box::new(Adsr::new(sr)),
         );
 
+        self.register_factory_with_keywords(
+            "moog_envelope",
+            "Moog Envelope",
+            "Envelopes",
+            "Minimoog-style RC contour with shared decay/release and sustain switch",
+            &[
+                "envelope",
+                "moog",
+                "contour",
+                "eg",
+                "vintage",
+                "exponential",
+                "decay",
+            ],
+            &[],
+            |sr| Box::new(MoogEnvelope::new(sr)),
+        );
+
         // =====================================================================
         // Amplifiers & VCAs
         // =====================================================================
@@ -409,34 +516,42 @@ impl ModuleRegistry {
         // =====================================================================
         // Mixers & Utilities
         // =====================================================================
-        self.register_factory_with_keywords(
+        self.register_factory_with_args(
             "mixer",
             "Mixer",
             "Utilities",
             "4-channel audio mixer",
             &["mix", "combine", "sum", "blend", "audio"],
             &["essential"],
-            |_| Box::new(Mixer::new(4)),
+            |_, args| {
+                Box::new(Mixer::new(
+                    arg_usize(args, "channels").unwrap_or(4).clamp(1, 64),
+                ))
+            },
         );
 
-        self.register_factory_with_keywords(
+        self.register_factory_with_args(
             "mixer8",
             "Mixer 8",
             "Utilities",
             "8-channel audio mixer for polyphony",
             &["mix", "combine", "sum", "blend", "audio", "poly", "voices"],
             &[],
-            |_| Box::new(Mixer::new(8)),
+            |_, args| {
+                Box::new(Mixer::new(
+                    arg_usize(args, "channels").unwrap_or(8).clamp(1, 64),
+                ))
+            },
         );
 
-        self.register_factory_with_keywords(
+        self.register_factory_with_args(
             "offset",
             "Offset",
             "Utilities",
             "DC offset / voltage source",
             &["dc", "voltage", "constant", "bias", "source"],
             &[],
-            |_| Box::new(Offset::new(0.0)),
+            |_, args| Box::new(Offset::new(arg_f64(args, "offset").unwrap_or(0.0))),
         );
 
         self.register_factory_with_keywords(
@@ -486,6 +601,24 @@ impl ModuleRegistry {
             |sr| Box::new(Flanger::new(sr)),
         );
 
+        self.register_factory_with_keywords(
+            "comb_filter",
+            "Comb Filter",
+            "Filters",
+            "Tuned feedforward/feedback comb filter with bipolar feedback",
+            &[
+                "comb",
+                "filter",
+                "resonance",
+                "metallic",
+                "flange",
+                "karplus",
+                "tuned",
+            ],
+            &[],
+            |sr| Box::new(CombFilter::new(sr)),
+        );
+
         self.register_factory_with_keywords(
             "phaser",
             "Phaser",
@@ -536,6 +669,24 @@ impl ModuleRegistry {
             |sr| Box::new(EnvelopeFollower::new(sr)),
         );
 
+        self.register_factory_with_args(
+            "band_energy",
+            "Band Energy",
+            "Utilities",
+            "Filterbank analyzer with a smoothed energy CV per band",
+            &[
+                "analyzer",
+                "spectrum",
+                "bands",
+                "energy",
+                "follower",
+                "visualizer",
+                "cv",
+            ],
+            &[],
+            |sr, args| Box::new(BandEnergy::new(sr, arg_usize(args, "bands").unwrap_or(8))),
+        );
+
         self.register_factory_with_keywords(
             "bitcrusher",
             "Bitcrusher",
@@ -546,6 +697,24 @@ impl ModuleRegistry {
             |_| Box::new(Bitcrusher::new()),
         );
 
+        self.register_factory_with_keywords(
+            "vintage_sampler",
+            "Vintage Sampler",
+            "Effects",
+            "12-bit sampler emulation with companding and reconstruction filters",
+            &[
+                "sampler",
+                "lofi",
+                "12-bit",
+                "vintage",
+                "mu-law",
+                "companding",
+                "bitcrusher",
+            ],
+            &[],
+            |sr| Box::new(VintageSampler::new(sr)),
+        );
+
         // P3 Effects
         self.register_factory_with_keywords(
             "tremolo",
@@ -567,6 +736,52 @@ impl ModuleRegistry {
             |sr| Box::new(Vibrato::new(sr)),
         );
 
+        self.register_factory_with_keywords(
+            "tape_wow_flutter",
+            "Tape Wow & Flutter",
+            "Effects",
+            "Tape transport instability with wow, flutter, and random drift",
+            &[
+                "tape", "wow", "flutter", "lofi", "vintage", "pitch", "drift",
+            ],
+            &[],
+            |sr| Box::new(TapeWowFlutter::new(sr)),
+        );
+        self.register_factory_with_keywords(
+            "space_echo",
+            "Space Echo",
+            "Effects",
+            "Stereo tape echo with filtered, saturated cross-feedback and wow",
+            &[
+                "delay",
+                "echo",
+                "tape",
+                "stereo",
+                "ping-pong",
+                "space",
+                "dub",
+            ],
+            &[],
+            |sr| Box::new(SpaceEcho::new(sr)),
+        );
+        self.register_factory_with_keywords(
+            "feedback_shifter",
+            "Feedback Shifter",
+            "Effects",
+            "Delay with a pitch shifter in the feedback loop for rising or falling echo trails",
+            &[
+                "delay",
+                "echo",
+                "pitch",
+                "shift",
+                "shepard",
+                "risset",
+                "glissando",
+            ],
+            &[],
+            |sr| Box::new(FeedbackShifter::new(sr)),
+        );
+
         self.register_factory_with_keywords(
             "distortion",
             "Distortion",
@@ -577,13 +792,49 @@ impl ModuleRegistry {
             |sr| Box::new(Distortion::new(sr)),
         );
 
+        self.register_factory_with_keywords(
+            "table_waveshaper",
+            "Table Waveshaper",
+            "Effects",
+            "Waveshaper with a user-loadable, interpolated transfer-function table",
+            &[
+                "waveshaper",
+                "transfer",
+                "table",
+                "distortion",
+                "curve",
+                "shaping",
+            ],
+            &["advanced"],
+            |_| Box::new(TableWaveshaper::new()),
+        );
+
+        self.register_factory_with_keywords(
+            "feedback_mixer",
+            "Feedback Mixer",
+            "Effects",
+            "No-input mixer style feedback network that self-oscillates",
+            &[
+                "feedback",
+                "no-input",
+                "drone",
+                "noise",
+                "self-oscillate",
+                "experimental",
+            ],
+            &[],
+            |sr| Box::new(FeedbackMixer::new(sr)),
+        );
+
         // P3 Oscillators
         self.register_factory_with_keywords(
             "supersaw",
             "Supersaw",
             "Oscillators",
-            "JP-8000 style 7-voice detuned supersaw oscillator",
-            &["supersaw", "trance", "unison", "detune", "thick"],
+            "JP-8000 style detuned supersaw with 3/5/7 voices and stereo spread",
+            &[
+                "supersaw", "trance", "unison", "detune", "thick", "stereo", "wide",
+            ],
             &[],
             |sr| Box::new(Supersaw::new(sr)),
         );
@@ -598,6 +849,76 @@ impl ModuleRegistry {
             |sr| Box::new(KarplusStrong::new(sr)),
         );
 
+        self.register_factory_with_keywords(
+            "waveguide",
+            "Waveguide Wind",
+            "Oscillators",
+            "Breath-driven flute/clarinet bore model with jet and reed excitation",
+            &[
+                "waveguide",
+                "flute",
+                "clarinet",
+                "wind",
+                "breath",
+                "physical",
+                "modeling",
+            ],
+            &[],
+            |sr| Box::new(Waveguide::new(sr)),
+        );
+
+        self.register_factory_with_keywords(
+            "phase_distortion_osc",
+            "Phase Distortion Oscillator",
+            "Oscillators",
+            "Casio CZ-style phase distortion oscillator with resonant waveform",
+            &[
+                "phase",
+                "distortion",
+                "cz",
+                "casio",
+                "digital",
+                "resonant",
+                "pd",
+            ],
+            &[],
+            |sr| Box::new(PhaseDistortionOsc::new(sr)),
+        );
+
+        self.register_factory_with_keywords(
+            "wave_terrain",
+            "Wave Terrain",
+            "Oscillators",
+            "2D wave terrain oscillator scanning a surface with two phasors",
+            &[
+                "terrain",
+                "2d",
+                "scanning",
+                "experimental",
+                "digital",
+                "surface",
+            ],
+            &[],
+            |sr| Box::new(WaveTerrain::new(sr)),
+        );
+
+        self.register_factory_with_keywords(
+            "modal_resonator",
+            "Modal Resonator",
+            "Oscillators",
+            "Bank of tuned decaying resonators for bells, bars, and drums",
+            &[
+                "modal",
+                "resonator",
+                "bell",
+                "percussion",
+                "physical",
+                "drum",
+            ],
+            &["advanced"],
+            |sr| Box::new(ModalResonator::new(sr)),
+        );
+
         // P3 Utilities
         self.register_factory_with_keywords(
             "scale_quantizer",
@@ -629,6 +950,24 @@ impl ModuleRegistry {
             |_| Box::new(Attenuverter::new()),
         );
 
+        self.register_factory_with_keywords(
+            "response_curve",
+            "Response Curve",
+            "Utilities",
+            "Shape a CV with linear, exponential, logarithmic, S or gamma curves",
+            &[
+                "curve",
+                "velocity",
+                "response",
+                "exponential",
+                "logarithmic",
+                "gamma",
+                "taper",
+            ],
+            &[],
+            |_| Box::new(ResponseCurve::new()),
+        );
+
         self.register_factory_with_keywords(
             "multiple",
             "Multiple",
@@ -649,6 +988,43 @@ impl ModuleRegistry {
             |_| Box::new(Crossfader::new()),
         );
 
+        self.register_factory_with_keywords(
+            "panner",
+            "Panner",
+            "Utilities",
+            "Mono or stereo panner with selectable pan laws and width",
+            &[
+                "pan",
+                "stereo",
+                "balance",
+                "width",
+                "pan law",
+                "constant power",
+            ],
+            &[],
+            |_| Box::new(Panner::new()),
+        );
+
+        self.register_factory_with_keywords(
+            "vector_mix",
+            "Vector Mixer",
+            "Utilities",
+            "Four-source joystick mixer with equal-power X/Y blending",
+            &["vector", "joystick", "mix", "blend", "xy", "wavestation"],
+            &[],
+            |_| Box::new(VectorMix::new()),
+        );
+
+        self.register_factory_with_args(
+            "layer_blend",
+            "Layer Blend",
+            "Utilities",
+            "Equal-power crossfade through gated audio layers with one CV",
+            &["layer", "blend", "crossfade", "morph", "velocity", "mix"],
+            &[],
+            |sr, args| Box::new(LayerBlend::new(sr, arg_usize(args, "layers").unwrap_or(3))),
+        );
+
         self.register_factory_with_keywords(
             "precision_adder",
             "Precision Adder",
@@ -699,6 +1075,37 @@ impl ModuleRegistry {
             |_| Box::new(SampleAndHold::new()),
         );
 
+        self.register_factory_with_keywords(
+            "random_source",
+            "Random Source",
+            "Modulation",
+            "Stepped, smooth, and drunk-walk random voltages from one seeded source",
+            &[
+                "random", "sample", "hold", "smooth", "drunk", "walk", "stepped", "chaos",
+            ],
+            &[],
+            |sr| Box::new(RandomSource::new(sr)),
+        );
+
+        self.register_factory_with_args(
+            "sample_hold_bank",
+            "Sample & Hold Bank",
+            "Utilities",
+            "Several sample-and-hold channels on one trigger, optionally staggered",
+            &[
+                "sample", "hold", "bank", "chord", "poly", "capture", "stagger",
+            ],
+            &[],
+            |sr, args| {
+                let channels = arg_usize(args, "channels").unwrap_or(4);
+                let shared = args
+                    .get("shared")
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or(false);
+                Box::new(SampleHoldBank::new(sr, channels).with_shared_input(shared))
+            },
+        );
+
         self.register_factory_with_keywords(
             "slew_limiter",
             "Slew Limiter",
@@ -709,6 +1116,44 @@ impl ModuleRegistry {
             |sr| Box::new(SlewLimiter::new(sr)),
         );
 
+        self.register_factory_with_keywords(
+            "vc_slew",
+            "VC Slew",
+            "Utilities",
+            "Shaped CV-controlled slew with slewing gate and end trigger",
+            &[
+                "slew",
+                "portamento",
+                "glide",
+                "lag",
+                "function",
+                "envelope",
+                "exponential",
+            ],
+            &[],
+            |sr| Box::new(VcSlew::new(sr)),
+        );
+
+        self.register_factory_with_keywords(
+            "declick",
+            "De-Click",
+            "Utilities",
+            "Lookahead smoother that fades across amplitude discontinuities",
+            &["declick", "click", "pop", "smooth", "discontinuity", "fade"],
+            &[],
+            |sr| Box::new(DeClick::new(sr)),
+        );
+
+        self.register_factory_with_keywords(
+            "dc_blocker",
+            "DC Blocker",
+            "Utilities",
+            "One-pole highpass that removes DC offset",
+            &["dc", "offset", "highpass", "blocker", "headroom"],
+            &[],
+            |sr| Box::new(DcBlocker::new(sr)),
+        );
+
         self.register_factory_with_keywords(
             "quantizer",
             "Quantizer",
@@ -719,30 +1164,113 @@ impl ModuleRegistry {
             |_| Box::new(Quantizer::new(Scale::Chromatic)),
         );
 
+        self.register_factory_with_keywords(
+            "smart_quantizer",
+            "Smart Quantizer",
+            "Utilities",
+            "Scale quantizer with glide, hysteresis, and a note-change trigger",
+            &[
+                "quantize",
+                "scale",
+                "glide",
+                "portamento",
+                "slew",
+                "trigger",
+                "hysteresis",
+            ],
+            &[],
+            |sr| Box::new(SmartQuantizer::new(sr)),
+        );
+
+        self.register_factory_with_keywords(
+            "weighted_quantizer",
+            "Weighted Quantizer",
+            "Utilities",
+            "Generative quantizer picking scale degrees by weight and proximity",
+            &[
+                "quantize",
+                "scale",
+                "random",
+                "probability",
+                "weight",
+                "generative",
+            ],
+            &[],
+            |_| Box::new(WeightedQuantizer::new(Scale::Major)),
+        );
+
         // =====================================================================
         // Sources
         // =====================================================================
-        self.register_factory_with_keywords(
+        self.register_factory_with_args(
             "noise",
             "Noise",
             "Sources",
             "White and pink noise generator",
             &["noise", "white", "pink", "random", "hiss"],
             &["essential"],
-            |_| Box::new(NoiseGenerator::new()),
+            |_, args| match arg_f64(args, "correlation") {
+                Some(correlation) => Box::new(NoiseGenerator::with_correlation(correlation)),
+                None => Box::new(NoiseGenerator::new()),
+            },
+        );
+
+        self.register_factory_with_keywords(
+            "sample_player",
+            "Sample Player",
+            "Sources",
+            "Triggered playback of a recorded sample with speed, reverse and loop",
+            &[
+                "sample", "sampler", "playback", "wav", "drum", "one-shot", "loop",
+            ],
+            &[],
+            |sr| Box::new(SamplePlayer::new(sr)),
         );
 
         // =====================================================================
         // Sequencing
         // =====================================================================
-        self.register_factory_with_keywords(
+        self.register_factory_with_args(
             "step_sequencer",
             "Step Sequencer",
             "Sequencing",
-            "8-step CV/gate sequencer",
-            &["sequencer", "step", "pattern", "melody", "cv", "gate"],
+            "2-64 step CV/gate sequencer with per-step ratchets",
+            &[
+                "sequencer",
+                "step",
+                "pattern",
+                "melody",
+                "cv",
+                "gate",
+                "ratchet",
+            ],
             &["essential"],
-            |_| Box::new(StepSequencer::new()),
+            |_, args| Box::new(StepSequencer::new(arg_usize(args, "length").unwrap_or(8))),
+        );
+
+        self.register_factory_with_args(
+            "gate_length_sequencer",
+            "Gate Length Sequencer",
+            "Sequencing",
+            "8-step CV sequencer with per-step gate length for staccato and legato",
+            &[
+                "sequencer",
+                "gate",
+                "length",
+                "legato",
+                "staccato",
+                "tie",
+                "step",
+            ],
+            &[],
+            // Steps are saved as state; older patches carry them as args
+            |_, args| {
+                let steps: Vec<(f64, f64)> = args
+                    .get("steps")
+                    .and_then(|s| serde_json::from_value(s.clone()).ok())
+                    .unwrap_or_default();
+                Box::new(GateLengthSequencer::new().with_steps(&steps))
+            },
         );
 
         self.register_factory_with_keywords(
@@ -755,6 +1283,60 @@ impl ModuleRegistry {
             |sr| Box::new(Clock::new(sr)),
         );
 
+        self.register_factory_with_args(
+            "clock_groove",
+            "Clock Groove",
+            "Sequencing",
+            "Applies a repeating timing/accent groove template to a clock",
+            &[
+                "groove", "swing", "shuffle", "humanize", "mpc", "clock", "timing",
+            ],
+            &[],
+            |_, args| {
+                let groove: Vec<(f64, f64)> = args
+                    .get("groove")
+                    .and_then(|g| serde_json::from_value(g.clone()).ok())
+                    .unwrap_or_default();
+                Box::new(ClockGroove::new().with_groove(&groove))
+            },
+        );
+
+        self.register_factory_with_keywords(
+            "clock_ratchet",
+            "Clock Ratchet",
+            "Sequencing",
+            "Bursts each clock pulse into 1-8 ratchets with density and randomness CV",
+            &[
+                "ratchet",
+                "clock",
+                "burst",
+                "roll",
+                "generative",
+                "rhythm",
+                "random",
+            ],
+            &[],
+            |_| Box::new(ClockRatchet::new()),
+        );
+
+        self.register_factory_with_keywords(
+            "note_repeat",
+            "Note Repeat",
+            "Sequencing",
+            "Clocked note repeat/roll while a gate is held, with velocity decay",
+            &[
+                "repeat",
+                "roll",
+                "ratchet",
+                "finger",
+                "drum",
+                "performance",
+                "stutter",
+            ],
+            &[],
+            |sr| Box::new(NoteRepeat::new(sr)),
+        );
+
         // =====================================================================
         // I/O
         // =====================================================================
@@ -768,6 +1350,43 @@ impl ModuleRegistry {
             |_| Box::new(StereoOutput::new()),
         );
 
+        self.register_factory_with_keywords(
+            "smart_widener",
+            "Smart Widener",
+            "Effects",
+            "Haas stereo widener that keeps the bass mono below a crossover",
+            &[
+                "stereo",
+                "width",
+                "widener",
+                "haas",
+                "mono",
+                "bass",
+                "crossover",
+            ],
+            &[],
+            |sr| Box::new(SmartWidener::new(sr)),
+        );
+
+        self.register_factory_with_keywords(
+            "multiband_imager",
+            "Multiband Imager",
+            "Effects",
+            "Three-band stereo imager with independent width per band",
+            &[
+                "stereo",
+                "width",
+                "imager",
+                "multiband",
+                "mono",
+                "bass",
+                "crossover",
+                "mastering",
+            ],
+            &[],
+            |sr| Box::new(MultibandImager::new(sr)),
+        );
+
         // =====================================================================
         // Effects
         // =====================================================================
@@ -861,6 +1480,30 @@ impl ModuleRegistry {
             |_| Box::new(LogicNot::new()),
         );
 
+        self.register_factory_with_keywords(
+            "trigger_util",
+            "Trigger Utility",
+            "Logic",
+            "S-trig/V-trig conversion and one-shot trigger latch",
+            &[
+                "s-trig", "v-trig", "trigger", "latch", "once", "invert", "moog",
+            ],
+            &[],
+            |_| Box::new(TriggerUtil::new()),
+        );
+
+        self.register_factory_with_keywords(
+            "trigger_merge",
+            "Trigger Merge",
+            "Logic",
+            "Merges four trigger sources into one with debounce and a coincidence count",
+            &[
+                "trigger", "merge", "or", "combine", "clock", "debounce", "rhythm",
+            ],
+            &[],
+            |sr| Box::new(TriggerMerge::new(sr)),
+        );
+
         self.register_factory_with_keywords(
             "comparator",
             "Comparator",
@@ -891,6 +1534,40 @@ impl ModuleRegistry {
             |_| Box::new(BernoulliGate::new()),
         );
 
+        self.register_factory_with_keywords(
+            "drum_logic",
+            "Drum Logic",
+            "Random",
+            "Per-hit play/skip/ghost substitution for generative drums",
+            &[
+                "drum",
+                "probability",
+                "ghost",
+                "generative",
+                "variation",
+                "trigger",
+            ],
+            &[],
+            |_| Box::new(DrumLogic::new()),
+        );
+
+        self.register_factory_with_keywords(
+            "gate_dropout",
+            "Gate Dropout",
+            "Random",
+            "Randomly drops gates, or inserts extra ones on rests",
+            &[
+                "dropout",
+                "probability",
+                "gate",
+                "generative",
+                "variation",
+                "random",
+            ],
+            &[],
+            |_| Box::new(GateDropout::new()),
+        );
+
         // =====================================================================
         // Analog Modeling
         // =====================================================================
@@ -960,6 +1637,50 @@ impl ModuleRegistry {
             |sr| Box::new(ParametricEq::new(sr)),
         );
 
+        self.register_factory_with_keywords(
+            "a_weight",
+            "A-Weighting",
+            "Utilities",
+            "IEC 61672 A-weighting filter for perceptual loudness metering",
+            &[
+                "weighting",
+                "loudness",
+                "meter",
+                "a-weight",
+                "psychoacoustic",
+                "filter",
+            ],
+            &[],
+            |sr| Box::new(AWeight::new(sr)),
+        );
+
+        self.register_factory_with_keywords(
+            "c_weight",
+            "C-Weighting",
+            "Utilities",
+            "IEC 61672 C-weighting filter for peak and high-level metering",
+            &["weighting", "loudness", "meter", "c-weight", "filter"],
+            &[],
+            |sr| Box::new(CWeight::new(sr)),
+        );
+
+        self.register_factory_with_keywords(
+            "loudness_comp",
+            "Loudness Compensation",
+            "Utilities",
+            "Loudness button: lifts lows and highs for low-volume monitoring",
+            &[
+                "loudness",
+                "monitor",
+                "equal-loudness",
+                "shelf",
+                "eq",
+                "bass",
+            ],
+            &[],
+            |sr| Box::new(LoudnessComp::new(sr)),
+        );
+
         self.register_factory_with_keywords(
             "vocoder",
             "Vocoder",
@@ -970,6 +1691,42 @@ impl ModuleRegistry {
             |sr| Box::new(Vocoder::new(sr)),
         );
 
+        self.register_factory_with_keywords(
+            "spectral_morph",
+            "Spectral Morph",
+            "Effects",
+            "STFT cross-synthesis morphing between two inputs",
+            &[
+                "spectral",
+                "morph",
+                "fft",
+                "cross",
+                "synthesis",
+                "blend",
+                "timbre",
+            ],
+            &[],
+            |sr| Box::new(SpectralMorph::new(sr)),
+        );
+
+        self.register_factory_with_keywords(
+            "convolution",
+            "Convolution",
+            "Effects",
+            "Impulse response convolution for rooms and cabinets",
+            &[
+                "convolution",
+                "impulse",
+                "response",
+                "ir",
+                "reverb",
+                "cabinet",
+                "room",
+            ],
+            &[],
+            |sr| Box::new(Convolution::new(sr)),
+        );
+
         self.register_factory_with_keywords(
             "pitch_shifter",
             "Pitch Shifter",
@@ -1056,9 +1813,38 @@ impl ModuleRegistry {
         factory: F,
     ) where
         F: Fn(f64) -> Box<dyn GraphModule> + Send + Sync + 'static,
+    {
+        self.register_factory_with_args(
+            type_id,
+            name,
+            category,
+            description,
+            keywords,
+            tags,
+            move |sample_rate, _| factory(sample_rate),
+        );
+    }
+
+    /// Register a module factory that receives construction arguments
+    ///
+    /// Use this for modules whose structure depends on constructor arguments
+    /// (see [`GraphModule::construction_args`]). The metadata port spec is
+    /// taken from an instance built with `Null` arguments.
+    #[allow(clippy::too_many_arguments)]
+    pub fn register_factory_with_args<F>(
+        &mut self,
+        type_id: &str,
+        name: &str,
+        category: &str,
+        description: &str,
+        keywords: &[&str],
+        tags: &[&str],
+        factory: F,
+    ) where
+        F: Fn(f64, &serde_json::Value) -> Box<dyn GraphModule> + Send + Sync + 'static,
     {
         // Get port spec from a temporary instance
-        let temp_instance = factory(44100.0);
+        let temp_instance = factory(44100.0, &serde_json::Value::Null);
         let port_spec = temp_instance.port_spec().clone();
 
         self.factories
@@ -1080,7 +1866,19 @@ impl ModuleRegistry {
 
     /// Instantiate a module by type ID
     pub fn instantiate(&self, type_id: &str, sample_rate: f64) -> Option<Box<dyn GraphModule>> {
-        self.factories.get(type_id).map(|f| f(sample_rate))
+        self.instantiate_with_args(type_id, sample_rate, &serde_json::Value::Null)
+    }
+
+    /// Instantiate a module by type ID with construction arguments
+    ///
+    /// Missing or malformed arguments fall back to the factory defaults.
+    pub fn instantiate_with_args(
+        &self,
+        type_id: &str,
+        sample_rate: f64,
+        args: &serde_json::Value,
+    ) -> Option<Box<dyn GraphModule>> {
+        self.factories.get(type_id).map(|f| f(sample_rate, args))
     }
 
     /// List all registered module types
@@ -1228,6 +2026,8 @@ impl Patch {
                 module_type: module.type_id().to_string(),
                 position: self.get_position(node_id),
                 state: module.serialize_state(),
+                args: Some(module.construction_args()).filter(|args| !args.is_null()),
+                sync_group: self.sync_group(node_id),
             })
             .collect();
 
@@ -1289,14 +2089,20 @@ impl Patch {
 
         // Instantiate modules
         for module_def in &def.modules {
-            let module = registry
-                .instantiate(&module_def.module_type, sample_rate)
+            let args = module_def.args.as_ref().unwrap_or(&serde_json::Value::Null);
+            let mut module = registry
+                .instantiate_with_args(&module_def.module_type, sample_rate, args)
                 .ok_or_else(|| {
                     PatchError::CompilationFailed(format!(
                         "Unknown module type: {}",
                         module_def.module_type
                     ))
                 })?;
+            if let Some(state) = &module_def.state {
+                module
+                    .deserialize_state(state)
+                    .map_err(PatchError::CompilationFailed)?;
+            }
 
             let handle = patch.add_boxed(&module_def.name, module);
 
@@ -1304,6 +2110,7 @@ impl Patch {
             if let Some((x, y)) = module_def.position {
                 patch.set_position(handle.id(), (x, y));
             }
+            patch.set_sync_group(handle.id(), module_def.sync_group);
 
             name_to_handle.insert(module_def.name.clone(), handle);
         }
@@ -1368,6 +2175,66 @@ impl Patch {
     }
 }
 
+impl FxChain {
+    /// Convert the chain to a serializable definition
+    pub fn to_def(&self) -> FxChainDef {
+        let effects = (0..self.len())
+            .filter_map(|i| {
+                let module = self.effect(i)?;
+                Some(FxSlotDef {
+                    module_type: module.type_id().to_string(),
+                    amount: self.amount(i)?,
+                    enabled: self.is_enabled(i)?,
+                    state: module.serialize_state(),
+                    args: Some(module.construction_args()).filter(|args| !args.is_null()),
+                })
+            })
+            .collect();
+        FxChainDef { effects }
+    }
+
+    /// Build a chain from a definition
+    pub fn from_def(
+        def: &FxChainDef,
+        registry: &ModuleRegistry,
+        sample_rate: f64,
+    ) -> Result<Self, PatchError> {
+        let mut chain = FxChain::new(sample_rate);
+        for (index, slot_def) in def.effects.iter().enumerate() {
+            let args = slot_def.args.as_ref().unwrap_or(&serde_json::Value::Null);
+            let mut module = registry
+                .instantiate_with_args(&slot_def.module_type, sample_rate, args)
+                .ok_or_else(|| {
+                    PatchError::CompilationFailed(format!(
+                        "Unknown module type: {}",
+                        slot_def.module_type
+                    ))
+                })?;
+            if let Some(state) = &slot_def.state {
+                module
+                    .deserialize_state(state)
+                    .map_err(PatchError::CompilationFailed)?;
+            }
+
+            chain.push(module);
+            chain.set_amount(index, slot_def.amount)?;
+            chain.set_enabled(index, slot_def.enabled)?;
+        }
+        chain.reset();
+        Ok(chain)
+    }
+}
+
+fn arg_f64(args: &serde_json::Value, key: &str) -> Option<f64> {
+    args.get(key).and_then(serde_json::Value::as_f64)
+}
+
+fn arg_usize(args: &serde_json::Value, key: &str) -> Option<usize> {
+    args.get(key)
+        .and_then(serde_json::Value::as_u64)
+        .map(|n| n as usize)
+}
+
 fn parse_port_ref(s: &str) -> Result<(&str, &str), PatchError> {
     let parts: Vec<&str> = s.splitn(2, '.').collect();
     if parts.len() != 2 {
@@ -1878,6 +2745,25 @@ mod tests {
         assert!(catalog.modules.iter().any(|m| m.type_id == "adsr"));
     }
 
+    #[test]
+    fn test_registered_modules_support_runtime_state() {
+        use crate::port::{RuntimeReader, RuntimeWriter};
+
+        let registry = ModuleRegistry::new();
+        for meta in registry.list_modules() {
+            let module = registry.instantiate(&meta.type_id, 44100.0).unwrap();
+            assert!(module.supports_runtime_state(), "{}", meta.type_id);
+
+            let mut state = RuntimeWriter::new();
+            module.serialize_runtime(&mut state);
+            let state = state.into_bytes();
+            let mut fresh = registry.instantiate(&meta.type_id, 44100.0).unwrap();
+            let mut reader = RuntimeReader::new(&state);
+            fresh.deserialize_runtime(&mut reader).unwrap();
+            reader.finish().unwrap();
+        }
+    }
+
     #[test]
     fn test_catalog_categories() {
         let registry = ModuleRegistry::new();
@@ -2032,4 +2918,260 @@ mod tests {
         // VCO should have "essential" tag
         assert!(metadata.tags.contains(&"essential".to_string()));
     }
+
+    #[test]
+    fn test_construction_args_round_trip_mixer() {
+        let mut patch = Patch::new(44100.0);
+        patch.add("mix", Mixer::new(6));
+        patch.add("bias", Offset::new(2.5));
+
+        let json = patch.to_def("Args").to_json().unwrap();
+        assert!(json.contains("\"channels\""));
+
+        let def = PatchDef::from_json(&json).unwrap();
+        let registry = ModuleRegistry::new();
+        let loaded = Patch::from_def(&def, &registry, 44100.0).unwrap();
+
+        let (_, _, mixer) = loaded.nodes().find(|(_, name, _)| *name == "mix").unwrap();
+        assert_eq!(mixer.port_spec().inputs.len(), 6);
+        let (_, _, bias) = loaded.nodes().find(|(_, name, _)| *name == "bias").unwrap();
+        assert_eq!(bias.get_param(0), Some(2.5));
+    }
+
+    #[test]
+    fn test_construction_args_missing_defaults() {
+        let registry = ModuleRegistry::new();
+
+        // Defs written before construction args existed have no "args" field
+        let json = r#"{"name":"m","module_type":"mixer","position":null,"state":null}"#;
+        let def: ModuleDef = serde_json::from_str(json).unwrap();
+        assert!(def.args.is_none());
+
+        let mixer = registry.instantiate("mixer", 44100.0).unwrap();
+        assert_eq!(mixer.port_spec().inputs.len(), 4);
+
+        // Unrelated or malformed fields fall back to the defaults
+        let args = serde_json::json!({ "channels": "six", "other": 1 });
+        let mixer = registry
+            .instantiate_with_args("mixer8", 44100.0, &args)
+            .unwrap();
+        assert_eq!(mixer.port_spec().inputs.len(), 8);
+
+        // Modules without structural arguments don't store any
+        let mut patch = Patch::new(44100.0);
+        patch.add("vco", Vco::new(44100.0));
+        assert!(patch.to_def("Plain").modules[0].args.is_none());
+    }
+
+    #[test]
+    fn test_construction_args_round_trip_clock_groove() {
+        let mut patch = Patch::new(44100.0);
+        patch.add(
+            "groove",
+            ClockGroove::new().with_groove(&[(0.0, 1.0), (0.2, 1.5), (-0.1, 0.8)]),
+        );
+
+        let def = patch.to_def("Groove");
+        let args = def.modules[0].args.clone().unwrap();
+        let registry = ModuleRegistry::new();
+        let module = registry
+            .instantiate_with_args("clock_groove", 44100.0, &args)
+            .unwrap();
+        assert_eq!(module.construction_args(), args);
+    }
+
+    #[test]
+    fn test_programmed_sequencer_and_quantizer_survive_round_trip() {
+        let mut patch = Patch::new(44100.0);
+        let mut seq = StepSequencer::new(16);
+        for index in 0..16 {
+            seq.set_step(index, index as f64 / 12.0 - 0.5, index % 3 != 0);
+            seq.set_ratchet(index, 1 + index % 4);
+        }
+        let seq = patch.add("seq", seq);
+        let mut weighted = WeightedQuantizer::new(Scale::Minor);
+        weighted.set_weight(4, 3.5);
+        patch.add("weighted", weighted);
+        let mut quant = Quantizer::new(Scale::Chromatic);
+        quant.set_scale(Scale::Dorian);
+        let quant = patch.add("quant", quant);
+        patch.connect(seq.out("cv"), quant.in_("in")).unwrap();
+        patch.set_output(quant.id());
+
+        let json = patch.to_def("Programmed").to_json().unwrap();
+        let def = PatchDef::from_json(&json).unwrap();
+        let loaded = Patch::from_def(&def, &ModuleRegistry::new(), 44100.0).unwrap();
+
+        let module = |name: &str| {
+            loaded
+                .nodes()
+                .find(|(_, node, _)| *node == name)
+                .map(|(_, _, module)| module)
+                .unwrap()
+        };
+        let state = module("seq").serialize_state().unwrap();
+        let steps: Vec<f64> = serde_json::from_value(state["steps"].clone()).unwrap();
+        let gates: Vec<bool> = serde_json::from_value(state["gates"].clone()).unwrap();
+        let ratchets: Vec<usize> = serde_json::from_value(state["ratchets"].clone()).unwrap();
+        for index in 0..16 {
+            assert_eq!(steps[index], index as f64 / 12.0 - 0.5);
+            assert_eq!(gates[index], index % 3 != 0);
+            assert_eq!(ratchets[index], 1 + index % 4);
+        }
+        assert_eq!(
+            module("quant").serialize_state(),
+            Some(serde_json::json!({ "scale": Scale::Dorian.index() }))
+        );
+        let weighted = module("weighted").serialize_state().unwrap();
+        assert_eq!(weighted["scale"], Scale::Minor.index());
+        assert_eq!(weighted["weights"][4], 3.5);
+
+        // State that does not fit the module is rejected
+        let mut bad = def.clone();
+        bad.modules[0].state = Some(serde_json::json!({ "steps": [1.0] }));
+        assert!(Patch::from_def(&bad, &ModuleRegistry::new(), 44100.0).is_err());
+    }
+
+    #[test]
+    fn test_gate_length_sequencer_survives_round_trip() {
+        let mut patch = Patch::new(44100.0);
+        let mut seq = GateLengthSequencer::new();
+        for index in 0..8 {
+            seq.set_step(index, index as f64 / 12.0, 0.25 * index as f64);
+        }
+        seq.set_gate_length(3, 1.5);
+        let seq = patch.add("seq", seq);
+        patch.set_output(seq.id());
+
+        let def = patch.to_def("Lengths");
+        let json = def.to_json().unwrap();
+        let loaded = Patch::from_def(
+            &PatchDef::from_json(&json).unwrap(),
+            &ModuleRegistry::new(),
+            44100.0,
+        )
+        .unwrap();
+        let (_, _, module) = loaded.nodes().next().unwrap();
+        assert_eq!(module.serialize_state(), def.modules[0].state);
+
+        let steps: Vec<(f64, f64)> =
+            serde_json::from_value(module.serialize_state().unwrap()["steps"].clone()).unwrap();
+        for (index, &(voltage, length)) in steps.iter().enumerate() {
+            assert_eq!(voltage, index as f64 / 12.0);
+            let expected = if index == 3 { 1.5 } else { 0.25 * index as f64 };
+            assert_eq!(length, expected);
+        }
+
+        // A saved sequence of the wrong length is rejected
+        let mut bad = def.clone();
+        bad.modules[0].state = Some(serde_json::json!({ "steps": [[0.0, 0.5]] }));
+        assert!(Patch::from_def(&bad, &ModuleRegistry::new(), 44100.0).is_err());
+    }
+
+    #[test]
+    fn test_panel_knobs_survive_round_trip() {
+        let mut patch = Patch::new(44100.0);
+        patch.add("vco", Vco::new(44100.0));
+        patch.add("lfo", Lfo::new(44100.0));
+        patch.add("svf", Svf::new(44100.0));
+        patch.add("adsr", Adsr::new(44100.0));
+        patch.add("delay", DelayLine::new(44100.0));
+        patch.add("reverb", Reverb::new(44100.0));
+        patch.add("vca", Vca::new());
+        patch.add("untouched", Vco::new(44100.0));
+
+        // Turn every knob to 30% of its range, off its default
+        let mut turned = Vec::new();
+        for (id, name, module) in patch.nodes() {
+            if name == "untouched" {
+                continue;
+            }
+            for param in module.params() {
+                turned.push((id, name.to_string(), param.id, param.range.apply(0.3)));
+            }
+        }
+        assert!(turned.len() >= 15);
+        for (id, _, param, value) in &turned {
+            patch.set_param(*id, *param, *value);
+        }
+
+        let json = patch.to_def("Knobs").to_json().unwrap();
+        let def = PatchDef::from_json(&json).unwrap();
+        assert!(def
+            .modules
+            .iter()
+            .all(|m| (m.name == "untouched") == m.state.is_none()));
+
+        let loaded = Patch::from_def(&def, &ModuleRegistry::new(), 44100.0).unwrap();
+        for (_, name, param, value) in &turned {
+            let node = loaded.get_node_id_by_name(name).unwrap();
+            let restored = loaded.get_param(node, *param).unwrap();
+            assert!(
+                (restored - value).abs() <= value.abs() * 1e-9,
+                "{name} param {param}: {restored} != {value}"
+            );
+        }
+
+        // Unknown knobs are rejected
+        let mut bad = def.clone();
+        bad.modules[0].state = Some(serde_json::json!({ "knobs": { "warp": 1.0 } }));
+        assert!(Patch::from_def(&bad, &ModuleRegistry::new(), 44100.0).is_err());
+    }
+
+    #[test]
+    fn test_frozen_random_source_survives_round_trip() {
+        let mut patch = Patch::new(1000.0);
+        let lfo = patch.add("lfo", Lfo::new(1000.0));
+        let random = patch.add("random", RandomSource::new(1000.0));
+        patch.add("vco", Vco::new(1000.0));
+        patch.connect(lfo.out("sqr"), random.in_("clock")).unwrap();
+        patch.set_output(random.id());
+        patch.compile().unwrap();
+        for _ in 0..10_000 {
+            patch.tick();
+        }
+
+        // Only the random source has anything to freeze
+        assert_eq!(patch.freeze_to_params(), 1);
+        let def = patch.to_def("Frozen");
+        let state = def.modules.iter().find(|m| m.name == "random").unwrap();
+        assert!(state.state.as_ref().unwrap().get("frozen").is_some());
+        assert!(def
+            .modules
+            .iter()
+            .all(|m| m.name == "random" || m.state.is_none()));
+
+        let loaded = Patch::from_def(&def, &ModuleRegistry::new(), 1000.0).unwrap();
+        let (_, _, module) = loaded
+            .nodes()
+            .find(|(_, name, _)| *name == "random")
+            .unwrap();
+        assert_eq!(module.serialize_state(), state.state);
+    }
+
+    #[test]
+    fn test_fx_chain_def_roundtrip() {
+        let registry = ModuleRegistry::new();
+        let mut chain = FxChain::new(44100.0)
+            .with_effect(Distortion::new(44100.0))
+            .with_effect(Reverb::new(44100.0));
+        chain.reorder(1, 0).unwrap();
+        chain.set_amount(0, 0.3).unwrap();
+        chain.set_enabled(1, false).unwrap();
+
+        let json = chain.to_def().to_json().unwrap();
+        let def = FxChainDef::from_json(&json).unwrap();
+        assert_eq!(def.effects[0].module_type, "reverb");
+        assert_eq!(def.effects[1].module_type, "distortion");
+
+        let loaded = FxChain::from_def(&def, &registry, 44100.0).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.effect(0).unwrap().type_id(), "reverb");
+        assert_eq!(loaded.amount(0), Some(0.3));
+        assert_eq!(loaded.is_enabled(1), Some(false));
+
+        let mut bad = def.clone();
+        bad.effects[0].module_type = "no_such_effect".into();
+        assert!(FxChain::from_def(&bad, &registry, 44100.0).is_err());
+    }
 }
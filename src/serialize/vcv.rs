@@ -0,0 +1,662 @@
+//! VCV Rack Patch Import and Export
+//!
+//! Converts between `PatchDef` and VCV Rack's JSON patch format for the
+//! modules that have a direct equivalent in VCV's Fundamental and Core
+//! plugins. The mapping is an explicit table, [`MODULE_MAP`]; anything it
+//! does not cover is skipped and listed in a [`VcvImportReport`].
+//!
+//! Rack 2 saves `.vcv` files as compressed archives holding a `patch.json`.
+//! [`import_vcv`] reads that JSON (extract it first), and [`export_vcv`]
+//! writes a plain JSON patch, which Rack 2 opens as a legacy `.vcv` file.
+//!
+//! Knob values travel through `PatchDef::parameters` as 0-1 knob positions
+//! keyed `"node.param"`, where `param` names the input the knob sets.
+//! Module positions convert between our coordinates and Rack's grid of
+//! 15 px columns (HP) and 380 px rows.
+
+use super::{CableDef, ModuleDef, PatchDef};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Width of one Rack column (1 HP) in our position units
+const HP_WIDTH: f32 = 15.0;
+/// Height of one Rack row in our position units
+const ROW_HEIGHT: f32 = 380.0;
+/// Rack version written into exported patches
+const RACK_VERSION: &str = "2.0.0";
+
+/// A VCV knob mapped onto one of our parameters
+#[derive(Debug, Clone, Copy)]
+pub struct VcvParam {
+    /// VCV param id
+    pub id: usize,
+    /// Our parameter (input) name
+    pub name: &'static str,
+    /// VCV knob range
+    pub min: f64,
+    pub max: f64,
+}
+
+/// How one of our module types maps onto a VCV module
+#[derive(Debug, Clone, Copy)]
+pub struct VcvModuleMap {
+    /// Our module type id
+    pub type_id: &'static str,
+    /// VCV plugin slug
+    pub plugin: &'static str,
+    /// VCV model slug
+    pub model: &'static str,
+    /// Panel width in HP, for laying out unpositioned modules
+    pub hp: i64,
+    /// VCV input id and our input port name
+    pub inputs: &'static [(usize, &'static str)],
+    /// VCV output id and our output port name
+    pub outputs: &'static [(usize, &'static str)],
+    /// Knobs carried across
+    pub params: &'static [VcvParam],
+}
+
+impl VcvModuleMap {
+    fn input_id(&self, port: &str) -> Option<usize> {
+        self.inputs
+            .iter()
+            .find(|(_, p)| *p == port)
+            .map(|(id, _)| *id)
+    }
+
+    fn output_id(&self, port: &str) -> Option<usize> {
+        self.outputs
+            .iter()
+            .find(|(_, p)| *p == port)
+            .map(|(id, _)| *id)
+    }
+
+    fn input_name(&self, id: usize) -> Option<&'static str> {
+        self.inputs.iter().find(|(i, _)| *i == id).map(|(_, p)| *p)
+    }
+
+    fn output_name(&self, id: usize) -> Option<&'static str> {
+        self.outputs.iter().find(|(i, _)| *i == id).map(|(_, p)| *p)
+    }
+}
+
+/// Modules with a VCV equivalent (Fundamental and Core 2.x)
+pub const MODULE_MAP: &[VcvModuleMap] = &[
+    VcvModuleMap {
+        type_id: "vco",
+        plugin: "Fundamental",
+        model: "VCO",
+        hp: 10,
+        inputs: &[(0, "voct"), (1, "fm"), (2, "sync"), (3, "pw")],
+        outputs: &[(0, "sin"), (1, "tri"), (2, "saw"), (3, "sqr")],
+        params: &[
+            VcvParam {
+                id: 2,
+                name: "voct",
+                min: -54.0,
+                max: 54.0,
+            },
+            VcvParam {
+                id: 4,
+                name: "fm",
+                min: -1.0,
+                max: 1.0,
+            },
+            VcvParam {
+                id: 5,
+                name: "pw",
+                min: 0.01,
+                max: 0.99,
+            },
+        ],
+    },
+    VcvModuleMap {
+        type_id: "svf",
+        plugin: "Fundamental",
+        model: "VCF",
+        hp: 8,
+        inputs: &[(0, "cutoff"), (1, "res"), (2, "drive"), (3, "in")],
+        outputs: &[(0, "lp"), (1, "hp")],
+        params: &[
+            VcvParam {
+                id: 0,
+                name: "cutoff",
+                min: 0.0,
+                max: 1.0,
+            },
+            VcvParam {
+                id: 2,
+                name: "res",
+                min: 0.0,
+                max: 1.0,
+            },
+            VcvParam {
+                id: 4,
+                name: "drive",
+                min: 0.0,
+                max: 1.0,
+            },
+        ],
+    },
+    VcvModuleMap {
+        type_id: "adsr",
+        plugin: "Fundamental",
+        model: "ADSR",
+        hp: 9,
+        inputs: &[
+            (0, "attack"),
+            (1, "decay"),
+            (2, "sustain"),
+            (3, "release"),
+            (4, "gate"),
+            (5, "retrig"),
+        ],
+        outputs: &[(0, "env")],
+        params: &[
+            VcvParam {
+                id: 0,
+                name: "attack",
+                min: 0.0,
+                max: 1.0,
+            },
+            VcvParam {
+                id: 1,
+                name: "decay",
+                min: 0.0,
+                max: 1.0,
+            },
+            VcvParam {
+                id: 2,
+                name: "sustain",
+                min: 0.0,
+                max: 1.0,
+            },
+            VcvParam {
+                id: 3,
+                name: "release",
+                min: 0.0,
+                max: 1.0,
+            },
+        ],
+    },
+    VcvModuleMap {
+        type_id: "vca",
+        plugin: "Fundamental",
+        model: "VCA-1",
+        hp: 3,
+        inputs: &[(0, "cv"), (1, "in")],
+        outputs: &[(0, "out")],
+        params: &[VcvParam {
+            id: 0,
+            name: "cv",
+            min: 0.0,
+            max: 1.0,
+        }],
+    },
+    VcvModuleMap {
+        type_id: "lfo",
+        plugin: "Fundamental",
+        model: "LFO",
+        hp: 9,
+        inputs: &[(2, "reset")],
+        outputs: &[(0, "sin"), (1, "tri"), (2, "saw"), (3, "sqr")],
+        params: &[VcvParam {
+            id: 2,
+            name: "rate",
+            min: -8.0,
+            max: 10.0,
+        }],
+    },
+    VcvModuleMap {
+        type_id: "noise",
+        plugin: "Fundamental",
+        model: "Noise",
+        hp: 3,
+        inputs: &[],
+        outputs: &[(0, "white"), (1, "pink")],
+        params: &[],
+    },
+    VcvModuleMap {
+        type_id: "stereo_output",
+        plugin: "Core",
+        model: "AudioInterface2",
+        hp: 4,
+        inputs: &[(0, "left"), (1, "right")],
+        outputs: &[],
+        params: &[],
+    },
+];
+
+/// Mapping for one of our module types
+pub fn map_for_type(type_id: &str) -> Option<&'static VcvModuleMap> {
+    MODULE_MAP.iter().find(|m| m.type_id == type_id)
+}
+
+/// Mapping for a VCV plugin and model
+pub fn map_for_model(plugin: &str, model: &str) -> Option<&'static VcvModuleMap> {
+    MODULE_MAP
+        .iter()
+        .find(|m| m.plugin == plugin && m.model == model)
+}
+
+/// What a conversion had to leave behind
+///
+/// Every entry is a human-readable description; an empty report means the
+/// conversion was lossless.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VcvImportReport {
+    /// Modules with no equivalent on the other side
+    pub skipped_modules: Vec<String>,
+    /// Cables touching a skipped module or an unmapped port
+    pub dropped_cables: Vec<String>,
+    /// Parameters, cable gains and module state that could not be carried
+    pub dropped_params: Vec<String>,
+}
+
+/// Export drops the same kinds of things as import
+pub type VcvExportReport = VcvImportReport;
+
+impl VcvImportReport {
+    /// Whether nothing was dropped
+    pub fn is_empty(&self) -> bool {
+        self.skipped_modules.is_empty()
+            && self.dropped_cables.is_empty()
+            && self.dropped_params.is_empty()
+    }
+
+    /// All entries as one warning list
+    pub fn warnings(&self) -> Vec<String> {
+        let modules = self
+            .skipped_modules
+            .iter()
+            .map(|m| format!("skipped module {}", m));
+        let cables = self
+            .dropped_cables
+            .iter()
+            .map(|c| format!("dropped cable {}", c));
+        let params = self
+            .dropped_params
+            .iter()
+            .map(|p| format!("dropped param {}", p));
+        modules.chain(cables).chain(params).collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VcvPatch {
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    modules: Vec<VcvModule>,
+    #[serde(default, alias = "wires")]
+    cables: Vec<VcvCable>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VcvModule {
+    #[serde(default)]
+    id: Option<i64>,
+    plugin: String,
+    model: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    params: Vec<VcvParamValue>,
+    #[serde(default)]
+    pos: (f64, f64),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VcvParamValue {
+    #[serde(alias = "paramId")]
+    id: usize,
+    value: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VcvCable {
+    #[serde(default)]
+    id: Option<i64>,
+    output_module_id: i64,
+    output_id: usize,
+    input_module_id: i64,
+    input_id: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+}
+
+/// Convert a patch definition to a VCV Rack patch (JSON)
+///
+/// Modules without a VCV equivalent are skipped, along with their cables.
+/// Cables on ports VCV lacks, cable gains and offsets, module state and
+/// parameters without a VCV knob are listed in the report.
+pub fn export_vcv(def: &PatchDef) -> Result<(String, VcvExportReport), serde_json::Error> {
+    let mut report = VcvExportReport::default();
+    let mut modules = Vec::new();
+    // Node name, VCV module id and mapping of each exported module
+    let mut exported: Vec<(&str, i64, &VcvModuleMap)> = Vec::new();
+    let mut next_hp = 0;
+
+    for (index, module) in def.modules.iter().enumerate() {
+        let Some(map) = map_for_type(&module.module_type) else {
+            report
+                .skipped_modules
+                .push(format!("{} ({})", module.name, module.module_type));
+            continue;
+        };
+        let id = index as i64 + 1;
+
+        let pos = match module.position {
+            Some((x, y)) => (
+                libm::roundf(x / HP_WIDTH) as f64,
+                libm::roundf(y / ROW_HEIGHT) as f64,
+            ),
+            None => (next_hp as f64, 0.0),
+        };
+        next_hp = next_hp.max(pos.0 as i64 + map.hp);
+
+        let params = map
+            .params
+            .iter()
+            .filter_map(|param| {
+                let key = format!("{}.{}", module.name, param.name);
+                let knob = def.parameters.get(&key)?.clamp(0.0, 1.0);
+                Some(VcvParamValue {
+                    id: param.id,
+                    value: param.min + knob * (param.max - param.min),
+                })
+            })
+            .collect();
+
+        if module.state.is_some() {
+            report.dropped_params.push(format!("{}.state", module.name));
+        }
+        if module.args.is_some() {
+            report.dropped_params.push(format!("{}.args", module.name));
+        }
+
+        modules.push(VcvModule {
+            id: Some(id),
+            plugin: map.plugin.to_string(),
+            model: map.model.to_string(),
+            version: RACK_VERSION.to_string(),
+            params,
+            pos,
+        });
+        exported.push((module.name.as_str(), id, map));
+    }
+
+    // Parameters with no VCV knob, in a stable order
+    let mut keys: Vec<&String> = def.parameters.keys().collect();
+    keys.sort();
+    for key in keys {
+        let carried = key.split_once('.').is_some_and(|(node, param)| {
+            exported
+                .iter()
+                .any(|(name, _, map)| *name == node && map.params.iter().any(|p| p.name == param))
+        });
+        if !carried {
+            report.dropped_params.push(key.clone());
+        }
+    }
+
+    let find = |node: &str| exported.iter().find(|(name, _, _)| *name == node);
+    let mut cables = Vec::new();
+    for cable in &def.cables {
+        let ends = cable.from.split_once('.').zip(cable.to.split_once('.'));
+        let mapped = ends.and_then(|((from_node, from_port), (to_node, to_port))| {
+            let (_, from_id, from_map) = find(from_node)?;
+            let (_, to_id, to_map) = find(to_node)?;
+            Some((
+                *from_id,
+                from_map.output_id(from_port)?,
+                *to_id,
+                to_map.input_id(to_port)?,
+            ))
+        });
+        let Some((output_module_id, output_id, input_module_id, input_id)) = mapped else {
+            report
+                .dropped_cables
+                .push(format!("{} -> {}", cable.from, cable.to));
+            continue;
+        };
+        if cable.attenuation.is_some_and(|a| a != 1.0) || cable.offset.is_some_and(|o| o != 0.0) {
+            report
+                .dropped_params
+                .push(format!("{} -> {} gain/offset", cable.from, cable.to));
+        }
+        cables.push(VcvCable {
+            id: Some(cables.len() as i64 + 1),
+            output_module_id,
+            output_id,
+            input_module_id,
+            input_id,
+            color: None,
+        });
+    }
+
+    let patch = VcvPatch {
+        version: RACK_VERSION.to_string(),
+        modules,
+        cables,
+    };
+    Ok((serde_json::to_string_pretty(&patch)?, report))
+}
+
+/// Convert a VCV Rack patch (JSON) to a patch definition
+///
+/// Modules are named after their type id (`vco`, `vco_2`, ...). Unmapped
+/// modules, cables on unmapped ports and knobs without an equivalent are
+/// skipped and listed in the report.
+pub fn import_vcv(json: &str) -> Result<(PatchDef, VcvImportReport), serde_json::Error> {
+    let patch: VcvPatch = serde_json::from_str(json)?;
+    let mut report = VcvImportReport::default();
+    let mut def = PatchDef::new("VCV Rack import");
+    // VCV module id, our node name (if imported) and mapping
+    let mut imported: Vec<(i64, Option<(String, &VcvModuleMap)>)> = Vec::new();
+
+    for (index, module) in patch.modules.iter().enumerate() {
+        // Rack 0.6 patches refer to modules by position
+        let id = module.id.unwrap_or(index as i64);
+        let Some(map) = map_for_model(&module.plugin, &module.model) else {
+            report
+                .skipped_modules
+                .push(format!("{}/{} (id {})", module.plugin, module.model, id));
+            imported.push((id, None));
+            continue;
+        };
+
+        let name = unique_name(&def, map.type_id);
+        for value in &module.params {
+            match map.params.iter().find(|p| p.id == value.id) {
+                Some(param) => {
+                    let knob = (value.value - param.min) / (param.max - param.min);
+                    def.parameters
+                        .insert(format!("{}.{}", name, param.name), knob.clamp(0.0, 1.0));
+                }
+                None => report
+                    .dropped_params
+                    .push(format!("{}.param{} = {}", name, value.id, value.value)),
+            }
+        }
+
+        def.modules
+            .push(ModuleDef::new(&name, map.type_id).with_position(
+                module.pos.0 as f32 * HP_WIDTH,
+                module.pos.1 as f32 * ROW_HEIGHT,
+            ));
+        imported.push((id, Some((name, map))));
+    }
+
+    let label = |id: i64| match imported.iter().find(|(i, _)| *i == id) {
+        Some((_, Some((name, _)))) => name.clone(),
+        Some((_, None)) => format!("module {}", id),
+        None => format!("missing module {}", id),
+    };
+    let find = |id: i64| {
+        imported
+            .iter()
+            .find(|(i, _)| *i == id)
+            .and_then(|(_, m)| m.as_ref())
+    };
+    for cable in &patch.cables {
+        let from = find(cable.output_module_id).and_then(|(name, map)| {
+            Some(format!("{}.{}", name, map.output_name(cable.output_id)?))
+        });
+        let to = find(cable.input_module_id)
+            .and_then(|(name, map)| Some(format!("{}.{}", name, map.input_name(cable.input_id)?)));
+        match from.zip(to) {
+            Some((from, to)) => def.cables.push(CableDef::new(from, to)),
+            None => report.dropped_cables.push(format!(
+                "{} output {} -> {} input {}",
+                label(cable.output_module_id),
+                cable.output_id,
+                label(cable.input_module_id),
+                cable.input_id
+            )),
+        }
+    }
+
+    Ok((def, report))
+}
+
+/// `base`, or `base_2`, `base_3`, ... if taken
+fn unique_name(def: &PatchDef, base: &str) -> String {
+    let taken = |name: &str| def.modules.iter().any(|m| m.name == name);
+    if !taken(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}_{}", base, n))
+        .find(|name| !taken(name))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Patch;
+    use crate::modules::{Mixer, StereoOutput, Svf, Vco};
+    use crate::serialize::ModuleRegistry;
+
+    #[test]
+    fn test_vcv_round_trip_of_mappable_subset() {
+        let mut patch = Patch::new(44100.0);
+        let vco = patch.add("vco", Vco::new(44100.0));
+        let svf = patch.add("svf", Svf::new(44100.0));
+        let mixer = patch.add("mixer", Mixer::new(2));
+        let out = patch.add("stereo_output", StereoOutput::new());
+        patch.connect(vco.out("saw"), svf.in_("in")).unwrap();
+        patch.connect(vco.out("sqr"), mixer.in_("ch0")).unwrap();
+        patch.connect(svf.out("bp"), out.in_("right")).unwrap();
+        patch.connect(svf.out("lp"), out.in_("left")).unwrap();
+        patch.set_position(vco.id(), (0.0, 0.0));
+        patch.set_position(svf.id(), (150.0, 0.0));
+        patch.set_position(out.id(), (300.0, 380.0));
+
+        let mut def = patch.to_def("Subtractive");
+        def.parameters.insert("svf.cutoff".into(), 0.7);
+        def.parameters.insert("svf.res".into(), 0.25);
+        def.parameters.insert("vco.voct".into(), 0.5);
+        def.parameters.insert("svf.keytrack_amt".into(), 1.0);
+
+        let (json, exported) = export_vcv(&def).unwrap();
+        assert_eq!(exported.skipped_modules, vec!["mixer (mixer)"]);
+        assert_eq!(
+            exported.dropped_cables,
+            vec!["vco.sqr -> mixer.ch0", "svf.bp -> stereo_output.right"]
+        );
+        assert!(exported
+            .dropped_params
+            .contains(&"svf.keytrack_amt".to_string()));
+
+        // The exported JSON is a Rack patch
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let vcf = &value["modules"][1];
+        assert_eq!(vcf["plugin"], "Fundamental");
+        assert_eq!(vcf["model"], "VCF");
+        assert_eq!(vcf["pos"], serde_json::json!([10.0, 0.0]));
+        assert_eq!(value["cables"].as_array().unwrap().len(), 2);
+
+        let (imported, report) = import_vcv(&json).unwrap();
+        assert!(report.is_empty(), "{:?}", report.warnings());
+        let modules: Vec<(&str, &str)> = imported
+            .modules
+            .iter()
+            .map(|m| (m.name.as_str(), m.module_type.as_str()))
+            .collect();
+        assert_eq!(
+            modules,
+            vec![
+                ("vco", "vco"),
+                ("svf", "svf"),
+                ("stereo_output", "stereo_output")
+            ]
+        );
+        let positions: Vec<_> = imported.modules.iter().map(|m| m.position).collect();
+        assert_eq!(
+            positions,
+            vec![Some((0.0, 0.0)), Some((150.0, 0.0)), Some((300.0, 380.0))]
+        );
+        let cables: Vec<(&str, &str)> = imported
+            .cables
+            .iter()
+            .map(|c| (c.from.as_str(), c.to.as_str()))
+            .collect();
+        assert_eq!(
+            cables,
+            vec![("vco.saw", "svf.in"), ("svf.lp", "stereo_output.left")]
+        );
+        for (key, value) in [("svf.cutoff", 0.7), ("svf.res", 0.25), ("vco.voct", 0.5)] {
+            assert!((imported.parameters[key] - value).abs() < 1e-12, "{}", key);
+        }
+        assert_eq!(imported.parameters.len(), 3);
+
+        // The import builds and plays
+        let mut rebuilt = Patch::from_def(&imported, &ModuleRegistry::new(), 44100.0).unwrap();
+        rebuilt.compile().unwrap();
+        rebuilt.tick();
+    }
+
+    #[test]
+    fn test_vcv_import_reports_unmapped_modules_cables_and_params() {
+        let json = r#"{
+            "version": "2.4.1",
+            "modules": [
+                {"id": 7, "plugin": "Fundamental", "model": "VCO",
+                 "params": [{"id": 2, "value": 12.0}, {"id": 7, "value": 1.0}],
+                 "pos": [4, 1]},
+                {"id": 9, "plugin": "Befaco", "model": "EvenVCO", "params": [], "pos": [20, 1]},
+                {"id": 11, "plugin": "Fundamental", "model": "VCO", "params": [], "pos": [30, 1]}
+            ],
+            "cables": [
+                {"id": 1, "outputModuleId": 9, "outputId": 0, "inputModuleId": 7, "inputId": 0},
+                {"id": 2, "outputModuleId": 7, "outputId": 2, "inputModuleId": 11, "inputId": 1},
+                {"id": 3, "outputModuleId": 7, "outputId": 0, "inputModuleId": 11, "inputId": 8}
+            ]
+        }"#;
+
+        let (def, report) = import_vcv(json).unwrap();
+        assert_eq!(report.skipped_modules, vec!["Befaco/EvenVCO (id 9)"]);
+        assert_eq!(
+            report.dropped_cables,
+            vec![
+                "module 9 output 0 -> vco input 0",
+                "vco output 0 -> vco_2 input 8"
+            ]
+        );
+        assert_eq!(report.dropped_params, vec!["vco.param7 = 1"]);
+        assert_eq!(report.warnings().len(), 4);
+
+        assert_eq!(def.modules[1].name, "vco_2");
+        assert_eq!(def.modules[0].position, Some((60.0, 380.0)));
+        assert_eq!(def.cables.len(), 1);
+        assert_eq!(def.cables[0].from, "vco.saw");
+        assert_eq!(def.cables[0].to, "vco_2.fm");
+        // +12 semitones sits at 61% of the VCO's +/-54 range
+        assert!((def.parameters["vco.voct"] - 66.0 / 108.0).abs() < 1e-12);
+    }
+}
@@ -547,8 +547,10 @@ impl RingBuffer {
 
 /// Processing context for block-oriented operations
 pub struct ProcessContext {
-    /// Sample rate
+    /// Sample rate of the output (use for tempo and absolute time)
     pub sample_rate: f64,
+    /// Internal oversampling factor (1 when not oversampling)
+    pub oversampling: usize,
     /// Block size
     pub block_size: usize,
     /// Current sample position (absolute)
@@ -564,6 +566,7 @@ impl ProcessContext {
     pub fn new(sample_rate: f64, block_size: usize) -> Self {
         Self {
             sample_rate,
+            oversampling: 1,
             block_size,
             sample_position: 0,
             tempo: None,
@@ -571,6 +574,11 @@ impl ProcessContext {
         }
     }
 
+    /// Sample rate DSP runs at internally (output rate times oversampling)
+    pub fn internal_sample_rate(&self) -> f64 {
+        self.sample_rate * self.oversampling as f64
+    }
+
     /// Get the current time in seconds
     pub fn time_seconds(&self) -> f64 {
         self.sample_position as f64 / self.sample_rate
@@ -0,0 +1,278 @@
+//! Musical Transport
+//!
+//! A deterministic transport (tempo, play state, and song position) that a
+//! [`Patch`](crate::graph::Patch) advances once per sample. Modules that need
+//! musical position (tempo-synced LFOs, automation players) read the shared
+//! transport instead of inferring time from their own sample counters, which
+//! makes renders and integration tests fully reproducible.
+
+use crate::port::{RuntimeReader, RuntimeWriter};
+use alloc::string::String;
+use libm::Libm;
+use serde::{Deserialize, Serialize};
+
+/// Tempo, play state, and position shared by all modules in a patch
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Transport {
+    bpm: f64,
+    playing: bool,
+    sample_rate: f64,
+    position_samples: u64,
+    /// Beat position at `anchor_samples` (re-anchored on tempo changes and jumps)
+    anchor_beats: f64,
+    anchor_samples: u64,
+    /// Loop region in beats (start, end)
+    loop_beats: Option<(f64, f64)>,
+}
+
+impl Transport {
+    /// Create a stopped transport at position zero
+    pub fn new(bpm: f64, sample_rate: f64) -> Self {
+        Self {
+            bpm: bpm.max(1.0),
+            playing: false,
+            sample_rate,
+            position_samples: 0,
+            anchor_beats: 0.0,
+            anchor_samples: 0,
+            loop_beats: None,
+        }
+    }
+
+    /// Start the transport
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    /// Stop the transport (position is kept)
+    pub fn stop(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn bpm(&self) -> f64 {
+        self.bpm
+    }
+
+    /// Change tempo; the current beat position is preserved
+    pub fn set_bpm(&mut self, bpm: f64) {
+        self.reanchor();
+        self.bpm = bpm.max(1.0);
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.reanchor();
+        self.sample_rate = sample_rate;
+    }
+
+    /// Number of samples in one beat at the current tempo
+    pub fn samples_per_beat(&self) -> f64 {
+        self.sample_rate * 60.0 / self.bpm
+    }
+
+    /// Position in samples since the start (or since the last loop wrap)
+    pub fn position_samples(&self) -> u64 {
+        self.position_samples
+    }
+
+    /// Position in beats (quarter notes)
+    pub fn position_beats(&self) -> f64 {
+        let elapsed = self.position_samples.saturating_sub(self.anchor_samples);
+        self.anchor_beats + elapsed as f64 / self.samples_per_beat()
+    }
+
+    /// Jump to a position in beats
+    pub fn set_position_beats(&mut self, beats: f64) {
+        let beats = beats.max(0.0);
+        self.position_samples = Libm::<f64>::round(beats * self.samples_per_beat()) as u64;
+        self.anchor_samples = self.position_samples;
+        self.anchor_beats = beats;
+    }
+
+    /// Jump to a position in samples
+    pub fn set_position_samples(&mut self, samples: u64) {
+        self.position_samples = samples;
+        self.anchor_samples = samples;
+        self.anchor_beats = samples as f64 / self.samples_per_beat();
+    }
+
+    fn reanchor(&mut self) {
+        self.anchor_beats = self.position_beats();
+        self.anchor_samples = self.position_samples;
+    }
+
+    /// Loop the position between two beat positions. Empty regions disable looping.
+    pub fn set_loop_beats(&mut self, start: f64, end: f64) {
+        let start = start.max(0.0);
+        self.loop_beats = if end > start {
+            Some((start, end))
+        } else {
+            None
+        };
+    }
+
+    pub fn clear_loop(&mut self) {
+        self.loop_beats = None;
+    }
+
+    pub fn loop_beats(&self) -> Option<(f64, f64)> {
+        self.loop_beats
+    }
+
+    /// Advance the transport by one sample (no-op while stopped)
+    pub fn advance(&mut self) {
+        if !self.playing {
+            return;
+        }
+
+        self.position_samples += 1;
+
+        if let Some((start, end)) = self.loop_beats {
+            let beats = self.position_beats();
+            // Tolerance absorbs rounding when the loop end falls exactly on a sample
+            if beats >= end - 1e-9 {
+                let wrapped = start + ((beats - start) % (end - start)).max(0.0);
+                self.set_position_beats(if wrapped >= end - 1e-9 {
+                    start
+                } else {
+                    wrapped
+                });
+            }
+        }
+    }
+
+    /// Reset the position to zero (play state and tempo are kept)
+    pub fn rewind(&mut self) {
+        self.set_position_samples(0);
+    }
+
+    /// Write tempo, play state, position, and loop for `Patch::save_runtime_state`
+    pub fn serialize_runtime(&self, state: &mut RuntimeWriter) {
+        state.put_f64(self.bpm);
+        state.put_bool(self.playing);
+        state.put_u64(self.position_samples);
+        state.put_f64(self.anchor_beats);
+        state.put_u64(self.anchor_samples);
+        state.put_bool(self.loop_beats.is_some());
+        let (start, end) = self.loop_beats.unwrap_or((0.0, 0.0));
+        state.put_f64(start);
+        state.put_f64(end);
+    }
+
+    /// Restore state written by `serialize_runtime`
+    pub fn deserialize_runtime(&mut self, state: &mut RuntimeReader<'_>) -> Result<(), String> {
+        self.bpm = state.f64()?;
+        self.playing = state.bool()?;
+        self.position_samples = state.u64()?;
+        self.anchor_beats = state.f64()?;
+        self.anchor_samples = state.u64()?;
+        let looping = state.bool()?;
+        let (start, end) = (state.f64()?, state.f64()?);
+        self.loop_beats = looping.then_some((start, end));
+        Ok(())
+    }
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Self::new(120.0, 44100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transport_stopped_does_not_advance() {
+        let mut transport = Transport::new(120.0, 1000.0);
+        transport.advance();
+        assert_eq!(transport.position_samples(), 0);
+        assert!(!transport.is_playing());
+    }
+
+    #[test]
+    fn test_transport_beat_position() {
+        // 120 BPM at 1kHz = 500 samples per beat
+        let mut transport = Transport::new(120.0, 1000.0);
+        assert!((transport.samples_per_beat() - 500.0).abs() < 1e-9);
+
+        transport.play();
+        for _ in 0..750 {
+            transport.advance();
+        }
+        assert_eq!(transport.position_samples(), 750);
+        assert!((transport.position_beats() - 1.5).abs() < 1e-9);
+
+        transport.stop();
+        transport.advance();
+        assert_eq!(transport.position_samples(), 750);
+    }
+
+    #[test]
+    fn test_transport_loop_wraps() {
+        let mut transport = Transport::new(120.0, 1000.0);
+        transport.set_loop_beats(1.0, 3.0);
+        transport.set_position_beats(2.5);
+        transport.play();
+
+        // Half a beat reaches the loop end and wraps back to the start
+        for _ in 0..250 {
+            transport.advance();
+        }
+        assert!((transport.position_beats() - 1.0).abs() < 1e-6);
+        assert_eq!(transport.position_samples(), 500);
+
+        for _ in 0..500 {
+            transport.advance();
+        }
+        assert!((transport.position_beats() - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_transport_invalid_loop_disabled() {
+        let mut transport = Transport::default();
+        transport.set_loop_beats(4.0, 4.0);
+        assert!(transport.loop_beats().is_none());
+
+        transport.set_loop_beats(0.0, 4.0);
+        assert_eq!(transport.loop_beats(), Some((0.0, 4.0)));
+        transport.clear_loop();
+        assert!(transport.loop_beats().is_none());
+    }
+
+    #[test]
+    fn test_transport_set_position_and_rewind() {
+        let mut transport = Transport::new(60.0, 100.0);
+        transport.set_position_samples(250);
+        assert!((transport.position_beats() - 2.5).abs() < 1e-9);
+
+        transport.rewind();
+        assert_eq!(transport.position_samples(), 0);
+        assert_eq!(transport.position_beats(), 0.0);
+    }
+
+    #[test]
+    fn test_transport_tempo_change_keeps_beat_position() {
+        let mut transport = Transport::new(120.0, 1000.0);
+        transport.play();
+        for _ in 0..500 {
+            transport.advance();
+        }
+        transport.set_bpm(60.0);
+        assert!((transport.position_beats() - 1.0).abs() < 1e-9);
+
+        // 60 BPM at 1kHz = 1000 samples per beat
+        for _ in 0..500 {
+            transport.advance();
+        }
+        assert!((transport.position_beats() - 1.5).abs() < 1e-9);
+    }
+}
@@ -6,7 +6,8 @@
 //! - Scope/analyzer modules for signal monitoring
 
 use crate::graph::{NodeId, Patch};
-use crate::port::{PortSpec, SignalKind};
+use crate::port::ParamId;
+use crate::port::{PortSpec, SignalColors};
 use std::collections::VecDeque;
 
 // =============================================================================
@@ -28,6 +29,11 @@ pub struct DotStyle {
     pub show_port_names: bool,
     /// Whether to color-code by signal type
     pub color_by_signal: bool,
+    /// Cable colors per signal type
+    pub signal_colors: SignalColors,
+    /// Draw modules as records with a port per field, so cables attach to
+    /// the ports they use (inputs on the left, outputs on the right)
+    pub port_records: bool,
     /// Background color
     pub bg_color: String,
     /// Node fill color
@@ -45,6 +51,8 @@ impl Default for DotStyle {
             font_size: 12,
             show_port_names: true,
             color_by_signal: true,
+            signal_colors: SignalColors::default(),
+            port_records: false,
             bg_color: "#1a1a2e".to_string(),
             node_color: "#16213e".to_string(),
             edge_color: "#e94560".to_string(),
@@ -82,6 +90,16 @@ impl DotStyle {
         self.node_shape = shape.into();
         self
     }
+
+    pub fn with_signal_colors(mut self, colors: SignalColors) -> Self {
+        self.signal_colors = colors;
+        self
+    }
+
+    pub fn with_port_records(mut self, enabled: bool) -> Self {
+        self.port_records = enabled;
+        self
+    }
 }
 
 /// DOT/GraphViz exporter for patches
@@ -116,9 +134,16 @@ impl DotExporter {
             node_map.insert(id, name.to_string());
 
             let spec = module.port_spec();
-            let label = Self::create_node_label(name, module.type_id(), spec);
-
-            dot.push_str(&format!("    \"{}\" [label=<{}>];\n", name, label));
+            if style.port_records {
+                let label = Self::create_record_label(name, module.type_id(), spec, style);
+                dot.push_str(&format!(
+                    "    \"{}\" [shape=record, label=\"{}\"];\n",
+                    name, label
+                ));
+            } else {
+                let label = Self::create_node_label(name, module.type_id(), spec);
+                dot.push_str(&format!("    \"{}\" [label=<{}>];\n", name, label));
+            }
         }
 
         dot.push('\n');
@@ -147,7 +172,8 @@ impl DotExporter {
 
             // Color by signal type if enabled
             if style.color_by_signal {
-                if let Some(color) = Self::get_signal_color(patch, cable.from.node, cable.from.port)
+                if let Some(color) =
+                    Self::get_signal_color(patch, cable.from.node, cable.from.port, style)
                 {
                     edge_attrs.push(format!("color=\"{}\"", color));
                 }
@@ -169,10 +195,17 @@ impl DotExporter {
                 format!(" [{}]", edge_attrs.join(", "))
             };
 
-            dot.push_str(&format!(
-                "    \"{}\" -> \"{}\"{};\n",
-                from_name, to_name, attrs
-            ));
+            if style.port_records {
+                dot.push_str(&format!(
+                    "    \"{}\":o{}:e -> \"{}\":i{}:w{};\n",
+                    from_name, cable.from.port, to_name, cable.to.port, attrs
+                ));
+            } else {
+                dot.push_str(&format!(
+                    "    \"{}\" -> \"{}\"{};\n",
+                    from_name, to_name, attrs
+                ));
+            }
         }
 
         dot.push_str("}\n");
@@ -227,6 +260,45 @@ impl DotExporter {
         label
     }
 
+    /// Record label: inputs, then the title, then outputs, side by side
+    fn create_record_label(name: &str, type_id: &str, spec: &PortSpec, style: &DotStyle) -> String {
+        let inputs: Vec<String> = spec
+            .inputs
+            .iter()
+            .map(|p| format!("<i{}> {}", p.id, Self::escape_record(&p.name)))
+            .collect();
+        let outputs: Vec<String> = spec
+            .outputs
+            .iter()
+            .map(|p| format!("<o{}> {}", p.id, Self::escape_record(&p.name)))
+            .collect();
+        let fields = format!(
+            "{{{}}}|{}\\n{}|{{{}}}",
+            inputs.join("|"),
+            Self::escape_record(name),
+            Self::escape_record(type_id),
+            outputs.join("|")
+        );
+        // Records stack their top-level fields along the rank direction, so
+        // left-right graphs need one more level to lay them out in a row
+        if matches!(style.rankdir.as_str(), "LR" | "RL") {
+            format!("{{{}}}", fields)
+        } else {
+            fields
+        }
+    }
+
+    fn escape_record(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            if matches!(c, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
     fn get_port_name(patch: &Patch, node: NodeId, port_id: u32, is_input: bool) -> String {
         for (id, _, module) in patch.nodes() {
             if id == node {
@@ -247,13 +319,18 @@ impl DotExporter {
         format!("port_{}", port_id)
     }
 
-    fn get_signal_color(patch: &Patch, node: NodeId, port_id: u32) -> Option<String> {
+    fn get_signal_color<'a>(
+        patch: &Patch,
+        node: NodeId,
+        port_id: u32,
+        style: &'a DotStyle,
+    ) -> Option<&'a str> {
         for (id, _, module) in patch.nodes() {
             if id == node {
                 let spec = module.port_spec();
                 for p in &spec.outputs {
                     if p.id == port_id {
-                        return Some(Self::signal_kind_color(&p.kind));
+                        return Some(style.signal_colors.get(p.kind));
                     }
                 }
                 break;
@@ -261,18 +338,6 @@ impl DotExporter {
         }
         None
     }
-
-    fn signal_kind_color(kind: &SignalKind) -> String {
-        match kind {
-            SignalKind::Audio => "#e94560".to_string(),         // Red
-            SignalKind::CvBipolar => "#0f3460".to_string(),     // Blue
-            SignalKind::CvUnipolar => "#00b4d8".to_string(),    // Cyan
-            SignalKind::VoltPerOctave => "#90be6d".to_string(), // Green
-            SignalKind::Gate => "#f9c74f".to_string(),          // Yellow
-            SignalKind::Trigger => "#f8961e".to_string(),       // Orange
-            SignalKind::Clock => "#9d4edd".to_string(),         // Purple
-        }
-    }
 }
 
 // =============================================================================
@@ -288,15 +353,31 @@ pub struct AutomationPoint {
     pub value: f64,
 }
 
+/// How an automation track moves between its points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutomationInterpolation {
+    /// Ramp linearly from one point to the next
+    #[default]
+    Linear,
+    /// Hold each point's value until the next point
+    Step,
+}
+
 /// Recorded automation data for a single parameter
 #[derive(Debug, Clone)]
 pub struct AutomationTrack {
     /// Parameter identifier (module_name.param_name)
     pub param_id: String,
-    /// Recorded points
+    /// Recorded points, in time order
     pub points: Vec<AutomationPoint>,
     /// Sample rate used during recording
     pub sample_rate: f64,
+    /// How playback moves between points
+    pub interpolation: AutomationInterpolation,
+    /// Value last written to the patch by playback
+    last_written: Option<f64>,
+    /// Overdub: time of the last point written since the parameter was touched
+    overdub_from: Option<u64>,
 }
 
 impl AutomationTrack {
@@ -305,46 +386,52 @@ impl AutomationTrack {
             param_id: param_id.into(),
             points: Vec::new(),
             sample_rate,
+            interpolation: AutomationInterpolation::Linear,
+            last_written: None,
+            overdub_from: None,
         }
     }
 
+    pub fn with_interpolation(mut self, interpolation: AutomationInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
     /// Add a point to the track
+    ///
+    /// A point earlier than the last one is inserted in time order.
     pub fn record(&mut self, time: u64, value: f64) {
-        self.points.push(AutomationPoint { time, value });
+        let index = self.points.partition_point(|p| p.time <= time);
+        self.points.insert(index, AutomationPoint { time, value });
     }
 
-    /// Get the value at a specific time (linear interpolation)
+    /// Get the value at a specific time, following the track's interpolation
     pub fn value_at(&self, time: u64) -> Option<f64> {
-        if self.points.is_empty() {
-            return None;
-        }
-
-        // Find surrounding points
-        let mut before: Option<&AutomationPoint> = None;
-        let mut after: Option<&AutomationPoint> = None;
-
-        for point in &self.points {
-            if point.time <= time {
-                before = Some(point);
-            }
-            if point.time >= time && after.is_none() {
-                after = Some(point);
-            }
-        }
+        // Index of the first point after `time`
+        let index = self.points.partition_point(|p| p.time <= time);
+        let before = index.checked_sub(1).map(|i| &self.points[i]);
+        let after = self.points.get(index);
 
         match (before, after) {
-            (Some(b), Some(a)) if b.time == a.time => Some(b.value),
-            (Some(b), Some(a)) => {
-                // Linear interpolation
+            (Some(b), Some(a)) if self.interpolation == AutomationInterpolation::Linear => {
                 let t = (time - b.time) as f64 / (a.time - b.time) as f64;
                 Some(b.value + t * (a.value - b.value))
             }
-            (Some(b), None) => Some(b.value),
+            (Some(b), _) => Some(b.value),
             (None, Some(a)) => Some(a.value),
             (None, None) => None,
         }
     }
 
+    /// Overdub a point, replacing what the track held since the last one
+    fn overdub(&mut self, time: u64, value: f64) {
+        let from = self.overdub_from.unwrap_or(time);
+        self.points
+            .retain(|p| p.time != time && !(p.time > from && p.time <= time));
+        self.record(time, value);
+        self.overdub_from = Some(time);
+    }
+
     /// Get duration in samples
     pub fn duration(&self) -> u64 {
         self.points.last().map(|p| p.time).unwrap_or(0)
@@ -398,6 +485,8 @@ pub struct AutomationRecorder {
     record_interval: u64,
     /// Sample counter for interval
     sample_counter: u64,
+    /// Whether playback records parameters moved by hand
+    overdub: bool,
 }
 
 impl AutomationRecorder {
@@ -409,6 +498,7 @@ impl AutomationRecorder {
             recording: false,
             record_interval: 1,
             sample_counter: 0,
+            overdub: false,
         }
     }
 
@@ -430,6 +520,76 @@ impl AutomationRecorder {
         self.recording = false;
     }
 
+    /// Record parameters moved by hand while `play` drives the others
+    ///
+    /// During playback a parameter whose value in the patch no longer
+    /// matches what playback last wrote has been touched. From then until
+    /// overdub is turned off, `play` records the parameter's live value
+    /// into its track at each call, replacing the points it passes over,
+    /// instead of playing it back.
+    pub fn set_overdub(&mut self, overdub: bool) {
+        self.overdub = overdub;
+        for track in &mut self.tracks {
+            track.overdub_from = None;
+        }
+    }
+
+    pub fn is_overdubbing(&self) -> bool {
+        self.overdub
+    }
+
+    /// Play the tracks into a patch at a transport position
+    ///
+    /// Each track's `param_id` names a node and one of its parameters, as
+    /// `"node.param"`, where `param` is a `ParamDef` name or a numeric id.
+    /// Tracks are interpolated at `transport_samples` and written with
+    /// `Patch::set_param`; call this once per tick (or block) for sample
+    /// accurate, repeatable playback. Tracks whose node or parameter does
+    /// not exist are skipped.
+    pub fn play(&mut self, patch: &mut Patch, transport_samples: u64) {
+        for track in &mut self.tracks {
+            let Some((node, param)) = Self::resolve(patch, &track.param_id) else {
+                continue;
+            };
+
+            if self.overdub {
+                let live = patch.get_param(node, param);
+                if track.overdub_from.is_none() {
+                    if let (Some(live), Some(written)) = (live, track.last_written) {
+                        if live != written {
+                            track.overdub_from = Some(transport_samples);
+                        }
+                    }
+                }
+                if track.overdub_from.is_some() {
+                    if let Some(live) = live {
+                        track.overdub(transport_samples, live);
+                        track.last_written = Some(live);
+                    }
+                    continue;
+                }
+            }
+
+            if let Some(value) = track.value_at(transport_samples) {
+                patch.set_param(node, param, value);
+                track.last_written = Some(value);
+            }
+        }
+    }
+
+    /// Node and parameter for a `"node.param"` id
+    fn resolve(patch: &Patch, param_id: &str) -> Option<(NodeId, ParamId)> {
+        let (node_name, param) = param_id.rsplit_once('.')?;
+        let (node, _, module) = patch.nodes().find(|(_, name, _)| *name == node_name)?;
+        let id = module
+            .params()
+            .iter()
+            .find(|p| p.name == param)
+            .map(|p| p.id)
+            .or_else(|| param.parse().ok())?;
+        Some((node, id))
+    }
+
     /// Check if recording
     pub fn is_recording(&self) -> bool {
         self.recording
@@ -480,10 +640,17 @@ impl AutomationRecorder {
         self.tracks.iter().find(|t| t.param_id == param_id)
     }
 
+    /// Get a specific track mutably
+    pub fn get_track_mut(&mut self, param_id: &str) -> Option<&mut AutomationTrack> {
+        self.tracks.iter_mut().find(|t| t.param_id == param_id)
+    }
+
     /// Clear all recorded data
     pub fn clear(&mut self) {
         for track in &mut self.tracks {
             track.points.clear();
+            track.last_written = None;
+            track.overdub_from = None;
         }
         self.current_time = 0;
     }
@@ -540,8 +707,27 @@ pub struct Scope {
     volt_div: f64,
     /// Frozen display buffer
     frozen_buffer: Option<Vec<f64>>,
+    /// Sweep mode for trace capture
+    sweep_mode: SweepMode,
+    /// Samples to wait after a trace before re-arming
+    holdoff: usize,
+    /// Holdoff samples remaining
+    holdoff_remaining: usize,
+    /// Samples waited for a trigger (auto sweep)
+    auto_wait: usize,
+    /// Trace being captured, if a sweep is running
+    capture: Option<Vec<f64>>,
+    /// Last completed trace
+    trace: Option<Vec<f64>>,
+    /// Number of completed traces
+    trace_count: u64,
+    /// Whether a single sweep has completed and awaits re-arming
+    single_done: bool,
 }
 
+/// Number of horizontal divisions in a trace
+pub const SCOPE_DIVISIONS: usize = 10;
+
 /// Scope trigger mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TriggerMode {
@@ -557,6 +743,18 @@ pub enum TriggerMode {
     Single,
 }
 
+/// Scope sweep mode, deciding when a new trace is captured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SweepMode {
+    /// Sweep on trigger, or free-run when no trigger arrives for a full trace
+    #[default]
+    Auto,
+    /// Sweep only on trigger; the last trace is held otherwise
+    Normal,
+    /// Sweep once on trigger, then hold until re-armed
+    Single,
+}
+
 impl Scope {
     pub fn new(buffer_size: usize) -> Self {
         Self {
@@ -570,6 +768,14 @@ impl Scope {
             time_div: buffer_size / 10,
             volt_div: 1.0,
             frozen_buffer: None,
+            sweep_mode: SweepMode::Auto,
+            holdoff: 0,
+            holdoff_remaining: 0,
+            auto_wait: 0,
+            capture: None,
+            trace: None,
+            trace_count: 0,
+            single_done: false,
         }
     }
 
@@ -585,6 +791,51 @@ impl Scope {
 
     pub fn set_time_div(&mut self, samples: usize) {
         self.time_div = samples.max(1);
+        self.capture = None;
+    }
+
+    pub fn time_div(&self) -> usize {
+        self.time_div
+    }
+
+    /// Length of a captured trace: time division times `SCOPE_DIVISIONS`
+    pub fn trace_len(&self) -> usize {
+        self.time_div * SCOPE_DIVISIONS
+    }
+
+    pub fn set_sweep_mode(&mut self, mode: SweepMode) {
+        self.sweep_mode = mode;
+        self.capture = None;
+        self.single_done = false;
+    }
+
+    pub fn sweep_mode(&self) -> SweepMode {
+        self.sweep_mode
+    }
+
+    /// Set the holdoff (samples ignored after each trace before re-arming)
+    pub fn set_holdoff(&mut self, samples: usize) {
+        self.holdoff = samples;
+    }
+
+    pub fn holdoff(&self) -> usize {
+        self.holdoff
+    }
+
+    /// Re-arm a single sweep
+    pub fn arm(&mut self) {
+        self.single_done = false;
+        self.holdoff_remaining = 0;
+    }
+
+    /// Last completed trace, starting at the trigger point
+    pub fn trace(&self) -> Option<&[f64]> {
+        self.trace.as_deref()
+    }
+
+    /// Number of traces completed since reset
+    pub fn trace_count(&self) -> u64 {
+        self.trace_count
     }
 
     pub fn set_volt_div(&mut self, volts: f64) {
@@ -594,27 +845,21 @@ impl Scope {
     /// Process a sample
     pub fn tick(&mut self, sample: f64) {
         // Check for trigger
-        let trigger_detected = match self.trigger_mode {
+        let rising = self.prev_sample < self.trigger_level && sample >= self.trigger_level;
+        let falling = self.prev_sample > self.trigger_level && sample <= self.trigger_level;
+        let edge = match self.trigger_mode {
             TriggerMode::Free => true,
-            TriggerMode::RisingEdge => {
-                self.prev_sample < self.trigger_level && sample >= self.trigger_level
-            }
-            TriggerMode::FallingEdge => {
-                self.prev_sample > self.trigger_level && sample <= self.trigger_level
-            }
-            TriggerMode::AnyEdge => {
-                (self.prev_sample < self.trigger_level && sample >= self.trigger_level)
-                    || (self.prev_sample > self.trigger_level && sample <= self.trigger_level)
-            }
-            TriggerMode::Single => {
-                if self.frozen_buffer.is_some() {
-                    false
-                } else {
-                    self.prev_sample < self.trigger_level && sample >= self.trigger_level
-                }
-            }
+            TriggerMode::RisingEdge | TriggerMode::Single => rising,
+            TriggerMode::FallingEdge => falling,
+            TriggerMode::AnyEdge => rising || falling,
+        };
+        let trigger_detected = match self.trigger_mode {
+            TriggerMode::Single => self.frozen_buffer.is_none() && edge,
+            _ => edge,
         };
 
+        self.sweep(sample, edge);
+
         if trigger_detected && !self.triggered {
             self.triggered = true;
             self.samples_since_trigger = 0;
@@ -640,6 +885,38 @@ impl Scope {
         self.prev_sample = sample;
     }
 
+    /// Advance the timebase: start, extend, or finish a trace
+    fn sweep(&mut self, sample: f64, edge: bool) {
+        let trace_len = self.trace_len();
+
+        if self.capture.is_none() {
+            if self.holdoff_remaining > 0 {
+                self.holdoff_remaining -= 1;
+                return;
+            }
+            if self.single_done {
+                return;
+            }
+            let start = edge || (self.sweep_mode == SweepMode::Auto && self.auto_wait >= trace_len);
+            if !start {
+                self.auto_wait += 1;
+                return;
+            }
+            self.capture = Some(Vec::with_capacity(trace_len));
+        }
+
+        if let Some(capture) = self.capture.as_mut() {
+            capture.push(sample);
+            if capture.len() >= trace_len {
+                self.trace = self.capture.take();
+                self.trace_count += 1;
+                self.holdoff_remaining = self.holdoff;
+                self.auto_wait = 0;
+                self.single_done = self.sweep_mode == SweepMode::Single;
+            }
+        }
+    }
+
     /// Get the display buffer
     pub fn get_buffer(&self) -> &[f64] {
         self.frozen_buffer.as_deref().unwrap_or_default()
@@ -677,37 +954,86 @@ impl Scope {
         self.samples_since_trigger = 0;
         self.prev_sample = 0.0;
         self.frozen_buffer = None;
+        self.holdoff_remaining = 0;
+        self.auto_wait = 0;
+        self.capture = None;
+        self.trace = None;
+        self.trace_count = 0;
+        self.single_done = false;
     }
 }
 
 /// Spectrum analyzer
+///
+/// Hann-windowed FFT over a ring buffer of the most recent `fft_size`
+/// samples, recomputed every `hop` samples (a quarter frame by default).
+/// Magnitudes are scaled so a full-scale (±1) sine reads 0 dB. All buffers
+/// are allocated up front, so `tick` never allocates.
 #[derive(Debug)]
 pub struct SpectrumAnalyzer {
     /// FFT size
     fft_size: usize,
-    /// Sample buffer
+    /// Samples between spectrum updates
+    hop: usize,
+    /// Samples since the last update
+    since_hop: usize,
+    /// Sample ring buffer
     buffer: Vec<f64>,
     /// Current write position
     write_pos: usize,
     /// Sample rate
     sample_rate: f64,
-    /// Latest magnitude spectrum (dB)
+    /// Hann window and the gain that normalizes it
+    window: Vec<f64>,
+    window_gain: f64,
+    /// FFT scratch and twiddles
+    re: Vec<f64>,
+    im: Vec<f64>,
+    cos_table: Vec<f64>,
+    sin_table: Vec<f64>,
+    /// Latest magnitude spectrum (dB), bins 0 to `fft_size / 2`
     spectrum: Vec<f64>,
     /// Smoothing factor (0.0 = no smoothing, 0.99 = heavy smoothing)
     smoothing: f64,
+    /// Spectra computed since creation or reset
+    frames: u64,
 }
 
 impl SpectrumAnalyzer {
+    /// Smallest FFT size
+    pub const MIN_FFT_SIZE: usize = 512;
+    /// Largest FFT size
+    pub const MAX_FFT_SIZE: usize = 8192;
+    /// Level reported for silent bins
+    const FLOOR_DB: f64 = -100.0;
+
+    /// Create an analyzer; `fft_size` is rounded up to a power of two in 512-8192
     pub fn new(fft_size: usize, sample_rate: f64) -> Self {
-        // Ensure power of 2
-        let fft_size = fft_size.next_power_of_two();
+        let fft_size = fft_size
+            .next_power_of_two()
+            .clamp(Self::MIN_FFT_SIZE, Self::MAX_FFT_SIZE);
+        let window: Vec<f64> = (0..fft_size)
+            .map(|i| 0.5 * (1.0 - (std::f64::consts::TAU * i as f64 / fft_size as f64).cos()))
+            .collect();
+        // A sine of amplitude A peaks at A * sum(window) / 2
+        let window_gain = 2.0 / window.iter().sum::<f64>();
+        let (cos_table, sin_table) = crate::modules::fft_twiddles(fft_size);
         Self {
             fft_size,
+            hop: fft_size / 4,
+            since_hop: 0,
             buffer: vec![0.0; fft_size],
             write_pos: 0,
             sample_rate,
-            spectrum: vec![-100.0; fft_size / 2],
+            window,
+            window_gain,
+            re: vec![0.0; fft_size],
+            im: vec![0.0; fft_size],
+            cos_table,
+            sin_table,
+            spectrum: vec![Self::FLOOR_DB; fft_size / 2 + 1],
             smoothing: 0.8,
+            frames: 0,
         }
     }
 
@@ -715,86 +1041,138 @@ impl SpectrumAnalyzer {
         self.smoothing = smoothing.clamp(0.0, 0.99);
     }
 
+    /// Set the samples between updates (clamped to 1 through the FFT size)
+    pub fn set_hop(&mut self, hop: usize) {
+        self.hop = hop.clamp(1, self.fft_size);
+    }
+
+    pub fn hop(&self) -> usize {
+        self.hop
+    }
+
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    /// Number of spectra computed so far
+    pub fn frame_count(&self) -> u64 {
+        self.frames
+    }
+
     /// Process a sample
     pub fn tick(&mut self, sample: f64) {
         self.buffer[self.write_pos] = sample;
         self.write_pos = (self.write_pos + 1) % self.fft_size;
 
-        // When buffer is full, compute spectrum
-        if self.write_pos == 0 {
+        self.since_hop += 1;
+        if self.since_hop >= self.hop {
+            self.since_hop = 0;
             self.compute_spectrum();
         }
     }
 
     fn compute_spectrum(&mut self) {
-        // Simple DFT (not optimized, but works for demonstration)
-        // In production, you'd use a proper FFT library
-        let n = self.fft_size;
-        let half = n / 2;
-
-        for k in 0..half {
-            let mut real = 0.0;
-            let mut imag = 0.0;
-
-            for (i, &sample) in self.buffer.iter().enumerate() {
-                // Apply Hann window
-                let window =
-                    0.5 * (1.0 - (2.0 * std::f64::consts::PI * i as f64 / (n - 1) as f64).cos());
-                let windowed = sample * window;
-
-                let angle = -2.0 * std::f64::consts::PI * k as f64 * i as f64 / n as f64;
-                real += windowed * angle.cos();
-                imag += windowed * angle.sin();
-            }
+        // Oldest sample first, so the window is centered on the frame
+        let (newer, older) = self.buffer.split_at(self.write_pos);
+        for ((re, &sample), &w) in self
+            .re
+            .iter_mut()
+            .zip(older.iter().chain(newer))
+            .zip(&self.window)
+        {
+            *re = sample * w;
+        }
+        self.im.fill(0.0);
+        crate::modules::fft_in_place(
+            &mut self.re,
+            &mut self.im,
+            &self.cos_table,
+            &self.sin_table,
+            false,
+        );
+
+        for (k, level) in self.spectrum.iter_mut().enumerate() {
+            let magnitude = self.re[k].hypot(self.im[k]) * self.window_gain;
+            let db = (20.0 * magnitude.log10()).max(Self::FLOOR_DB);
+            *level = self.smoothing * *level + (1.0 - self.smoothing) * db;
+        }
+        self.frames += 1;
+    }
 
-            let magnitude = (real * real + imag * imag).sqrt() / (n as f64);
-            let db = 20.0 * (magnitude + 1e-10).log10();
+    /// Magnitude of each bin in dB, from DC to Nyquist
+    pub fn magnitudes_db(&self) -> &[f64] {
+        &self.spectrum
+    }
 
-            // Apply smoothing
-            self.spectrum[k] = self.smoothing * self.spectrum[k] + (1.0 - self.smoothing) * db;
-        }
+    /// Center frequency of a bin in Hz
+    pub fn frequency_for_bin(&self, bin: usize) -> f64 {
+        bin as f64 * self.sample_rate / self.fft_size as f64
+    }
+
+    /// Nearest bin to a frequency (clamped to Nyquist)
+    pub fn bin_for_frequency(&self, freq: f64) -> usize {
+        let bin = (freq.max(0.0) * self.fft_size as f64 / self.sample_rate).round() as usize;
+        bin.min(self.spectrum.len() - 1)
     }
 
     /// Get the spectrum as (frequency, magnitude_db) pairs
     pub fn get_spectrum(&self) -> Vec<(f64, f64)> {
-        let freq_resolution = self.sample_rate / self.fft_size as f64;
-
         self.spectrum
             .iter()
             .enumerate()
-            .map(|(i, &db)| (i as f64 * freq_resolution, db))
+            .map(|(i, &db)| (self.frequency_for_bin(i), db))
             .collect()
     }
 
-    /// Get magnitude at a specific frequency
-    pub fn magnitude_at(&self, freq: f64) -> f64 {
-        let bin = (freq * self.fft_size as f64 / self.sample_rate) as usize;
-        if bin < self.spectrum.len() {
-            self.spectrum[bin]
-        } else {
-            -100.0
+    /// Group the spectrum into log-spaced bands for display
+    ///
+    /// Fills `bands` with (center frequency, dB) for equal-ratio bands from
+    /// `min_freq` to `max_freq`. A band reads the loudest bin it covers, or
+    /// the nearest bin when it is narrower than one.
+    pub fn log_bands(&self, min_freq: f64, max_freq: f64, bands: &mut [(f64, f64)]) {
+        let count = bands.len();
+        let min_freq = min_freq.max(self.frequency_for_bin(1) * 0.5);
+        let ratio = (max_freq.max(min_freq) / min_freq).powf(1.0 / count.max(1) as f64);
+        for (i, band) in bands.iter_mut().enumerate() {
+            let low = min_freq * ratio.powi(i as i32);
+            let high = low * ratio;
+            let center = (low * high).sqrt();
+            let first = self.bin_for_frequency(low);
+            let last = self.bin_for_frequency(high).max(first);
+            let db = self.spectrum[first..=last]
+                .iter()
+                .copied()
+                .fold(Self::FLOOR_DB, f64::max);
+            *band = (center, db);
         }
     }
 
-    /// Get peak frequency
-    pub fn peak_frequency(&self) -> f64 {
-        let freq_resolution = self.sample_rate / self.fft_size as f64;
+    /// Get magnitude at a specific frequency
+    pub fn magnitude_at(&self, freq: f64) -> f64 {
+        self.spectrum[self.bin_for_frequency(freq)]
+    }
 
-        let (peak_bin, _) = self
-            .spectrum
+    /// Loudest bin
+    pub fn peak_bin(&self) -> usize {
+        self.spectrum
             .iter()
             .enumerate()
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
-            .unwrap_or((0, &-100.0));
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(bin, _)| bin)
+    }
 
-        peak_bin as f64 * freq_resolution
+    /// Get peak frequency
+    pub fn peak_frequency(&self) -> f64 {
+        self.frequency_for_bin(self.peak_bin())
     }
 
     /// Reset the analyzer
     pub fn reset(&mut self) {
         self.buffer.fill(0.0);
-        self.spectrum.fill(-100.0);
+        self.spectrum.fill(Self::FLOOR_DB);
         self.write_pos = 0;
+        self.since_hop = 0;
+        self.frames = 0;
     }
 }
 
@@ -914,9 +1292,158 @@ impl LevelMeter {
     }
 }
 
+/// Min/max of a CV over one capture interval
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CvSpan {
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Downsampled history of a control signal for plotting
+///
+/// Keeps one `CvSpan` per capture interval (1 ms by default) in a ring
+/// buffer covering a few seconds, so envelopes and LFOs can be drawn
+/// without storing every sample. Each span records the extremes of its
+/// interval, so a transient shorter than the interval still shows up.
+#[derive(Debug)]
+pub struct CvMonitor {
+    sample_rate: f64,
+    /// Capture interval in milliseconds
+    interval_ms: f64,
+    /// Capture interval in samples
+    interval_samples: usize,
+    /// History length in seconds
+    history_seconds: f64,
+    /// Completed spans, oldest first
+    history: VecDeque<CvSpan>,
+    /// Span being accumulated
+    current: Option<CvSpan>,
+    /// Samples in the current span
+    count: usize,
+}
+
+impl CvMonitor {
+    pub fn new(sample_rate: f64) -> Self {
+        let mut monitor = Self {
+            sample_rate,
+            interval_ms: 1.0,
+            interval_samples: 1,
+            history_seconds: 5.0,
+            history: VecDeque::new(),
+            current: None,
+            count: 0,
+        };
+        monitor.configure();
+        monitor
+    }
+
+    /// Builder: set the capture interval in milliseconds
+    pub fn with_interval_ms(mut self, ms: f64) -> Self {
+        self.set_interval_ms(ms);
+        self
+    }
+
+    /// Set the capture interval (clears the history)
+    pub fn set_interval_ms(&mut self, ms: f64) {
+        self.interval_ms = ms.max(0.0);
+        self.configure();
+    }
+
+    pub fn interval_ms(&self) -> f64 {
+        self.interval_ms
+    }
+
+    /// Builder: set how many seconds of history are kept
+    pub fn with_history_seconds(mut self, seconds: f64) -> Self {
+        self.set_history_seconds(seconds);
+        self
+    }
+
+    /// Set the history length (clears the history)
+    pub fn set_history_seconds(&mut self, seconds: f64) {
+        self.history_seconds = seconds.max(0.0);
+        self.configure();
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+        self.configure();
+    }
+
+    /// Capture interval in samples
+    pub fn interval_samples(&self) -> usize {
+        self.interval_samples
+    }
+
+    /// Number of spans the history holds once full
+    pub fn capacity(&self) -> usize {
+        ((self.history_seconds * 1000.0 / self.interval_ms) as usize).max(1)
+    }
+
+    fn configure(&mut self) {
+        self.interval_samples = ((self.interval_ms * self.sample_rate / 1000.0) as usize).max(1);
+        self.interval_ms = self.interval_samples as f64 * 1000.0 / self.sample_rate;
+        self.history = VecDeque::with_capacity(self.capacity());
+        self.current = None;
+        self.count = 0;
+    }
+
+    /// Process a sample
+    pub fn tick(&mut self, sample: f64) {
+        let span = self.current.get_or_insert(CvSpan {
+            min: sample,
+            max: sample,
+        });
+        span.min = span.min.min(sample);
+        span.max = span.max.max(sample);
+        self.count += 1;
+
+        if self.count >= self.interval_samples {
+            if self.history.len() >= self.capacity() {
+                self.history.pop_front();
+            }
+            self.history.extend(self.current.take());
+            self.count = 0;
+        }
+    }
+
+    /// Completed spans, oldest first
+    pub fn history(&self) -> Vec<CvSpan> {
+        self.history.iter().copied().collect()
+    }
+
+    /// Number of completed spans
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    /// Get display data as (seconds before now, min, max), oldest first
+    pub fn get_display_data(&self) -> Vec<(f64, f64, f64)> {
+        let len = self.history.len();
+        let interval = self.interval_ms / 1000.0;
+        self.history
+            .iter()
+            .enumerate()
+            .map(|(i, span)| ((len - i) as f64 * interval, span.min, span.max))
+            .collect()
+    }
+
+    /// Clear the history
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.current = None;
+        self.count = 0;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modules::{Adsr, Offset, StepSequencer, Vco};
 
     // DOT Export tests
 
@@ -934,6 +1461,41 @@ mod tests {
         assert_eq!(style.bg_color, "#ffffff");
     }
 
+    #[test]
+    fn test_dot_export_colors_cables_by_signal_kind() {
+        let mut patch = Patch::new(44100.0);
+        let seq = patch.add("seq", StepSequencer::new(8));
+        let env = patch.add("env", Adsr::new(44100.0));
+        let vco = patch.add("vco", Vco::new(44100.0));
+        patch.connect(seq.out("gate"), env.in_("gate")).unwrap();
+        patch.connect(seq.out("cv"), vco.in_("voct")).unwrap();
+
+        let colors = SignalColors::default();
+        let dot = DotExporter::export_default(&patch);
+        assert!(dot.contains(&format!(
+            "\"seq\" -> \"env\" [label=\"gate→gate\", color=\"{}\"]",
+            colors.gate
+        )));
+        assert!(dot.contains(&format!("color=\"{}\"", colors.volt_per_octave)));
+
+        // Custom colors and records with a field per port
+        let style = DotStyle::default()
+            .with_signal_colors(SignalColors {
+                gate: "#123456".into(),
+                ..SignalColors::default()
+            })
+            .with_port_records(true);
+        let dot = DotExporter::export(&patch, &style);
+        assert!(
+            dot.contains("\"seq\":o11:e -> \"env\":i0:w [label=\"gate→gate\", color=\"#123456\"]")
+        );
+        assert!(dot.contains("\"seq\" [shape=record, label=\"{{<i0> clock|<i1> reset|<i2> length}|seq\\nstep_sequencer|{<o10> cv|<o11> gate|<o12> trig}}\"]"));
+
+        // Top-to-bottom graphs need no extra nesting
+        let dot = DotExporter::export(&patch, &style.with_rankdir("TB"));
+        assert!(dot.contains("label=\"{<i0> clock|<i1> reset|<i2> length}|seq"));
+    }
+
     #[test]
     fn test_dot_style_minimal() {
         let style = DotStyle::minimal();
@@ -994,6 +1556,57 @@ mod tests {
         assert!(track.points.len() < original_len);
     }
 
+    #[test]
+    fn test_automation_playback_drives_params() {
+        let mut patch = Patch::new(44100.0);
+        let offset = patch.add("offset", Offset::new(0.0)).id();
+
+        let mut recorder = AutomationRecorder::new(44100.0);
+        recorder.add_track("offset.0");
+        let track = recorder.get_track_mut("offset.0").unwrap();
+        track.record(0, 0.0);
+        track.record(1000, 1.0);
+        track.record(2000, 0.5);
+
+        recorder.play(&mut patch, 500);
+        assert_eq!(patch.get_param(offset, 0), Some(0.5));
+        recorder.play(&mut patch, 1500);
+        assert_eq!(patch.get_param(offset, 0), Some(0.75));
+        recorder.play(&mut patch, 5000);
+        assert_eq!(patch.get_param(offset, 0), Some(0.5));
+
+        recorder.get_track_mut("offset.0").unwrap().interpolation = AutomationInterpolation::Step;
+        recorder.play(&mut patch, 1500);
+        assert_eq!(patch.get_param(offset, 0), Some(1.0));
+    }
+
+    #[test]
+    fn test_automation_overdub_records_touched_params() {
+        let mut patch = Patch::new(44100.0);
+        let offset = patch.add("offset", Offset::new(0.0)).id();
+
+        let mut recorder = AutomationRecorder::new(44100.0);
+        recorder.add_track("offset.0");
+        let track = recorder.get_track_mut("offset.0").unwrap();
+        track.record(0, 0.0);
+        track.record(1000, 1.0);
+        recorder.set_overdub(true);
+
+        recorder.play(&mut patch, 0);
+        patch.set_param(offset, 0, 0.25);
+        for t in [100, 200, 300] {
+            recorder.play(&mut patch, t);
+        }
+        // Touched: playback no longer overwrites the hand-set value
+        assert_eq!(patch.get_param(offset, 0), Some(0.25));
+        recorder.set_overdub(false);
+
+        recorder.play(&mut patch, 200);
+        assert_eq!(patch.get_param(offset, 0), Some(0.25));
+        recorder.play(&mut patch, 650);
+        assert_eq!(patch.get_param(offset, 0), Some(0.625));
+    }
+
     // Scope tests
 
     #[test]
@@ -1030,8 +1643,169 @@ mod tests {
         assert!(!data.is_empty());
     }
 
+    #[test]
+    fn test_scope_traces_phase_aligned() {
+        let mut scope = Scope::new(100);
+        scope.set_trigger_mode(TriggerMode::RisingEdge);
+        scope.set_sweep_mode(SweepMode::Normal);
+        scope.set_time_div(8);
+        scope.set_holdoff(30);
+
+        // Period of 37 samples: traces of 80 samples span two cycles
+        let period = 37.0;
+        let mut traces = Vec::new();
+        let mut count = 0;
+        for i in 0..5000 {
+            scope.tick((2.0 * std::f64::consts::PI * (i as f64 + 0.5) / period).sin());
+            if scope.trace_count() > count {
+                count = scope.trace_count();
+                traces.push(scope.trace().unwrap().to_vec());
+            }
+        }
+
+        assert!(traces.len() > 10);
+        for trace in &traces {
+            assert_eq!(trace.len(), scope.trace_len());
+            for (a, b) in trace.iter().zip(&traces[0]) {
+                assert!((a - b).abs() < 1e-9, "traces are not phase-aligned");
+            }
+        }
+    }
+
+    #[test]
+    fn test_scope_normal_sweep_holds_last_trace() {
+        let mut scope = Scope::new(100);
+        scope.set_trigger_mode(TriggerMode::RisingEdge);
+        scope.set_trigger_level(0.5);
+        scope.set_sweep_mode(SweepMode::Normal);
+
+        for i in 0..300 {
+            scope.tick(if i % 50 < 25 { 1.0 } else { 0.0 });
+        }
+        let count = scope.trace_count();
+        let held = scope.trace().unwrap().to_vec();
+        assert!(count > 0);
+
+        // Below the trigger level: no new trace, the last one is held
+        for _ in 0..1000 {
+            scope.tick(0.2);
+        }
+        assert_eq!(scope.trace_count(), count);
+        assert_eq!(scope.trace().unwrap(), held.as_slice());
+
+        // Auto sweep free-runs without a trigger
+        scope.set_sweep_mode(SweepMode::Auto);
+        for _ in 0..1000 {
+            scope.tick(0.2);
+        }
+        assert!(scope.trace_count() > count);
+        assert!(scope.trace().unwrap().iter().all(|&v| v == 0.2));
+    }
+
+    // CV monitor tests
+
+    #[test]
+    fn test_cv_monitor_tracks_lfo_envelope() {
+        let sr = 1000.0;
+        let mut monitor = CvMonitor::new(sr).with_interval_ms(100.0);
+        assert_eq!(monitor.interval_samples(), 100);
+
+        // 20 Hz LFO whose amplitude ramps from 0 to 5V over 4 seconds
+        let amplitude = |t: f64| 5.0 * t / 4.0;
+        for n in 0..4000 {
+            let t = n as f64 / sr;
+            monitor.tick(amplitude(t) * (2.0 * std::f64::consts::PI * 20.0 * t).sin());
+        }
+
+        let history = monitor.history();
+        assert_eq!(history.len(), 40);
+        for (i, span) in history.iter().enumerate() {
+            // Peaks late in each interval set its extremes
+            let envelope = amplitude((i + 1) as f64 * 0.1);
+            assert!(
+                (span.max - envelope).abs() < 0.15,
+                "span {i} max {}",
+                span.max
+            );
+            assert!(
+                (span.min + envelope).abs() < 0.15,
+                "span {i} min {}",
+                span.min
+            );
+        }
+    }
+
+    #[test]
+    fn test_cv_monitor_keeps_transients_and_history_length() {
+        let mut monitor = CvMonitor::new(1000.0)
+            .with_interval_ms(50.0)
+            .with_history_seconds(1.0);
+        assert_eq!(monitor.capacity(), 20);
+
+        // A one-sample spike is kept in its span's maximum
+        for n in 0..100 {
+            monitor.tick(if n == 23 { 8.0 } else { 1.0 });
+        }
+        let history = monitor.history();
+        assert_eq!(history[0], CvSpan { min: 1.0, max: 8.0 });
+        assert_eq!(history[1], CvSpan { min: 1.0, max: 1.0 });
+
+        // The ring buffer drops the oldest spans once full
+        for _ in 0..5000 {
+            monitor.tick(0.0);
+        }
+        assert_eq!(monitor.len(), 20);
+        assert!(monitor.history().iter().all(|span| span.max == 0.0));
+        assert!((monitor.get_display_data()[0].0 - 1.0).abs() < 1e-12);
+    }
+
     // Spectrum analyzer tests
 
+    #[test]
+    fn test_spectrum_analyzer_finds_1khz_peak_bin() {
+        let mut analyzer = SpectrumAnalyzer::new(2048, 44100.0);
+        analyzer.set_smoothing(0.0);
+        assert_eq!(analyzer.hop(), 512);
+        for i in 0..8192 {
+            let sample = (std::f64::consts::TAU * 1000.0 * i as f64 / 44100.0).sin();
+            analyzer.tick(sample);
+        }
+        assert_eq!(analyzer.frame_count(), 16);
+
+        // 1 kHz sits at bin 46.4 of a 2048-point frame
+        let expected = 1000.0 * 2048.0 / 44100.0;
+        let peak = analyzer.peak_bin();
+        assert!((peak as f64 - expected).abs() <= 1.0, "peak bin {}", peak);
+        assert!((analyzer.frequency_for_bin(peak) - 1000.0).abs() < 44100.0 / 2048.0);
+
+        // One peak near 0 dB; the window keeps distant bins far below it
+        let magnitudes = analyzer.magnitudes_db();
+        assert_eq!(magnitudes.len(), 1025);
+        assert!(magnitudes[peak] > -3.0 && magnitudes[peak] < 0.5);
+        for (bin, &db) in magnitudes.iter().enumerate() {
+            if bin.abs_diff(peak) > 3 {
+                assert!(db < magnitudes[peak] - 40.0, "bin {} at {} dB", bin, db);
+            }
+        }
+
+        // Log bands put the peak in the band around 1 kHz
+        let mut bands = [(0.0, 0.0); 30];
+        analyzer.log_bands(20.0, 20000.0, &mut bands);
+        let (loudest, _) = bands
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.1.total_cmp(&b.1))
+            .unwrap();
+        let (center, db) = bands[loudest];
+        assert!(center > 800.0 && center < 1250.0, "band at {} Hz", center);
+        assert_eq!(db, magnitudes[peak]);
+
+        // Sizes are clamped to a power of two in range
+        assert_eq!(SpectrumAnalyzer::new(100, 44100.0).fft_size(), 512);
+        assert_eq!(SpectrumAnalyzer::new(3000, 44100.0).fft_size(), 4096);
+        assert_eq!(SpectrumAnalyzer::new(1 << 16, 44100.0).fft_size(), 8192);
+    }
+
     #[test]
     fn test_spectrum_analyzer() {
         let mut analyzer = SpectrumAnalyzer::new(256, 44100.0);
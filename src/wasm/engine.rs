@@ -364,6 +364,23 @@ impl QuiverEngine {
         Ok(())
     }
 
+    /// Glide a parameter to a value over `ms` milliseconds, for GUI sliders
+    pub fn set_param_smoothed(
+        &mut self,
+        node_name: &str,
+        param_index: u32,
+        value: f64,
+        ms: f64,
+    ) -> Result<(), JsValue> {
+        let node_id = self
+            .get_node_id_by_name(node_name)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown module: {}", node_name)))?;
+
+        self.patch
+            .set_param_smoothed(node_id, param_index, value, ms);
+        Ok(())
+    }
+
     /// Get a parameter value
     pub fn get_param(&self, node_name: &str, param_index: u32) -> Result<f64, JsValue> {
         let node_id = self